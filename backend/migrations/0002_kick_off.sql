@@ -0,0 +1,40 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+-- Record kick-off times instead of just the date of each game.
+-- Existing games kick off at the configured first kick-off in the competition time zone, which
+-- the server sets on the connection before migrating. A 2pm UTC kick-off if they aren't set.
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM information_schema.columns
+               WHERE table_name = 'games' AND column_name = 'game_date') THEN
+        ALTER TABLE games ADD COLUMN IF NOT EXISTS kick_off TIMESTAMPTZ;
+        UPDATE games
+            SET kick_off = (game_date + COALESCE(NULLIF(current_setting('kelpie.first_kick_off', TRUE), ''), '14:00')::TIME)
+                AT TIME ZONE COALESCE(NULLIF(current_setting('kelpie.timezone', TRUE), ''), 'UTC')
+            WHERE kick_off IS NULL;
+        ALTER TABLE games ALTER COLUMN kick_off SET NOT NULL;
+        ALTER TABLE games DROP COLUMN game_date;
+    END IF;
+END $$;
@@ -0,0 +1,69 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+-- Table to store seasons
+CREATE TABLE IF NOT EXISTS seasons (
+    season_id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL
+);
+
+-- Teams taking part in each season
+CREATE TABLE IF NOT EXISTS season_teams (
+    season_id INT NOT NULL REFERENCES seasons(season_id) ON DELETE CASCADE,
+    team_id   INT NOT NULL REFERENCES teams(team_id) ON DELETE CASCADE,
+    PRIMARY KEY (season_id, team_id)
+);
+
+-- Tippers enrolled in each season
+CREATE TABLE IF NOT EXISTS season_tippers (
+    season_id INT NOT NULL REFERENCES seasons(season_id) ON DELETE CASCADE,
+    tipper_id INT NOT NULL REFERENCES tippers(tipper_id) ON DELETE CASCADE,
+    PRIMARY KEY (season_id, tipper_id)
+);
+
+-- Every round belongs to a season, existing rounds all go into one season.
+-- A database without rounds doesn't need one.
+DO $$
+DECLARE
+    legacy_season INT;
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM information_schema.columns
+                   WHERE table_name = 'rounds' AND column_name = 'season_id') THEN
+        INSERT INTO seasons (name, start_date, end_date)
+            SELECT 'Season ' || EXTRACT(YEAR FROM MIN(start_date)), MIN(start_date), MAX(end_date)
+            FROM rounds
+            HAVING EXISTS (SELECT 1 FROM rounds)
+            RETURNING season_id INTO legacy_season;
+        ALTER TABLE rounds ADD COLUMN season_id INT REFERENCES seasons(season_id);
+        IF legacy_season IS NOT NULL THEN
+            UPDATE rounds SET season_id = legacy_season;
+            INSERT INTO season_teams (season_id, team_id) SELECT legacy_season, team_id FROM teams;
+            INSERT INTO season_tippers (season_id, tipper_id) SELECT legacy_season, tipper_id FROM tippers;
+        END IF;
+        ALTER TABLE rounds ALTER COLUMN season_id SET NOT NULL;
+        ALTER TABLE rounds ADD UNIQUE (season_id, round_number);
+    END IF;
+END $$;
@@ -0,0 +1,32 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+-- Grounds games are played at, teams sharing a ground can't both be at home on the same day
+CREATE TABLE IF NOT EXISTS venues (
+    venue_id SERIAL PRIMARY KEY,
+    name     TEXT NOT NULL UNIQUE
+);
+
+ALTER TABLE teams ADD COLUMN IF NOT EXISTS home_venue_id INT REFERENCES venues(venue_id) ON DELETE SET NULL;
+ALTER TABLE games ADD COLUMN IF NOT EXISTS venue_id INT REFERENCES venues(venue_id) ON DELETE SET NULL;
@@ -0,0 +1,34 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+-- Emails sent to tippers about a round, so nobody is sent the same one twice
+CREATE TABLE IF NOT EXISTS mail_history (
+    kind      TEXT NOT NULL,
+    round_id  INT NOT NULL REFERENCES rounds(round_id) ON DELETE CASCADE,
+    tipper_id INT NOT NULL REFERENCES tippers(tipper_id) ON DELETE CASCADE,
+    email     TEXT NOT NULL,
+    subject   TEXT NOT NULL,
+    sent_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (kind, round_id, tipper_id)
+);
@@ -22,30 +22,36 @@
  *
  */
 #![allow(unused)]
-use chrono::NaiveDate;
+use chrono::{DateTime, FixedOffset, Utc};
 use kelpie_models::game::Game;
 use log::error;
 use rocket_db_pools::sqlx;
 use rocket_db_pools::sqlx::PgConnection;
 use rocket_db_pools::sqlx::Row;
 
+/// Without a `venue_id` the game is played at the home team's ground, if it has one.
+// One argument per column, like the rest of this module
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn insert(
     pool: &mut PgConnection,
     round_id: i32,
     home_team_id: i32,
     away_team_id: i32,
-    game_date: NaiveDate,
+    kick_off: DateTime<FixedOffset>,
+    venue_id: Option<i32>,
     home_team_score: Option<i32>,
     away_team_score: Option<i32>,
 ) -> Result<Game, sqlx::Error> {
     let result = sqlx::query(
-        "INSERT INTO games (round_id, home_team_id, away_team_id, game_date, home_team_score, away_team_score) \
-         VALUES ($1, $2, $3, $4, $5, $6) RETURNING game_id",
+        "INSERT INTO games (round_id, home_team_id, away_team_id, kick_off, venue_id, home_team_score, away_team_score) \
+         VALUES ($1, $2, $3, $4, COALESCE($5, (SELECT home_venue_id FROM teams WHERE team_id = $2)), $6, $7) \
+         RETURNING game_id, venue_id",
     )
         .bind(round_id)
         .bind(home_team_id)
         .bind(away_team_id)
-        .bind(game_date)
+        .bind(kick_off)
+        .bind(venue_id)
         .bind(home_team_score)
         .bind(away_team_score)
         .fetch_one(pool)
@@ -54,7 +60,8 @@ pub(crate) async fn insert(
     match result {
         Ok(row) => {
             let id = row.get::<i32, _>(0);
-            Ok(Game {game_id: Some(id), round_id: Some(round_id), home_team_id, away_team_id, game_date, home_team_score, away_team_score })
+            let venue_id = row.get::<Option<i32>, _>(1);
+            Ok(Game {game_id: Some(id), round_id: Some(round_id), home_team_id, away_team_id, kick_off, venue_id, home_team_score, away_team_score })
         }
         Err(e) => {
             error!("Error inserting game: {}", e);
@@ -63,23 +70,25 @@ pub(crate) async fn insert(
     }
 }
 
-
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn update(
     pool: &mut PgConnection,
     game_id: i32,
     home_team_id: i32,
     away_team_id: i32,
-    game_date: NaiveDate,
+    kick_off: DateTime<FixedOffset>,
+    venue_id: Option<i32>,
     home_team_score: Option<i32>,
     away_team_score: Option<i32>,
 ) -> Result<u64, sqlx::Error> {
     let result = sqlx::query(
-        "UPDATE games SET home_team_id=$1, away_team_id=$2, game_date=$3, \
-         home_team_score=$4, away_team_score=$5 WHERE game_id=$6",
+        "UPDATE games SET home_team_id=$1, away_team_id=$2, kick_off=$3, venue_id=$4, \
+         home_team_score=$5, away_team_score=$6 WHERE game_id=$7",
     )
         .bind(home_team_id)
         .bind(away_team_id)
-        .bind(game_date)
+        .bind(kick_off)
+        .bind(venue_id)
         .bind(home_team_score)
         .bind(away_team_score)
         .bind(game_id)
@@ -95,6 +104,29 @@ pub(crate) async fn update(
     }
 }
 
+/// Record a game's score, leaving the fixture alone. `None` for both clears the result.
+pub(crate) async fn set_result(
+    pool: &mut PgConnection,
+    game_id: i32,
+    home_team_score: Option<i32>,
+    away_team_score: Option<i32>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE games SET home_team_score=$1, away_team_score=$2 WHERE game_id=$3")
+        .bind(home_team_score)
+        .bind(away_team_score)
+        .bind(game_id)
+        .execute(pool)
+        .await;
+
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error setting game result: {}", e);
+            Err(e)
+        }
+    }
+}
+
 pub(crate) async fn delete(pool: &mut PgConnection, game_id: i32) -> Result<u64, sqlx::Error> {
     let result = sqlx::query("DELETE FROM games WHERE game_id=$1")
         .bind(game_id)
@@ -115,15 +147,16 @@ fn from_row(row: &sqlx::postgres::PgRow) -> Game {
         round_id: Some(row.get::<i32, _>(1)),
         home_team_id: row.get::<i32, _>(2),
         away_team_id: row.get::<i32, _>(3),
-        game_date: row.get::<NaiveDate, _>(4),
+        kick_off: row.get::<DateTime<Utc>, _>(4).fixed_offset(),
         home_team_score: row.get::<Option<i32>, _>(5),
         away_team_score: row.get::<Option<i32>, _>(6),
+        venue_id: row.get::<Option<i32>, _>(7),
     }
 }
 
 pub(crate) async fn get(pool: &mut PgConnection, game_id: i32) -> Result<Option<Game>, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT game_id, round_id, home_team_id, away_team_id, game_date, home_team_score, away_team_score \
+        "SELECT game_id, round_id, home_team_id, away_team_id, kick_off, home_team_score, away_team_score, venue_id \
          FROM games WHERE game_id=$1",
     )
         .bind(game_id)
@@ -144,8 +177,8 @@ pub(crate) async fn get(pool: &mut PgConnection, game_id: i32) -> Result<Option<
 
 pub(crate) async fn get_for_round(pool: &mut PgConnection, round_id: i32) -> Result<Vec<Game>, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT game_id, round_id, home_team_id, away_team_id, game_date, home_team_score, away_team_score \
-         FROM games WHERE round_id = $1 ORDER BY game_date",
+        "SELECT game_id, round_id, home_team_id, away_team_id, kick_off, home_team_score, away_team_score, venue_id \
+         FROM games WHERE round_id = $1 ORDER BY kick_off, game_id",
     )
         .bind(round_id)
         .fetch_all(pool)
@@ -165,8 +198,8 @@ pub(crate) async fn get_for_round(pool: &mut PgConnection, round_id: i32) -> Res
 
 pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Game>, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT game_id, round_id, home_team_id, away_team_id, game_date, home_team_score, away_team_score \
-         FROM games ORDER BY game_date",
+        "SELECT game_id, round_id, home_team_id, away_team_id, kick_off, home_team_score, away_team_score, venue_id \
+         FROM games ORDER BY kick_off",
     )
         .fetch_all(pool)
         .await;
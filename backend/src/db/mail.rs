@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+#![allow(unused)]
+use chrono::{DateTime, Utc};
+use kelpie_models::tipper::Tipper;
+use log::error;
+use rocket::serde::Serialize;
+use sqlx::{PgConnection, Row};
+
+/// The `kind` of a reminder to tip
+pub(crate) const REMINDER: &str = "reminder";
+/// The `kind` of a round summary
+pub(crate) const ROUND_SUMMARY: &str = "round_summary";
+
+/// An email recorded in the mail history.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct SentMail {
+    pub(crate) kind: String,
+    pub(crate) round_id: i32,
+    pub(crate) tipper_id: i32,
+    pub(crate) email: String,
+    pub(crate) subject: String,
+    pub(crate) sent_at: DateTime<Utc>,
+}
+
+/// Rounds whose first game kicks off after `from` and no later than `until`.
+pub(crate) async fn rounds_starting(pool: &mut PgConnection, from: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT round_id FROM games
+                GROUP BY round_id
+                HAVING MIN(kick_off) > $1 AND MIN(kick_off) <= $2
+                ORDER BY MIN(kick_off)"#,
+    )
+        .bind(from)
+        .bind(until)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting rounds starting: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Rounds where every game has a score and the last game kicked off after `from` and no later than `until`.
+pub(crate) async fn rounds_completed(pool: &mut PgConnection, from: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT round_id FROM games
+                GROUP BY round_id
+                HAVING MAX(kick_off) > $1 AND MAX(kick_off) <= $2
+                   AND bool_and(home_team_score IS NOT NULL AND away_team_score IS NOT NULL)
+                ORDER BY MAX(kick_off)"#,
+    )
+        .bind(from)
+        .bind(until)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting completed rounds: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Whether the round has games and every one of them has a score, as for [`rounds_completed`].
+pub(crate) async fn round_completed(pool: &mut PgConnection, round_id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT COALESCE(bool_and(home_team_score IS NOT NULL AND away_team_score IS NOT NULL), FALSE)
+                FROM games WHERE round_id = $1"#,
+    )
+        .bind(round_id)
+        .fetch_one(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.get::<bool, _>(0)),
+        Err(e) => {
+            error!("Error checking the round is completed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// The tippers already sent a `kind` email for the round.
+pub(crate) async fn sent_to(pool: &mut PgConnection, kind: &str, round_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query("SELECT tipper_id FROM mail_history WHERE kind = $1 AND round_id = $2")
+        .bind(kind)
+        .bind(round_id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting mail recipients: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Tippers in the round's season who haven't tipped every game of the round, with the games they
+/// haven't tipped, leaving out anyone already sent a `kind` email for the round.
+pub(crate) async fn untipped_games(pool: &mut PgConnection, round_id: i32, kind: &str) -> Result<Vec<(Tipper, Vec<i32>)>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT t.tipper_id, t.name, t.email, t.is_admin, g.game_id
+                FROM rounds r
+                JOIN season_tippers st ON st.season_id = r.season_id
+                JOIN tippers t ON t.tipper_id = st.tipper_id
+                JOIN games g ON g.round_id = r.round_id
+                WHERE r.round_id = $1
+                  AND NOT EXISTS (SELECT 1 FROM tips WHERE tips.game_id = g.game_id AND tips.tipper_id = t.tipper_id)
+                  AND NOT EXISTS (SELECT 1 FROM mail_history h
+                                  WHERE h.kind = $2 AND h.round_id = r.round_id AND h.tipper_id = t.tipper_id)
+                ORDER BY t.name, g.kick_off, g.game_id"#,
+    )
+        .bind(round_id)
+        .bind(kind)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => {
+            let mut tippers: Vec<(Tipper, Vec<i32>)> = Vec::new();
+            for row in rows {
+                let tipper_id = row.get::<i32, _>(0);
+                let game_id = row.get::<i32, _>(4);
+                match tippers.last_mut() {
+                    Some((tipper, games)) if tipper.id == Some(tipper_id) => games.push(game_id),
+                    _ => {
+                        let tipper = Tipper {
+                            id: Some(tipper_id),
+                            name: row.get::<String, _>(1),
+                            email: row.get::<String, _>(2),
+                            password: None,
+                            is_admin: row.get::<bool, _>(3),
+                        };
+                        tippers.push((tipper, vec![game_id]));
+                    }
+                }
+            }
+            Ok(tippers)
+        }
+        Err(e) => {
+            error!("Error getting untipped games: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Record an email as sent, once per kind, round and tipper.
+pub(crate) async fn record_sent(pool: &mut PgConnection, kind: &str, round_id: i32, tipper_id: i32,
+                                email: &str, subject: &str) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"INSERT INTO mail_history (kind, round_id, tipper_id, email, subject)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT DO NOTHING"#,
+    )
+        .bind(kind)
+        .bind(round_id)
+        .bind(tipper_id)
+        .bind(email)
+        .bind(subject)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error recording sent mail: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Emails sent, newest first, for one round when `round_id` is given.
+pub(crate) async fn get_history(pool: &mut PgConnection, round_id: Option<i32>) -> Result<Vec<SentMail>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT kind, round_id, tipper_id, email, subject, sent_at FROM mail_history
+                WHERE ($1::INT IS NULL OR round_id = $1)
+                ORDER BY sent_at DESC"#,
+    )
+        .bind(round_id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.iter().map(|row| SentMail {
+            kind: row.get::<String, _>(0),
+            round_id: row.get::<i32, _>(1),
+            tipper_id: row.get::<i32, _>(2),
+            email: row.get::<String, _>(3),
+            subject: row.get::<String, _>(4),
+            sent_at: row.get::<DateTime<Utc>, _>(5),
+        }).collect()),
+        Err(e) => {
+            error!("Error getting mail history: {}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::util::config::TippingConfig;
+use crate::DbTips;
+use log::error;
+use rocket::fairing::AdHoc;
+use rocket_db_pools::Database;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::{PgPool, Row};
+
+/// The schema migrations in `backend/migrations`, embedded at build time.
+pub(crate) static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Bring the database schema up to date on start up.
+///
+/// Refuses to start if the database has been migrated by a newer version of the backend,
+/// rather than run against a schema this version doesn't know about.
+///
+/// The competition time zone and first kick-off are set on the connection as `kelpie.timezone`
+/// and `kelpie.first_kick_off`, for migrations that convert existing dates to kick-off times.
+pub(crate) fn stage() -> AdHoc {
+    AdHoc::try_on_ignite("Database Migrations", |rocket| async {
+        let Some(db) = DbTips::fetch(&rocket) else {
+            error!("Database not available for migrations");
+            return Err(rocket);
+        };
+
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0);
+        match applied_version(&db.0).await {
+            Ok(Some(version)) if version > latest => {
+                error!("Database schema version {} is newer than this server supports ({}), refusing to start",
+                    version, latest);
+                return Err(rocket);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!("Error reading database schema version: {}", e);
+                return Err(rocket);
+            }
+        }
+
+        let config = rocket.state::<TippingConfig>().cloned().unwrap_or_default();
+        match migrate(&db.0, &config).await {
+            Ok(()) => {
+                tracing::info!("Database schema is at version {}", latest);
+                Ok(rocket)
+            }
+            Err(e) => {
+                error!("Error migrating database: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+/// Run the migrations on a connection with the competition settings, see [`stage`].
+async fn migrate(pool: &PgPool, config: &TippingConfig) -> Result<(), MigrateError> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SELECT set_config('kelpie.timezone', $1, false), set_config('kelpie.first_kick_off', $2, false)")
+        .bind(config.timezone.name())
+        .bind(config.first_kick_off.to_string())
+        .execute(&mut *conn)
+        .await?;
+    // `run` on a connection trips "implementation of `Acquire` is not general enough" in the fairing
+    MIGRATOR.run_direct(&mut *conn).await
+}
+
+/// The latest migration applied to the database, `None` for a database that has never been migrated.
+async fn applied_version(pool: &PgPool) -> Result<Option<i64>, sqlx::Error> {
+    let exists = sqlx::query("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?
+        .get::<bool, _>(0);
+    if !exists {
+        return Ok(None);
+    }
+    let row = sqlx::query("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get::<Option<i64>, _>(0))
+}
@@ -23,8 +23,12 @@
  */
 
 pub(crate) mod game;
+pub(crate) mod migrations;
 pub(crate) mod round;
+pub(crate) mod season;
 pub(crate) mod team;
 pub(crate) mod tipper;
 pub(crate) mod tip;
-pub(crate) mod reporting;
\ No newline at end of file
+pub(crate) mod reporting;
+pub(crate) mod venue;
+pub(crate) mod mail;
\ No newline at end of file
@@ -22,85 +22,702 @@
  *
  */
 #![allow(unused)]
+use crate::db::{game, round, season, team, tip, tipper};
+use crate::util::config::{LadderPoints, TippingConfig};
+use crate::util::scoring::{self, RoundScore};
+use crate::util::tip_lock;
+use chrono::{DateTime, FixedOffset};
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+use kelpie_models::scoring::ScoringRules;
+use kelpie_models::tip::Tip;
 use rocket::serde::Serialize;
 use sqlx::PgConnection;
+use std::collections::HashMap;
 
-fn leaderboard_sql(where_clause: &str) -> String {
-    format!(r#"
-        WITH tip_scores AS (
-            SELECT
-                tips.tipper_id,
-                tippers.name AS tipper_name,
-                games.round_id,
-                tips.game_id,
-                CASE
-                    WHEN (tips.team_id = games.home_team_id AND games.home_team_score >= games.away_team_score)
-                      OR (tips.team_id = games.away_team_id AND games.away_team_score >= games.home_team_score)
-                    THEN 1 ELSE 0 END AS score
-            FROM tips
-            JOIN tippers ON tips.tipper_id = tippers.tipper_id
-            JOIN games ON tips.game_id = games.game_id
-            {where_clause}
-        ),
-        round_perfect AS (
-            SELECT
-                ts.tipper_id,
-                ts.round_id,
-                r.bonus_points,
-                CASE WHEN COUNT(*) = SUM(ts.score) AND COUNT(*) > 0 THEN r.bonus_points ELSE 0 END AS bonus
-            FROM tip_scores ts
-            JOIN rounds r ON ts.round_id = r.round_id
-            GROUP BY ts.tipper_id, ts.round_id, r.bonus_points
-        ),
-        tipper_scores AS (
-            SELECT
-                tippers.name AS tipper_name,
-                COALESCE(SUM(ts.score),0) AS tip_score
-            FROM tippers
-            LEFT JOIN tip_scores ts ON tippers.tipper_id = ts.tipper_id
-            GROUP BY tippers.name
-        ),
-        tipper_bonuses AS (
-            SELECT
-                tippers.name AS tipper_name,
-                COALESCE(SUM(rp.bonus),0) AS bonus_score
-            FROM tippers
-            LEFT JOIN round_perfect rp ON tippers.tipper_id = rp.tipper_id
-            GROUP BY tippers.name
-        )
-        SELECT
-            ts.tipper_name,
-            ts.tip_score,
-            tb.bonus_score,
-            (ts.tip_score + tb.bonus_score) AS total_score
-        FROM tipper_scores ts
-        LEFT JOIN tipper_bonuses tb ON ts.tipper_name = tb.tipper_name
-        ORDER BY total_score DESC
-    "#, where_clause = where_clause)
-}
-
-pub async fn get_leaderboard(pool: &mut PgConnection) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
-    let query = leaderboard_sql("");
-    let leaderboard = sqlx::query_as::<_, LeaderboardEntry>(&query)
-        .fetch_all(pool)
-        .await?;
-    Ok(leaderboard)
+/// Rounds scored under their season's scoring rules.
+pub(crate) struct ScoredRounds {
+    /// The tippers scored, with their names
+    pub(crate) tippers: Vec<(i32, String)>,
+    pub(crate) rounds: Vec<ScoredRound>,
 }
 
-pub async fn get_score_by_round(pool: &mut PgConnection, round_id: i32) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
-    let query = leaderboard_sql("WHERE games.round_id = $1");
-    let leaderboard = sqlx::query_as::<_, LeaderboardEntry>(&query)
-        .bind(round_id)
-        .fetch_all(pool)
-        .await?;
+pub(crate) struct ScoredRound {
+    pub(crate) round: Round,
+    pub(crate) games: Vec<Game>,
+    pub(crate) tips: Vec<Tip>,
+    /// Every tipper's score for the round, by tipper id
+    pub(crate) scores: HashMap<i32, RoundScore>,
+}
+
+impl ScoredRound {
+    /// Whether any game in the round has a result yet.
+    pub(crate) fn has_results(&self) -> bool {
+        self.games.iter().any(|g| g.home_team_score.is_some() && g.away_team_score.is_some())
+    }
+}
+
+async fn score_rounds(pool: &mut PgConnection, rounds: Vec<Round>, tipper_season: Option<i32>) -> Result<ScoredRounds, sqlx::Error> {
+    let tippers: Vec<(i32, String)> = tipper::get_all(pool, tipper_season).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    let tipper_ids: Vec<i32> = tippers.iter().map(|(id, _)| *id).collect();
+
+    let mut rules: HashMap<i32, ScoringRules> = HashMap::new();
+    let mut scored = Vec::new();
+    for round in rounds {
+        let Some(round_id) = round.round_id else { continue };
+        if !rules.contains_key(&round.season_id) {
+            let season_rules = season::get_scoring_rules(pool, round.season_id).await?.unwrap_or_default();
+            rules.insert(round.season_id, season_rules);
+        }
+        let games = game::get_for_round(pool, round_id).await?;
+        let tips = tip::get_by_round(pool, round_id).await?;
+        let scores = scoring::score_round(&rules[&round.season_id], round.bonus_points as i64, &games, &tips, &tipper_ids);
+        scored.push(ScoredRound { round, games, tips, scores });
+    }
+    Ok(ScoredRounds { tippers, rounds: scored })
+}
+
+/// The rounds of a season, or of all seasons, scored for the season's tippers, or all tippers.
+pub(crate) async fn score_season(pool: &mut PgConnection, season_id: Option<i32>) -> Result<ScoredRounds, sqlx::Error> {
+    let rounds = round::get_all(pool, season_id).await?;
+    score_rounds(pool, rounds, season_id).await
+}
+
+/// A single round scored for the tippers in its season.
+pub(crate) async fn score_round(pool: &mut PgConnection, round_id: i32) -> Result<ScoredRounds, sqlx::Error> {
+    match round::get(pool, round_id).await? {
+        Some(round) => {
+            let season_id = round.season_id;
+            score_rounds(pool, vec![round], Some(season_id)).await
+        }
+        None => Ok(ScoredRounds { tippers: vec![], rounds: vec![] }),
+    }
+}
+
+/// Rank the tippers on their total over `rounds`.
+fn standings(tippers: &[(i32, String)], rounds: &[ScoredRound]) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = tippers.iter().map(|(tipper_id, name)| {
+        let mut total = RoundScore::default();
+        for round in rounds {
+            total += round.scores.get(tipper_id).copied().unwrap_or_default();
+        }
+        LeaderboardEntry {
+            tipper_id: *tipper_id,
+            tipper_name: name.clone(),
+            tip_score: total.tip_score,
+            bonus_score: total.bonus_score,
+            total_score: total.total(),
+            margin_error: total.margin_error,
+            rank: 0,
+            previous_rank: None,
+        }
+    }).collect();
+    // Ties on points go to whoever has been closest with their margins
+    entries.sort_by(|a, b| b.total_score.cmp(&a.total_score)
+        .then_with(|| a.margin_error.cmp(&b.margin_error))
+        .then_with(|| a.tipper_name.cmp(&b.tipper_name)));
 
-    Ok(leaderboard)
+    // Tippers still level after the tie-breaker share a rank, and the next rank is skipped
+    for i in 0..entries.len() {
+        entries[i].rank = if i > 0
+            && entries[i].total_score == entries[i - 1].total_score
+            && entries[i].margin_error == entries[i - 1].margin_error {
+            entries[i - 1].rank
+        } else {
+            i as i64 + 1
+        };
+    }
+    entries
 }
 
-#[derive(sqlx::FromRow, Debug, Serialize)]
+/// The leaderboard with each tipper's rank before the latest round with results.
+fn leaderboard(scored: &ScoredRounds) -> Vec<LeaderboardEntry> {
+    let mut entries = standings(&scored.tippers, &scored.rounds);
+    if let Some(latest) = scored.rounds.iter().rposition(|r| r.has_results()) {
+        if latest > 0 {
+            let previous: HashMap<i32, i64> = standings(&scored.tippers, &scored.rounds[..latest])
+                .into_iter()
+                .map(|e| (e.tipper_id, e.rank))
+                .collect();
+            for entry in &mut entries {
+                entry.previous_rank = previous.get(&entry.tipper_id).copied();
+            }
+        }
+    }
+    entries
+}
+
+/// The leaderboard for a season, or the all-time leaderboard when no season is given.
+pub async fn get_leaderboard(pool: &mut PgConnection, season_id: Option<i32>) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+    let scored = score_season(pool, season_id).await?;
+    Ok(leaderboard(&scored))
+}
+
+pub async fn get_score_by_round(pool: &mut PgConnection, round_id: i32) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+    let scored = score_round(pool, round_id).await?;
+    Ok(leaderboard(&scored))
+}
+
+#[derive(Debug, Serialize)]
 pub struct LeaderboardEntry {
+    pub tipper_id: i32,
     pub tipper_name: String,
     pub tip_score: i64,
     pub bonus_score: i64,
     pub total_score: i64,
-}
\ No newline at end of file
+    pub margin_error: i64,
+    /// 1 for the leader, tippers level on points and margin error share a rank
+    pub rank: i64,
+    /// The rank before the latest round with results, `None` when there is no earlier round
+    pub previous_rank: Option<i64>,
+}
+
+
+/// Every tipper's score for every round of a season, in leaderboard order.
+pub async fn get_matrix(pool: &mut PgConnection, season_id: Option<i32>) -> Result<ScoreMatrix, sqlx::Error> {
+    let scored = score_season(pool, season_id).await?;
+    let rounds = scored.rounds.iter()
+        .filter_map(|r| r.round.round_id.map(|round_id| MatrixRound {
+            round_id,
+            season_id: r.round.season_id,
+            round_number: r.round.round_number,
+        }))
+        .collect();
+    let rows = standings(&scored.tippers, &scored.rounds).into_iter().map(|entry| {
+        let mut running_total = 0;
+        let cells = scored.rounds.iter().map(|r| {
+            // Rounds still to be played are left blank rather than shown as zero
+            if !r.has_results() {
+                return None;
+            }
+            let score = r.scores.get(&entry.tipper_id).copied().unwrap_or_default();
+            running_total += score.total();
+            Some(MatrixCell {
+                tip_score: score.tip_score,
+                bonus_score: score.bonus_score,
+                running_total,
+            })
+        }).collect();
+        MatrixRow {
+            tipper_id: entry.tipper_id,
+            tipper_name: entry.tipper_name,
+            rank: entry.rank,
+            cells,
+        }
+    }).collect();
+    Ok(ScoreMatrix { rounds, rows })
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScoreMatrix {
+    pub rounds: Vec<MatrixRound>,
+    /// One row per tipper, each with a cell for every round
+    pub rows: Vec<MatrixRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatrixRound {
+    pub round_id: i32,
+    pub season_id: i32,
+    pub round_number: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatrixRow {
+    pub tipper_id: i32,
+    pub tipper_name: String,
+    pub rank: i64,
+    /// `None` for rounds without results
+    pub cells: Vec<Option<MatrixCell>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatrixCell {
+    pub tip_score: i64,
+    pub bonus_score: i64,
+    pub running_total: i64,
+}
+
+/// The standings for a round and for its season up to and including the round, for the round
+/// summary email. `None` if there is no such round.
+pub(crate) async fn get_round_summary(pool: &mut PgConnection, round_id: i32) -> Result<Option<RoundSummary>, sqlx::Error> {
+    let Some(round) = round::get(pool, round_id).await? else {
+        return Ok(None);
+    };
+    let season_name = season::get(pool, round.season_id).await?.map(|s| s.name).unwrap_or_default();
+    let scored = score_season(pool, Some(round.season_id)).await?;
+    let Some(index) = scored.rounds.iter().position(|r| r.round.round_id == Some(round_id)) else {
+        return Ok(None);
+    };
+    Ok(Some(RoundSummary {
+        season_name,
+        round_number: round.round_number,
+        round: standings(&scored.tippers, &scored.rounds[index..=index]),
+        season: standings(&scored.tippers, &scored.rounds[..=index]),
+    }))
+}
+
+pub(crate) struct RoundSummary {
+    pub(crate) season_name: String,
+    pub(crate) round_number: i32,
+    /// Placings on the round's scores alone
+    pub(crate) round: Vec<LeaderboardEntry>,
+    /// Placings on the season total after the round
+    pub(crate) season: Vec<LeaderboardEntry>,
+}
+
+/// Each round's scores and tips, for a single round or every round of a season, with kick-offs in local time.
+pub(crate) async fn get_round_reports(pool: &mut PgConnection, config: &TippingConfig, season_id: Option<i32>, round_id: Option<i32>)
+    -> Result<Vec<RoundReport>, sqlx::Error> {
+    let scored = match round_id {
+        Some(round_id) => score_round(pool, round_id).await?,
+        None => score_season(pool, season_id).await?,
+    };
+    let teams: HashMap<i32, String> = team::get_all(pool, None).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    let team_name = |id: i32| teams.get(&id).cloned().unwrap_or_default();
+
+    Ok(scored.rounds.iter().map(|r| {
+        let mut games = config.localise(r.games.clone());
+        games.sort_by_key(|g| (g.kick_off, g.game_id));
+        let mut tips = vec![];
+        for (tipper_id, tipper_name) in &scored.tippers {
+            for game in &games {
+                let tip = r.tips.iter().find(|t| t.tipper_id == *tipper_id && Some(t.game_id) == game.game_id);
+                let tipped_team_id = tip.and_then(|t| t.team_id);
+                tips.push(TipLine {
+                    tipper_name: tipper_name.clone(),
+                    kick_off: game.kick_off,
+                    home_team_name: team_name(game.home_team_id),
+                    away_team_name: team_name(game.away_team_id),
+                    tipped_team_name: tipped_team_id.map(team_name),
+                    margin: tip.and_then(|t| t.margin),
+                    correct: scoring::outcome(game).flatten().map(|winner| tipped_team_id == Some(winner)),
+                });
+            }
+        }
+        RoundReport {
+            round_number: r.round.round_number,
+            has_results: r.has_results(),
+            scores: standings(&scored.tippers, std::slice::from_ref(r)),
+            tips,
+        }
+    }).collect())
+}
+
+pub(crate) struct RoundReport {
+    pub(crate) round_number: i32,
+    pub(crate) has_results: bool,
+    /// Placings on the round's scores alone
+    pub(crate) scores: Vec<LeaderboardEntry>,
+    /// Every tipper's tip for every game, by tipper then kick-off
+    pub(crate) tips: Vec<TipLine>,
+}
+
+pub(crate) struct TipLine {
+    pub(crate) tipper_name: String,
+    pub(crate) kick_off: DateTime<FixedOffset>,
+    pub(crate) home_team_name: String,
+    pub(crate) away_team_name: String,
+    /// `None` when the tipper didn't tip the game
+    pub(crate) tipped_team_name: Option<String>,
+    pub(crate) margin: Option<i32>,
+    /// `None` until the game has a result, and for a draw
+    pub(crate) correct: Option<bool>,
+}
+
+/// How one tipper has been going over a season, or all seasons. `None` if there is no such tipper.
+pub async fn get_form_guide(pool: &mut PgConnection, tipper_id: i32, season_id: Option<i32>) -> Result<Option<FormGuide>, sqlx::Error> {
+    let Some(tipper) = tipper::get(pool, tipper_id).await? else {
+        return Ok(None);
+    };
+    let scored = score_season(pool, season_id).await?;
+    let teams: HashMap<i32, String> = team::get_all(pool, None).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    Ok(Some(form_guide(tipper_id, tipper.name, &scored, &teams)))
+}
+
+/// The team picked by more tippers than the other, `None` if the crowd was split evenly.
+fn favourite(game: &Game, tips: &[Tip]) -> Option<i32> {
+    let picked = |team_id| tips.iter().filter(|t| Some(t.game_id) == game.game_id && t.team_id == Some(team_id)).count();
+    let (home, away) = (picked(game.home_team_id), picked(game.away_team_id));
+    if home > away {
+        Some(game.home_team_id)
+    } else if away > home {
+        Some(game.away_team_id)
+    } else {
+        None
+    }
+}
+
+fn percentage(count: i64, of: i64) -> f64 {
+    if of == 0 { 0.0 } else { count as f64 * 100.0 / of as f64 }
+}
+
+/// Drawn games are left out of the strike rate, streaks and team form as there was no winner to tip,
+/// while an untipped game with a winner counts as a miss.
+fn form_guide(tipper_id: i32, tipper_name: String, scored: &ScoredRounds, teams: &HashMap<i32, String>) -> FormGuide {
+    let mut guide = FormGuide { tipper_id, tipper_name, ..FormGuide::default() };
+    let mut team_form: HashMap<i32, TeamForm> = HashMap::new();
+    let mut streak = 0;
+
+    for scored_round in &scored.rounds {
+        let tipped: HashMap<i32, i32> = scored_round.tips.iter()
+            .filter(|t| t.tipper_id == tipper_id)
+            .filter_map(|t| t.team_id.map(|team_id| (t.game_id, team_id)))
+            .collect();
+        let mut games: Vec<&Game> = scored_round.games.iter().collect();
+        games.sort_by_key(|g| (g.kick_off, g.game_id));
+
+        let mut round = RoundForm {
+            round_id: scored_round.round.round_id.unwrap_or_default(),
+            round_number: scored_round.round.round_number,
+            ..RoundForm::default()
+        };
+        let mut complete = true;
+        for game in games {
+            let Some(game_id) = game.game_id else { continue };
+            let team_id = tipped.get(&game_id).copied();
+            if let Some(team_id) = team_id {
+                guide.tips += 1;
+                if team_id == game.home_team_id {
+                    guide.home_tips += 1;
+                }
+                if let Some(favourite) = favourite(game, &scored_round.tips) {
+                    guide.favourite_games += 1;
+                    if team_id == favourite {
+                        guide.favourite_tips += 1;
+                    }
+                }
+            }
+
+            let winner = match scoring::outcome(game) {
+                Some(Some(winner)) => winner,
+                Some(None) => continue,
+                None => {
+                    complete = false;
+                    continue;
+                }
+            };
+            round.decided += 1;
+            let correct = team_id == Some(winner);
+            if correct {
+                round.correct += 1;
+                streak += 1;
+                guide.longest_streak = guide.longest_streak.max(streak);
+            } else {
+                streak = 0;
+            }
+            if let Some(team_id) = team_id {
+                let form = team_form.entry(team_id).or_insert_with(|| TeamForm {
+                    team_id,
+                    team_name: teams.get(&team_id).cloned().unwrap_or_default(),
+                    tipped: 0,
+                    correct: 0,
+                });
+                form.tipped += 1;
+                if correct {
+                    form.correct += 1;
+                }
+            }
+        }
+
+        if !scored_round.has_results() {
+            continue;
+        }
+        let score = scored_round.scores.get(&tipper_id).copied().unwrap_or_default();
+        round.tip_score = score.tip_score;
+        round.bonus_score = score.bonus_score;
+        round.perfect = complete && round.decided > 0 && round.correct == round.decided;
+        guide.decided += round.decided;
+        guide.correct += round.correct;
+        if round.perfect {
+            guide.perfect_rounds += 1;
+        }
+        guide.rounds.push(round);
+    }
+
+    guide.strike_rate = percentage(guide.correct, guide.decided);
+    guide.home_rate = percentage(guide.home_tips, guide.tips);
+    guide.favourite_rate = percentage(guide.favourite_tips, guide.favourite_games);
+
+    let mut teams: Vec<TeamForm> = team_form.into_values().collect();
+    teams.sort_by(|a, b| b.net().cmp(&a.net()).then_with(|| a.team_name.cmp(&b.team_name)));
+    guide.best_team = teams.first().filter(|t| t.net() > 0).cloned();
+    guide.worst_team = teams.last().filter(|t| t.net() < 0).cloned();
+    guide.teams = teams;
+    guide
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct FormGuide {
+    pub tipper_id: i32,
+    pub tipper_name: String,
+    /// Rounds with results, in round order
+    pub rounds: Vec<RoundForm>,
+    /// Games with a winner
+    pub decided: i64,
+    /// Games where the tipper picked the winner
+    pub correct: i64,
+    /// Percentage of decided games tipped correctly
+    pub strike_rate: f64,
+    pub perfect_rounds: i64,
+    /// Most decided games tipped correctly in a row
+    pub longest_streak: i64,
+    /// The team that has earned the tipper the most, net of the times it let them down
+    pub best_team: Option<TeamForm>,
+    pub worst_team: Option<TeamForm>,
+    /// Every team the tipper has picked in a decided game, best first
+    pub teams: Vec<TeamForm>,
+    pub tips: i64,
+    pub home_tips: i64,
+    pub home_rate: f64,
+    /// Games where one side was picked by more tippers than the other
+    pub favourite_games: i64,
+    pub favourite_tips: i64,
+    pub favourite_rate: f64,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct RoundForm {
+    pub round_id: i32,
+    pub round_number: i32,
+    pub tip_score: i64,
+    pub bonus_score: i64,
+    pub decided: i64,
+    pub correct: i64,
+    /// Every game played and every winner tipped
+    pub perfect: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TeamForm {
+    pub team_id: i32,
+    pub team_name: String,
+    pub tipped: i64,
+    pub correct: i64,
+}
+
+impl TeamForm {
+    fn net(&self) -> i64 {
+        2 * self.correct - self.tipped
+    }
+}
+
+/// How the tippers in a round's season picked each of its games, in kick-off order.
+/// The picks for a game are kept hidden until it locks at `now`.
+pub async fn get_game_breakdown(pool: &mut PgConnection, config: &TippingConfig, round_id: i32, now: DateTime<FixedOffset>)
+    -> Result<Option<Vec<GameBreakdown>>, sqlx::Error> {
+    let Some(round) = round::get(pool, round_id).await? else {
+        return Ok(None);
+    };
+    let games = game::get_for_round(pool, round_id).await?;
+    let tips = tip::get_by_round(pool, round_id).await?;
+    let tippers: Vec<(i32, String)> = tipper::get_all(pool, Some(round.season_id)).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    let teams: HashMap<i32, String> = team::get_all(pool, None).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    Ok(Some(game_breakdown(config, &round, games, &tips, &tippers, &teams, now)))
+}
+
+fn game_breakdown(config: &TippingConfig, round: &Round, mut games: Vec<Game>, tips: &[Tip], tippers: &[(i32, String)],
+                  teams: &HashMap<i32, String>, now: DateTime<FixedOffset>) -> Vec<GameBreakdown> {
+    games.sort_by_key(|g| (g.kick_off, g.game_id));
+    games.iter().filter_map(|game| {
+        let game_id = game.game_id?;
+        let picked: HashMap<i32, i32> = tips.iter()
+            .filter(|t| t.game_id == game_id)
+            .filter_map(|t| t.team_id.map(|team_id| (t.tipper_id, team_id)))
+            .collect();
+        let locked = tip_lock::is_locked(config, round, &games, game, now);
+        let picks = |team_id| locked.then(|| tippers.iter().filter(|(id, _)| picked.get(id) == Some(&team_id)).count() as i64);
+        let winner = scoring::outcome(game).flatten();
+        let correct_tippers: Vec<String> = match winner {
+            Some(winner) => tippers.iter()
+                .filter(|(id, _)| picked.get(id) == Some(&winner))
+                .map(|(_, name)| name.clone())
+                .collect(),
+            None => vec![],
+        };
+        Some(GameBreakdown {
+            game_id,
+            home_team_id: game.home_team_id,
+            home_team_name: teams.get(&game.home_team_id).cloned().unwrap_or_default(),
+            away_team_id: game.away_team_id,
+            away_team_name: teams.get(&game.away_team_id).cloned().unwrap_or_default(),
+            home_team_score: game.home_team_score,
+            away_team_score: game.away_team_score,
+            home_picks: picks(game.home_team_id),
+            away_picks: picks(game.away_team_id),
+            untipped: locked.then(|| tippers.iter().filter(|(id, _)| !picked.contains_key(id)).count() as i64),
+            winner_team_id: winner,
+            nobody_correct: winner.is_some() && correct_tippers.is_empty(),
+            everybody_correct: winner.is_some() && !tippers.is_empty() && correct_tippers.len() == tippers.len(),
+            correct_tippers,
+        })
+    }).collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameBreakdown {
+    pub game_id: i32,
+    pub home_team_id: i32,
+    pub home_team_name: String,
+    pub away_team_id: i32,
+    pub away_team_name: String,
+    pub home_team_score: Option<i32>,
+    pub away_team_score: Option<i32>,
+    /// The pick counts are `None` until the game locks, so nobody can follow the crowd
+    pub home_picks: Option<i64>,
+    pub away_picks: Option<i64>,
+    /// Tippers in the season who didn't tip the game
+    pub untipped: Option<i64>,
+    /// `None` until the game has a result, and for a draw
+    pub winner_team_id: Option<i32>,
+    /// Names of the tippers who picked the winner
+    pub correct_tippers: Vec<String>,
+    pub nobody_correct: bool,
+    pub everybody_correct: bool,
+}
+
+/// The team ladder from the results of a season's games, up to and including round `up_to_round` if given.
+pub async fn get_ladder(pool: &mut PgConnection, season_id: Option<i32>, up_to_round: Option<i32>, points: LadderPoints)
+    -> Result<Vec<LadderEntry>, sqlx::Error> {
+    let team_ids: Vec<i32> = team::get_all(pool, season_id).await?.into_iter().filter_map(|t| t.id).collect();
+    let names: HashMap<i32, String> = team::get_all(pool, None).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    let mut games = Vec::new();
+    for round in round::get_all(pool, season_id).await? {
+        if up_to_round.is_some_and(|last| round.round_number > last) {
+            continue;
+        }
+        if let Some(round_id) = round.round_id {
+            games.extend(game::get_for_round(pool, round_id).await?);
+        }
+    }
+    Ok(ladder(&team_ids, &names, &games, points))
+}
+
+fn ladder(team_ids: &[i32], names: &HashMap<i32, String>, games: &[Game], points: LadderPoints) -> Vec<LadderEntry> {
+    let new_entry = |team_id: i32| LadderEntry::new(team_id, names.get(&team_id).cloned().unwrap_or_default());
+    let mut entries: HashMap<i32, LadderEntry> = team_ids.iter().map(|&id| (id, new_entry(id))).collect();
+
+    for game in games {
+        let (Some(home), Some(away)) = (game.home_team_score, game.away_team_score) else { continue };
+        for (team_id, scored, conceded) in [(game.home_team_id, home, away), (game.away_team_id, away, home)] {
+            // A team no longer in the season still gets a line for the games it played
+            let entry = entries.entry(team_id).or_insert_with(|| new_entry(team_id));
+            entry.played += 1;
+            entry.points_for += scored as i64;
+            entry.points_against += conceded as i64;
+            if scored > conceded {
+                entry.won += 1;
+                entry.premiership_points += points.win;
+            } else if scored < conceded {
+                entry.lost += 1;
+            } else {
+                entry.drawn += 1;
+                entry.premiership_points += points.draw;
+            }
+        }
+    }
+
+    let mut entries: Vec<LadderEntry> = entries.into_values().collect();
+    for entry in &mut entries {
+        entry.percentage = (entry.points_against > 0)
+            .then(|| entry.points_for as f64 * 100.0 / entry.points_against as f64);
+    }
+    // A team that has scored without conceding has the best percentage of all
+    let ratio = |e: &LadderEntry| match e.percentage {
+        Some(percentage) => percentage,
+        None if e.points_for > 0 => f64::INFINITY,
+        None => 0.0,
+    };
+    entries.sort_by(|a, b| b.premiership_points.cmp(&a.premiership_points)
+        .then_with(|| ratio(b).total_cmp(&ratio(a)))
+        .then_with(|| a.team_name.cmp(&b.team_name)));
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.position = i as i64 + 1;
+    }
+    entries
+}
+
+#[derive(Debug, Serialize)]
+pub struct LadderEntry {
+    pub position: i64,
+    pub team_id: i32,
+    pub team_name: String,
+    pub played: i64,
+    pub won: i64,
+    pub lost: i64,
+    pub drawn: i64,
+    pub points_for: i64,
+    pub points_against: i64,
+    /// Points for as a percentage of points against, `None` until the team has conceded a point
+    pub percentage: Option<f64>,
+    pub premiership_points: i64,
+}
+
+impl LadderEntry {
+    fn new(team_id: i32, team_name: String) -> Self {
+        LadderEntry {
+            position: 0,
+            team_id,
+            team_name,
+            played: 0,
+            won: 0,
+            lost: 0,
+            drawn: 0,
+            points_for: 0,
+            points_against: 0,
+            percentage: None,
+            premiership_points: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, round, tip};
+
+    #[test]
+    fn a_team_yet_to_concede_tops_the_percentage() {
+        let names: HashMap<i32, String> = [(1, "Geelong"), (2, "Collingwood"), (3, "Carlton"), (4, "Essendon")]
+            .into_iter()
+            .map(|(id, name)| (id, name.to_string()))
+            .collect();
+        // Geelong and Carlton both win, Geelong without conceding a point
+        let games = [game(1, 1, 2, "2025-03-14T19:40:00+11:00", Some((50, 0))), game(2, 3, 4, "2025-03-15T13:45:00+11:00", Some((90, 30)))];
+        let entries = ladder(&[1, 2, 3, 4], &names, &games, LadderPoints { win: 4, draw: 2 });
+        let order: Vec<_> = entries.iter().map(|e| (e.team_id, e.percentage)).collect();
+        assert_eq!(order, [(1, None), (3, Some(300.0)), (4, Some(100.0 / 3.0)), (2, Some(0.0))]);
+    }
+
+    #[test]
+    fn picks_are_hidden_until_the_game_locks() {
+        let games = vec![game(1, 1, 2, "2025-03-14T19:40:00+11:00", None), game(2, 3, 4, "2025-03-15T13:45:00+11:00", None)];
+        let tips = [tip(1, 1, 1, None), tip(2, 1, 2, None), tip(1, 2, 3, None), tip(2, 2, 3, None)];
+        let tippers = [(1, "Sam".to_string()), (2, "Alex".to_string()), (3, "Jo".to_string())];
+        let now = DateTime::parse_from_rfc3339("2025-03-14T20:00:00+11:00").unwrap();
+        let breakdown = game_breakdown(&TippingConfig::default(), &round(1, "2025-03-14", "2025-03-16"), games, &tips,
+                                       &tippers, &HashMap::new(), now);
+
+        let counts = |g: &GameBreakdown| (g.home_picks, g.away_picks, g.untipped);
+        assert_eq!(counts(&breakdown[0]), (Some(1), Some(1), Some(1)));
+        assert_eq!(counts(&breakdown[1]), (None, None, None));
+    }
+}
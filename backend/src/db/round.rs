@@ -27,14 +27,16 @@ use kelpie_models::round::Round;
 use log::error;
 use sqlx::postgres::PgRow;
 use sqlx::{PgConnection, Row};
+use std::collections::HashMap;
 
-pub(crate) async fn insert(pool: &mut PgConnection, round_number: i32,
+pub(crate) async fn insert(pool: &mut PgConnection, season_id: i32, round_number: i32,
                            start_date: NaiveDate, end_date: NaiveDate,
                            bonus_points: i32 ) -> Result<Round, sqlx::Error> {
     let result = sqlx::query(
-        r#"INSERT INTO rounds (round_number, start_date, end_date, bonus_points)
-                VALUES ($1, $2, $3, $4) RETURNING round_id"#,
+        r#"INSERT INTO rounds (season_id, round_number, start_date, end_date, bonus_points)
+                VALUES ($1, $2, $3, $4, $5) RETURNING round_id"#,
     )
+        .bind(season_id)
         .bind(round_number)
         .bind(start_date)
         .bind(end_date)
@@ -45,7 +47,7 @@ pub(crate) async fn insert(pool: &mut PgConnection, round_number: i32,
     match result {
         Ok(row) => {
             let id = row.get::<i32, _>(0);
-            Ok(Round{round_id: Some(id), round_number, start_date, end_date, bonus_points})
+            Ok(Round{round_id: Some(id), season_id, round_number, start_date, end_date, bonus_points})
         }
         Err(e) => {
             error!("Error inserting round: {}", e);
@@ -96,16 +98,17 @@ pub(crate) async fn delete(pool: &mut PgConnection, id: i32) -> Result<u64, sqlx
 
 fn from_row(row: PgRow) -> Round {
     let round_id = row.get::<i32, _>(0);
-    let round_number = row.get::<i32, _>(1);
-    let start_date = row.get::<NaiveDate, _>(2);
-    let end_date = row.get::<NaiveDate, _>(3);
-    let bonus_points = row.get::<i32, _>(4);
-    Round{round_id: Some(round_id), round_number, start_date, end_date, bonus_points}
+    let season_id = row.get::<i32, _>(1);
+    let round_number = row.get::<i32, _>(2);
+    let start_date = row.get::<NaiveDate, _>(3);
+    let end_date = row.get::<NaiveDate, _>(4);
+    let bonus_points = row.get::<i32, _>(5);
+    Round{round_id: Some(round_id), season_id, round_number, start_date, end_date, bonus_points}
 }
 
 pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Round>, sqlx::Error> {
     let result = sqlx::query(
-        r#"SELECT round_id, round_number, start_date, end_date, bonus_points
+        r#"SELECT round_id, season_id, round_number, start_date, end_date, bonus_points
                 FROM rounds WHERE round_id=$1"#
         )
         .bind(id)
@@ -126,11 +129,12 @@ pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Round
     }
 }
 
-pub(crate) async fn get_last_round (pool: &mut PgConnection) -> Result<Option<Round>, sqlx::Error> {
+pub(crate) async fn get_last_round (pool: &mut PgConnection, season_id: i32) -> Result<Option<Round>, sqlx::Error> {
     let result = sqlx::query(
-        r#"SELECT round_id, round_number, start_date, end_date, bonus_points
-                FROM rounds ORDER BY round_number DESC"#
+        r#"SELECT round_id, season_id, round_number, start_date, end_date, bonus_points
+                FROM rounds WHERE season_id = $1 ORDER BY round_number DESC"#
     )
+        .bind(season_id)
         .fetch_optional(pool)
         .await;
 
@@ -148,8 +152,39 @@ pub(crate) async fn get_last_round (pool: &mut PgConnection) -> Result<Option<Ro
     }
 }
 
-pub(crate) async fn round_with_number_exists (pool: &mut PgConnection, round_number: i32) -> Result<bool, sqlx::Error> {
-    let result = sqlx::query("SELECT count(*) FROM rounds WHERE round_number = $1 LIMIT 1")
+/// The round a tipper should be tipping on `today`, from the latest season they take part in.
+///
+/// That is the earliest round which hasn't finished yet, or the last round once the season is over.
+pub(crate) async fn get_current(pool: &mut PgConnection, tipper_id: i32, today: NaiveDate) -> Result<Option<Round>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT round_id, season_id, round_number, start_date, end_date, bonus_points
+                FROM rounds
+                WHERE season_id = (SELECT seasons.season_id FROM seasons
+                                    JOIN season_tippers ON season_tippers.season_id = seasons.season_id
+                                    WHERE season_tippers.tipper_id = $1
+                                    ORDER BY seasons.start_date DESC LIMIT 1)
+                ORDER BY end_date < $2,
+                         CASE WHEN end_date >= $2 THEN start_date END,
+                         start_date DESC
+                LIMIT 1"#
+    )
+        .bind(tipper_id)
+        .bind(today)
+        .fetch_optional(pool)
+        .await;
+
+    match result {
+        Ok(row) => Ok(row.map(from_row)),
+        Err(e) => {
+            error!("Error getting current round: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn round_with_number_exists (pool: &mut PgConnection, season_id: i32, round_number: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("SELECT count(*) FROM rounds WHERE season_id = $1 AND round_number = $2 LIMIT 1")
+        .bind(season_id)
         .bind(round_number)
         .fetch_one(pool)
         .await;
@@ -165,10 +200,11 @@ pub(crate) async fn round_with_number_exists (pool: &mut PgConnection, round_num
         }
     }
 }
-pub(crate) async fn round_with_number_used (pool: &mut PgConnection, round_id: i32, round_number: i32) -> Result<bool, sqlx::Error> {
+pub(crate) async fn round_with_number_used (pool: &mut PgConnection, round_id: i32, season_id: i32, round_number: i32) -> Result<bool, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT count(*) FROM rounds WHERE round_id != $1 AND round_number = $2 LIMIT 1")
+        "SELECT count(*) FROM rounds WHERE round_id != $1 AND season_id = $2 AND round_number = $3 LIMIT 1")
         .bind(round_id)
+        .bind(season_id)
         .bind(round_number)
         .fetch_one(pool)
         .await;
@@ -185,11 +221,14 @@ pub(crate) async fn round_with_number_used (pool: &mut PgConnection, round_id: i
     }
 }
 
-pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Round>, sqlx::Error> {
+/// All rounds, or only those in `season_id` when it is given.
+pub(crate) async fn get_all(pool: &mut PgConnection, season_id: Option<i32>) -> Result<Vec<Round>, sqlx::Error> {
     let result = sqlx::query(
-        r#"SELECT round_id, round_number, start_date, end_date, bonus_points
-                FROM rounds ORDER BY round_number"#
+        r#"SELECT round_id, season_id, round_number, start_date, end_date, bonus_points
+                FROM rounds WHERE ($1::INT IS NULL OR season_id = $1)
+                ORDER BY start_date, round_number"#
         )
+        .bind(season_id)
         .fetch_all(pool)
         .await;
 
@@ -207,3 +246,61 @@ pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Round>, sqlx:
         }
     }
 }
+
+/// The teams with a bye in the round.
+pub(crate) async fn get_byes(pool: &mut PgConnection, round_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query("SELECT team_id FROM round_byes WHERE round_id = $1 ORDER BY team_id")
+        .bind(round_id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting round byes: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Replace the teams with a bye in the round.
+pub(crate) async fn set_byes(pool: &mut PgConnection, round_id: i32, team_ids: &[i32]) -> Result<(), sqlx::Error> {
+    let result = sqlx::query("DELETE FROM round_byes WHERE round_id = $1")
+        .bind(round_id)
+        .execute(&mut *pool)
+        .await;
+    if let Err(e) = result {
+        error!("Error clearing round byes: {}", e);
+        return Err(e);
+    }
+    for team_id in team_ids {
+        let result = sqlx::query("INSERT INTO round_byes (round_id, team_id) VALUES ($1, $2)")
+            .bind(round_id)
+            .bind(team_id)
+            .execute(&mut *pool)
+            .await;
+        if let Err(e) = result {
+            error!("Error adding round bye: {}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// How many byes each team has had in the season's rounds, teams without a bye are left out.
+pub(crate) async fn get_bye_counts(pool: &mut PgConnection, season_id: i32) -> Result<HashMap<i32, usize>, sqlx::Error> {
+    let result = sqlx::query(
+        r#"SELECT round_byes.team_id, COUNT(*) FROM round_byes
+                JOIN rounds ON rounds.round_id = round_byes.round_id
+                WHERE rounds.season_id = $1
+                GROUP BY round_byes.team_id"#)
+        .bind(season_id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(|row| (row.get::<i32, _>(0), row.get::<i64, _>(1) as usize)).collect()),
+        Err(e) => {
+            error!("Error counting byes: {}", e);
+            Err(e)
+        }
+    }
+}
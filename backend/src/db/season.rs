@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+#![allow(unused)]
+use chrono::NaiveDate;
+use kelpie_models::scoring::ScoringRules;
+use kelpie_models::season::Season;
+use log::error;
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{PgConnection, Row};
+
+pub(crate) async fn insert(pool: &mut PgConnection, name: String,
+                           start_date: NaiveDate, end_date: NaiveDate) -> Result<Season, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT INTO seasons (name, start_date, end_date) VALUES ($1, $2, $3) RETURNING season_id")
+        .bind(name.clone())
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_one(pool)
+        .await;
+    match result {
+        Ok(row) => {
+            let id = row.get::<i32, _>(0);
+            Ok(Season { id: Some(id), name, start_date, end_date })
+        }
+        Err(e) => {
+            error!("Error inserting season: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String,
+                           start_date: NaiveDate, end_date: NaiveDate) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE seasons SET name=$1, start_date=$2, end_date=$3 WHERE season_id = $4")
+        .bind(name.clone())
+        .bind(start_date)
+        .bind(end_date)
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error updating season: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn delete(pool: &mut PgConnection, id: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM seasons WHERE season_id = $1")
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error deleting season: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn from_row(row: PgRow) -> Season {
+    let id = row.get::<i32, _>(0);
+    let name = row.get::<String, _>(1);
+    let start_date = row.get::<NaiveDate, _>(2);
+    let end_date = row.get::<NaiveDate, _>(3);
+    Season { id: Some(id), name, start_date, end_date }
+}
+
+pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Season>, sqlx::Error> {
+    let result = sqlx::query(
+        "SELECT season_id, name, start_date, end_date FROM seasons WHERE season_id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.map(from_row)),
+        Err(e) => {
+            error!("Error getting season: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Season>, sqlx::Error> {
+    let result = sqlx::query(
+        "SELECT season_id, name, start_date, end_date FROM seasons ORDER BY start_date")
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(from_row).collect()),
+        Err(e) => {
+            error!("Error getting all seasons: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn get_team_ids(pool: &mut PgConnection, id: i32) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query("SELECT team_id FROM season_teams WHERE season_id = $1 ORDER BY team_id")
+        .bind(id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting season teams: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Replace the teams taking part in the season.
+pub(crate) async fn set_team_ids(pool: &mut PgConnection, id: i32, team_ids: &[i32]) -> Result<(), sqlx::Error> {
+    let result = sqlx::query("DELETE FROM season_teams WHERE season_id = $1")
+        .bind(id)
+        .execute(&mut *pool)
+        .await;
+    if let Err(e) = result {
+        error!("Error clearing season teams: {}", e);
+        return Err(e);
+    }
+    for team_id in team_ids {
+        let result = sqlx::query("INSERT INTO season_teams (season_id, team_id) VALUES ($1, $2)")
+            .bind(id)
+            .bind(team_id)
+            .execute(&mut *pool)
+            .await;
+        if let Err(e) = result {
+            error!("Error adding season team: {}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) async fn get_tipper_ids(pool: &mut PgConnection, id: i32) -> Result<Vec<i32>, sqlx::Error> {
+    let result = sqlx::query("SELECT tipper_id FROM season_tippers WHERE season_id = $1 ORDER BY tipper_id")
+        .bind(id)
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(|row| row.get::<i32, _>(0)).collect()),
+        Err(e) => {
+            error!("Error getting season tippers: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Replace the tippers enrolled in the season.
+pub(crate) async fn set_tipper_ids(pool: &mut PgConnection, id: i32, tipper_ids: &[i32]) -> Result<(), sqlx::Error> {
+    let result = sqlx::query("DELETE FROM season_tippers WHERE season_id = $1")
+        .bind(id)
+        .execute(&mut *pool)
+        .await;
+    if let Err(e) = result {
+        error!("Error clearing season tippers: {}", e);
+        return Err(e);
+    }
+    for tipper_id in tipper_ids {
+        let result = sqlx::query("INSERT INTO season_tippers (season_id, tipper_id) VALUES ($1, $2)")
+            .bind(id)
+            .bind(tipper_id)
+            .execute(&mut *pool)
+            .await;
+        if let Err(e) = result {
+            error!("Error adding season tipper: {}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Enrol a tipper in the season, if they aren't already.
+pub(crate) async fn add_tipper(pool: &mut PgConnection, id: i32, tipper_id: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("INSERT INTO season_tippers (season_id, tipper_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(id)
+        .bind(tipper_id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error adding season tipper: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn get_scoring_rules(pool: &mut PgConnection, id: i32) -> Result<Option<ScoringRules>, sqlx::Error> {
+    let result = sqlx::query("SELECT scoring_rules FROM seasons WHERE season_id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.map(|row| row.get::<Json<ScoringRules>, _>(0).0)),
+        Err(e) => {
+            error!("Error getting scoring rules: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn set_scoring_rules(pool: &mut PgConnection, id: i32, rules: &ScoringRules) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE seasons SET scoring_rules = $1 WHERE season_id = $2")
+        .bind(Json(rules))
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error setting scoring rules: {}", e);
+            Err(e)
+        }
+    }
+}
@@ -28,17 +28,18 @@ use rocket_db_pools::sqlx;
 use rocket_db_pools::sqlx::PgConnection;
 use rocket_db_pools::sqlx::Row;
 
-pub(crate) async fn insert(pool: &mut PgConnection, name: String, nickname: String) -> Result<Team, sqlx::Error> {
+pub(crate) async fn insert(pool: &mut PgConnection, name: String, nickname: String, home_venue_id: Option<i32>) -> Result<Team, sqlx::Error> {
     let result =
-        sqlx::query("INSERT INTO teams (name, nickname) VALUES ($1, $2) RETURNING team_id")
+        sqlx::query("INSERT INTO teams (name, nickname, home_venue_id) VALUES ($1, $2, $3) RETURNING team_id")
             .bind(name.clone())
             .bind(nickname.clone())
+            .bind(home_venue_id)
             .fetch_one(pool)
             .await;
     match result {
         Ok(row) => {
             let id = row.get::<i32, _>(0);
-            Ok(Team { id: Some(id), name, nickname, can_delete: Some(true) })
+            Ok(Team { id: Some(id), name, nickname, home_venue_id, can_delete: Some(true) })
         }
         Err(e) => {
             error!("Error inserting team: {}", e);
@@ -47,10 +48,11 @@ pub(crate) async fn insert(pool: &mut PgConnection, name: String, nickname: Stri
     }
 }
 
-pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String, nickname: String) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("UPDATE teams SET name=$1, nickname=$2 WHERE team_id = $3")
+pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String, nickname: String, home_venue_id: Option<i32>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE teams SET name=$1, nickname=$2, home_venue_id=$3 WHERE team_id = $4")
         .bind(name.clone())
         .bind(nickname.clone())
+        .bind(home_venue_id)
         .bind(id)
         .execute(pool)
         .await;
@@ -81,18 +83,19 @@ fn from_row(row: &sqlx::postgres::PgRow) -> Team {
     let id = row.get::<i32, _>(0);
     let name = row.get::<String, _>(1);
     let nickname = row.get::<String, _>(2);
+    let home_venue_id = row.get::<Option<i32>, _>(3);
     // can_delete is optional, only present in some queries
-    let can_delete = if row.len() > 3 {
-        Some(!row.get::<bool, _>(3))
+    let can_delete = if row.len() > 4 {
+        Some(!row.get::<bool, _>(4))
     } else {
         None
     };
-    Team { id: Some(id), name, nickname, can_delete }
+    Team { id: Some(id), name, nickname, home_venue_id, can_delete }
 }
 
 pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Team>, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT team_id, name, nickname FROM teams WHERE team_id = $1")
+        "SELECT team_id, name, nickname, home_venue_id FROM teams WHERE team_id = $1")
         .bind(id)
         .fetch_optional(pool)
         .await;
@@ -108,11 +111,15 @@ pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Team>
     }
 }
 
-pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Team>, sqlx::Error> {
+/// All teams, or only those taking part in `season_id` when it is given.
+pub(crate) async fn get_all(pool: &mut PgConnection, season_id: Option<i32>) -> Result<Vec<Team>, sqlx::Error> {
     let result = sqlx::query(
-        "SELECT teams.*, exists(SELECT 1 FROM games WHERE teams.team_id = games.away_team_id
+        "SELECT teams.team_id, teams.name, teams.nickname, teams.home_venue_id, exists(SELECT 1 FROM games WHERE teams.team_id = games.away_team_id
                   OR teams.team_id = games.home_team_id) AS prohibit_delete FROM teams
+                  WHERE ($1::INT IS NULL
+                     OR teams.team_id IN (SELECT team_id FROM season_teams WHERE season_id = $1))
                   ORDER BY teams.name")
+        .bind(season_id)
         .fetch_all(pool)
         .await;
     match result {
@@ -41,10 +41,11 @@ pub(crate) async fn delete_by_round(pool: &mut PgConnection, round_id: i32) -> R
 }
 
 pub(crate) async fn insert(pool: &mut PgConnection, tip: &Tip) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("INSERT INTO tips (tipper_id, game_id, team_id) VALUES ($1, $2, $3)")
+    let result = sqlx::query("INSERT INTO tips (tipper_id, game_id, team_id, margin) VALUES ($1, $2, $3, $4)")
         .bind(tip.tipper_id)
         .bind(tip.game_id)
         .bind(tip.team_id)
+        .bind(tip.margin)
         .execute(pool)
         .await;
     match result {
@@ -58,10 +59,11 @@ pub(crate) async fn insert(pool: &mut PgConnection, tip: &Tip) -> Result<u64, sq
 
 pub(crate) async fn update(pool: &mut PgConnection, tip: &Tip,
 ) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("UPDATE tips SET team_id = $1 WHERE tipper_id = $2 AND game_id = $3")
+    let result = sqlx::query("UPDATE tips SET team_id = $1, margin = $4 WHERE tipper_id = $2 AND game_id = $3")
         .bind(tip.team_id)
         .bind(tip.tipper_id)
         .bind(tip.game_id)
+        .bind(tip.margin)
         .execute(pool)
         .await;
 
@@ -79,7 +81,10 @@ pub(crate) async fn get_by_tipper_and_round(
     tipper_id: i32,
     round_id: i32,
 ) -> Result<Vec<Tip>, sqlx::Error> {
-    let result = sqlx::query("SELECT tipper_id, game_id, team_id FROM tips WHERE tipper_id = $1 AND game_id IN (SELECT game_id FROM games WHERE round_id = $2)")
+    let result = sqlx::query(
+        "SELECT tips.tipper_id, tips.game_id, tips.team_id, tips.margin FROM tips \
+         JOIN games ON tips.game_id = games.game_id \
+         WHERE tips.tipper_id = $1 AND games.round_id = $2 ORDER BY games.kick_off")
         .bind(tipper_id)
         .bind(round_id)
         .fetch_all(pool)
@@ -99,11 +104,31 @@ pub(crate) async fn get_by_tipper_and_round(
     }
 }
 
+/// Every tipper's tips for a round.
+pub(crate) async fn get_by_round(pool: &mut PgConnection, round_id: i32) -> Result<Vec<Tip>, sqlx::Error> {
+    let result = sqlx::query(
+        "SELECT tips.tipper_id, tips.game_id, tips.team_id, tips.margin FROM tips \
+         JOIN games ON tips.game_id = games.game_id \
+         WHERE games.round_id = $1")
+        .bind(round_id)
+        .fetch_all(pool)
+        .await;
+
+    match result {
+        Ok(rows) => Ok(rows.into_iter().map(from_row).collect()),
+        Err(e) => {
+            log::error!("Error fetching tips for round: {}", e);
+            Err(e)
+        }
+    }
+}
+
 fn from_row(row: PgRow) -> Tip {
     Tip {
         tipper_id: row.get::<i32, _>(0),
         game_id: row.get::<i32, _>(1),
         team_id: Some(row.get::<i32, _>(2)),
+        margin: row.get::<Option<i32>, _>(3),
     }
 }
 
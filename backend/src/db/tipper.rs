@@ -29,16 +29,17 @@ use rocket_db_pools::sqlx::PgConnection;
 use rocket_db_pools::sqlx::Row;
 use sqlx::postgres::PgRow;
 
-pub(crate) async fn insert(pool: &mut PgConnection, name: String, email: String) -> Result<Tipper, sqlx::Error> {
-    let result = sqlx::query("INSERT INTO tippers (name, email) VALUES ($1, $2) RETURNING tipper_id")
+pub(crate) async fn insert(pool: &mut PgConnection, name: String, email: String, is_admin: bool) -> Result<Tipper, sqlx::Error> {
+    let result = sqlx::query("INSERT INTO tippers (name, email, is_admin) VALUES ($1, $2, $3) RETURNING tipper_id")
         .bind(name.clone())
         .bind(email.clone())
+        .bind(is_admin)
         .fetch_one(pool)
         .await;
     match result {
         Ok(row) => {
             let id = row.get::<i32, _>(0);
-            Ok(Tipper { id: Some(id), name, email})
+            Ok(Tipper { id: Some(id), name, email, password: None, is_admin })
         },
         Err(e) => {
             error!("Error inserting tipper: {}", e);
@@ -47,10 +48,11 @@ pub(crate) async fn insert(pool: &mut PgConnection, name: String, email: String)
     }
 }
 
-pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String, email: String) -> Result<u64, sqlx::Error> {
-    let result = sqlx::query("UPDATE tippers SET name=$1, email=$2 WHERE tipper_id = $3")
+pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String, email: String, is_admin: bool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE tippers SET name=$1, email=$2, is_admin=$3 WHERE tipper_id = $4")
         .bind(name.clone())
         .bind(email.clone())
+        .bind(is_admin)
         .bind(id)
         .execute(pool)
         .await;
@@ -80,7 +82,7 @@ pub(crate) async fn delete(pool: &mut PgConnection, id: i32) -> Result<u64, sqlx
 }
 
 pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Tipper>, sqlx::Error> {
-    let result = sqlx::query("SELECT tipper_id, name, email FROM tippers WHERE tipper_id = $1")
+    let result = sqlx::query("SELECT tipper_id, name, email, is_admin FROM tippers WHERE tipper_id = $1")
         .bind(id)
         .fetch_optional(pool)
         .await;
@@ -99,17 +101,99 @@ pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Tippe
     }
 }
 
+pub(crate) async fn set_password_hash(pool: &mut PgConnection, id: i32, password_hash: String) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE tippers SET password_hash = $1 WHERE tipper_id = $2")
+        .bind(password_hash)
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error setting tipper password: {}", e);
+            Err(e)
+        },
+    }
+}
+
+/// Whether the tipper is an admin, `None` if there is no such tipper.
+pub(crate) async fn is_admin(pool: &mut PgConnection, id: i32) -> Result<Option<bool>, sqlx::Error> {
+    let result = sqlx::query("SELECT is_admin FROM tippers WHERE tipper_id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.map(|row| row.get::<bool, _>(0))),
+        Err(e) => {
+            error!("Error checking tipper is admin: {}", e);
+            Err(e)
+        },
+    }
+}
+
+pub(crate) async fn admin_exists(pool: &mut PgConnection) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("SELECT EXISTS (SELECT 1 FROM tippers WHERE is_admin)")
+        .fetch_one(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.get::<bool, _>(0)),
+        Err(e) => {
+            error!("Error checking for admins: {}", e);
+            Err(e)
+        },
+    }
+}
+
+/// The tipper with the given email along with their password hash, if they have one.
+pub(crate) async fn get_for_login(pool: &mut PgConnection, email: &str) -> Result<Option<(Tipper, Option<String>)>, sqlx::Error> {
+    let result = sqlx::query("SELECT tipper_id, name, email, is_admin, password_hash FROM tippers WHERE lower(email) = lower($1)")
+        .bind(email)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.map(|row| {
+            let password_hash = row.get::<Option<String>, _>(4);
+            (from_row(row), password_hash)
+        })),
+        Err(e) => {
+            error!("Error getting tipper for login: {}", e);
+            Err(e)
+        },
+    }
+}
+
+/// The id of the tipper with the given email, ignoring case.
+pub(crate) async fn get_id_by_email(pool: &mut PgConnection, email: &str) -> Result<Option<i32>, sqlx::Error> {
+    let result = sqlx::query("SELECT tipper_id FROM tippers WHERE lower(email) = lower($1)")
+        .bind(email)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => Ok(row.map(|row| row.get::<i32, _>(0))),
+        Err(e) => {
+            error!("Error getting tipper by email: {}", e);
+            Err(e)
+        },
+    }
+}
+
 fn from_row(row: PgRow) -> Tipper {
     let tipper_id = row.get::<i32, _>(0);
     let name = row.get::<String, _>(1);
     let email = row.get::<String, _>(2);
-    let tipper = Tipper { id: Some(tipper_id), name, email };
+    let is_admin = row.get::<bool, _>(3);
+    let tipper = Tipper { id: Some(tipper_id), name, email, password: None, is_admin };
     tipper
 }
 
-pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Tipper>, sqlx::Error> {
+/// All tippers, or only those enrolled in `season_id` when it is given.
+pub(crate) async fn get_all(pool: &mut PgConnection, season_id: Option<i32>) -> Result<Vec<Tipper>, sqlx::Error> {
     let result =
-        sqlx::query("SELECT tipper_id, name, email FROM tippers ORDER BY name")
+        sqlx::query("SELECT tipper_id, name, email, is_admin FROM tippers \
+                     WHERE ($1::INT IS NULL \
+                        OR tipper_id IN (SELECT tipper_id FROM season_tippers WHERE season_id = $1)) \
+                     ORDER BY name")
+            .bind(season_id)
             .fetch_all(pool)
             .await;
     match result {
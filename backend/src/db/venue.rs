@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+#![allow(unused)]
+use kelpie_models::venue::Venue;
+use log::error;
+use rocket_db_pools::sqlx;
+use rocket_db_pools::sqlx::PgConnection;
+use rocket_db_pools::sqlx::Row;
+
+pub(crate) async fn insert(pool: &mut PgConnection, name: String) -> Result<Venue, sqlx::Error> {
+    let result =
+        sqlx::query("INSERT INTO venues (name) VALUES ($1) RETURNING venue_id")
+            .bind(name.clone())
+            .fetch_one(pool)
+            .await;
+    match result {
+        Ok(row) => {
+            let id = row.get::<i32, _>(0);
+            Ok(Venue { id: Some(id), name, can_delete: Some(true) })
+        }
+        Err(e) => {
+            error!("Error inserting venue: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn update(pool: &mut PgConnection, id: i32, name: String) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("UPDATE venues SET name=$1 WHERE venue_id = $2")
+        .bind(name.clone())
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error updating venue: {}", e);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) async fn delete(pool: &mut PgConnection, id: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM venues WHERE venue_id = $1")
+        .bind(id)
+        .execute(pool)
+        .await;
+    match result {
+        Ok(result) => Ok(result.rows_affected()),
+        Err(e) => {
+            error!("Error deleting venue: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn from_row(row: &sqlx::postgres::PgRow) -> Venue {
+    let id = row.get::<i32, _>(0);
+    let name = row.get::<String, _>(1);
+    // can_delete is optional, only present in some queries
+    let can_delete = if row.len() > 2 {
+        Some(!row.get::<bool, _>(2))
+    } else {
+        None
+    };
+    Venue { id: Some(id), name, can_delete }
+}
+
+pub(crate) async fn get(pool: &mut PgConnection, id: i32) -> Result<Option<Venue>, sqlx::Error> {
+    let result = sqlx::query("SELECT venue_id, name FROM venues WHERE venue_id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+    match result {
+        Ok(row) => match row {
+            Some(row) => Ok(Some(from_row(&row))),
+            None => Ok(None),
+        },
+        Err(e) => {
+            error!("Error getting venue: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// All venues, a venue can't be deleted while a team or game uses it.
+pub(crate) async fn get_all(pool: &mut PgConnection) -> Result<Vec<Venue>, sqlx::Error> {
+    let result = sqlx::query(
+        "SELECT venue_id, name,
+                exists(SELECT 1 FROM teams WHERE teams.home_venue_id = venues.venue_id)
+                OR exists(SELECT 1 FROM games WHERE games.venue_id = venues.venue_id) AS prohibit_delete
+         FROM venues ORDER BY name")
+        .fetch_all(pool)
+        .await;
+    match result {
+        Ok(rows) => {
+            let venues = rows.into_iter().map(|row| from_row(&row)).collect();
+            Ok(venues)
+        }
+        Err(e) => {
+            error!("Error getting all venues: {}", e);
+            Err(e)
+        }
+    }
+}
@@ -28,9 +28,10 @@ mod db;
 mod util;
 
 use crate::routes::tippers;
-use crate::routes::{reports, rounds, teams, tips};
+use crate::routes::{auth, games, import, mail, reports, rounds, seasons, teams, tips, venues};
 use rocket::fs::{relative, FileServer};
 
+use crate::util::config;
 use crate::util::logging::setup_logging;
 use rocket_db_pools::{sqlx, Database};
 
@@ -45,11 +46,23 @@ fn rocket() -> _ {
 
     let rocket = rocket::build()
         .attach(DbTips::init())
+        .attach(config::stage())
+        .attach(db::migrations::stage())
+        .attach(util::auth::bootstrap())
+        .attach(util::results_provider::stage())
+        .attach(util::reminders::stage())
+        .attach(util::round_summary::stage())
         .mount("/", FileServer::from(relative!("./static")))
         .mount("/", tippers::routes())
         .mount("/", teams::routes())
+        .mount("/", venues::routes())
         .mount("/", rounds::routes())
+        .mount("/", games::routes())
+        .mount("/", import::routes())
+        .mount("/", seasons::routes())
         .mount("/", tips::routes())
+        .mount("/", auth::routes())
+        .mount("/", mail::routes())
         .mount("/reports/", reports::routes());
     rocket
 }
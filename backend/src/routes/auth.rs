@@ -0,0 +1,76 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::tipper;
+use crate::util::auth::{self, AuthTipper};
+use crate::util::ApiError;
+use crate::DbTips;
+use kelpie_models::tipper::Tipper;
+use rocket::http::CookieJar;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::Route;
+use rocket_db_pools::Connection;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![login, logout, me]
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Login {
+    email: String,
+    password: String,
+}
+
+#[post("/api/login", data = "<login>")]
+pub(crate) async fn login(login: Json<Login>, cookies: &CookieJar<'_>, mut pool: Connection<DbTips>,
+) -> Result<Json<Tipper>, ApiError> {
+    match tipper::get_for_login(&mut **pool, login.email.trim()).await? {
+        Some((tipper, Some(password_hash))) if auth::verify_password(&login.password, &password_hash) => {
+            auth::start_session(cookies, tipper.id.unwrap_or_default());
+            Ok(Json(tipper))
+        }
+        _ => Err(ApiError::Unauthorized("Incorrect email or password".to_string())),
+    }
+}
+
+#[post("/api/logout")]
+pub(crate) async fn logout(cookies: &CookieJar<'_>) -> &'static str {
+    auth::end_session(cookies);
+    "OK"
+}
+
+/// The logged in tipper, so the app can pick up an existing session.
+#[get("/api/me")]
+pub(crate) async fn me(auth: AuthTipper, cookies: &CookieJar<'_>, mut pool: Connection<DbTips>,
+) -> Result<Json<Tipper>, ApiError> {
+    match tipper::get(&mut **pool, auth.tipper_id).await? {
+        Some(tipper) => Ok(Json(tipper)),
+        None => {
+            // The tipper has been deleted since logging in
+            auth::end_session(cookies);
+            Err(ApiError::Unauthorized("Not logged in".to_string()))
+        }
+    }
+}
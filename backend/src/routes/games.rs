@@ -0,0 +1,67 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::game;
+use crate::util::auth::AdminUser;
+use crate::util::config::TippingConfig;
+use crate::util::ApiError;
+use crate::DbTips;
+use kelpie_models::game::Game;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::{Route, State};
+use rocket_db_pools::Connection;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![set_result]
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct GameResult {
+    pub(crate) home_team_score: Option<i32>,
+    pub(crate) away_team_score: Option<i32>,
+}
+
+/// Enter or clear the score of a single game without touching the rest of its round.
+#[patch("/api/games/<id>/result", data = "<result>")]
+pub(crate) async fn set_result(_admin: AdminUser, id: i32, result: Json<GameResult>, mut pool: Connection<DbTips>,
+                               config: &State<TippingConfig>,
+) -> Result<Json<Game>, ApiError> {
+    match (result.home_team_score, result.away_team_score) {
+        (Some(home), Some(away)) if home < 0 || away < 0 => {
+            return Err(ApiError::Invalid("Scores can't be negative".to_string()));
+        }
+        (Some(_), None) | (None, Some(_)) => {
+            return Err(ApiError::Invalid("Enter both scores, or neither to clear the result".to_string()));
+        }
+        _ => {}
+    }
+    match game::set_result(&mut **pool, id, result.home_team_score, result.away_team_score).await? {
+        0 => Err(ApiError::NotFound(format!("Game with ID {} not found", id))),
+        _ => match game::get(&mut **pool, id).await? {
+            Some(game) => Ok(Json(config.localise(vec![game]).remove(0))),
+            None => Err(ApiError::NotFound(format!("Game with ID {} not found", id))),
+        },
+    }
+}
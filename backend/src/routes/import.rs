@@ -0,0 +1,65 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::season;
+use crate::util::auth::AdminUser;
+use crate::util::config::TippingConfig;
+use crate::util::import::{self, ImportReport};
+use crate::util::{upload, ApiError};
+use crate::DbTips;
+use rocket::data::Data;
+use rocket::http::ContentType;
+use rocket::serde::json::Json;
+use rocket::{Route, State};
+use rocket_db_pools::Connection;
+use sqlx::Acquire;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![import_games]
+}
+
+/// Import fixtures and results for a season from a CSV or JSON file.
+///
+/// Unless `dry_run=false` is given this only reports what would change.
+#[post("/api/import?<season_id>&<dry_run>", data = "<file>")]
+pub(crate) async fn import_games(_admin: AdminUser, season_id: i32, dry_run: Option<bool>, content_type: &ContentType,
+                                 file: Data<'_>, mut pool: Connection<DbTips>, config: &State<TippingConfig>,
+) -> Result<Json<ImportReport>, ApiError> {
+    if season::get(&mut **pool, season_id).await?.is_none() {
+        return Err(ApiError::NotFound(format!("Season with ID {} not found", season_id)));
+    }
+    let text = upload::read_text(file).await?;
+    let rows = import::parse(&text, content_type.is_json()).map_err(ApiError::Invalid)?;
+
+    let mut tx = pool.begin().await?;
+    let mut report = import::plan(&mut tx, config, season_id, &rows).await?;
+    if dry_run.unwrap_or(true) || !report.errors.is_empty() {
+        return Ok(Json(report));
+    }
+    import::apply(&mut tx, config, season_id, &mut report).await?;
+    if !report.errors.is_empty() {
+        return Ok(Json(report));
+    }
+    tx.commit().await?;
+    Ok(Json(report))
+}
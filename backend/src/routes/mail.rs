@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::mail::{self, SentMail};
+use crate::db::{reporting, tipper};
+use crate::util::auth::AdminUser;
+use crate::util::config::TippingConfig;
+use crate::util::mail::{transport, Email};
+use crate::util::{round_summary, ApiError};
+use crate::DbTips;
+use rocket::serde::json::Json;
+use rocket::{Route, State};
+use rocket_db_pools::Connection;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![history, preview_round_summary, send_round_summary]
+}
+
+/// Emails sent to tippers, for one round when `round_id` is given.
+#[get("/api/mail/history?<round_id>")]
+pub(crate) async fn history(_admin: AdminUser, round_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Json<Vec<SentMail>>, ApiError> {
+    let sent = mail::get_history(&mut **pool, round_id).await?;
+    Ok(Json(sent))
+}
+
+/// The round summary email as `tipper_id` would get it, or as the admin would.
+#[get("/api/mail/round_summary/<round_id>?<tipper_id>")]
+pub(crate) async fn preview_round_summary(admin: AdminUser, round_id: i32, tipper_id: Option<i32>, mut pool: Connection<DbTips>,
+                                          config: &State<TippingConfig>,
+) -> Result<Json<Email>, ApiError> {
+    let Some(summary) = reporting::get_round_summary(&mut **pool, round_id).await? else {
+        return Err(ApiError::NotFound(format!("Round with ID {} not found", round_id)));
+    };
+    let Some(tipper) = tipper::get(&mut **pool, tipper_id.unwrap_or(admin.tipper_id)).await? else {
+        return Err(ApiError::NotFound("Tipper not found".to_string()));
+    };
+    let handlebars = round_summary::template(config.round_summary.as_ref()).map_err(ApiError::Error)?;
+    let email = round_summary::compose(&handlebars, &summary, &tipper).map_err(ApiError::Error)?;
+    Ok(Json(email))
+}
+
+/// Send the round summary now to the tippers who haven't had it, returning how many were sent.
+#[post("/api/mail/round_summary/<round_id>")]
+pub(crate) async fn send_round_summary(_admin: AdminUser, round_id: i32, mut pool: Connection<DbTips>,
+                                       config: &State<TippingConfig>,
+) -> Result<Json<usize>, ApiError> {
+    let Some(mail_config) = config.mail.as_ref() else {
+        return Err(ApiError::Invalid("Mail is not configured".to_string()));
+    };
+    if !mail::round_completed(&mut **pool, round_id).await? {
+        return Err(ApiError::Invalid("The round summary can't be sent until every game has a score".to_string()));
+    }
+    let transport = transport(mail_config).map_err(ApiError::Error)?;
+    let handlebars = round_summary::template(config.round_summary.as_ref()).map_err(ApiError::Error)?;
+    let sent = round_summary::send_summary(&mut **pool, transport.as_ref(), &handlebars, round_id).await
+        .map_err(ApiError::Error)?;
+    Ok(Json(sent))
+}
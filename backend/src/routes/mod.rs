@@ -26,4 +26,10 @@ pub(crate) mod tippers;
 pub(crate) mod teams;
 pub(crate) mod rounds;
 pub(crate) mod tips;
-pub(crate) mod reports;
\ No newline at end of file
+pub(crate) mod reports;
+pub(crate) mod seasons;
+pub(crate) mod auth;
+pub(crate) mod games;
+pub(crate) mod import;
+pub(crate) mod venues;
+pub(crate) mod mail;
\ No newline at end of file
@@ -21,19 +21,32 @@
  *      Trevor Campbell
  *
  */
-use crate::db::reporting::{get_leaderboard, get_score_by_round, LeaderboardEntry};
+use crate::db::reporting::{get_form_guide, get_game_breakdown, get_ladder, get_leaderboard, get_matrix, get_round_reports, get_score_by_round, FormGuide, GameBreakdown, LadderEntry, LeaderboardEntry, RoundReport, ScoreMatrix};
+use crate::db::{self, season};
+use crate::util::auth::AdminUser;
+use crate::util::config::{LadderPoints, TippingConfig};
+use crate::util::download::Download;
+use crate::util::export::{self, ExportFormat};
+use crate::util::pdf::{self, SheetGame};
+use crate::util::{scoring, ApiError};
 use crate::DbTips;
+use chrono::Utc;
+use rocket::http::ContentType;
 use rocket::serde::json::Json;
-use rocket::Route;
+use rocket::{Route, State};
 use rocket_db_pools::Connection;
+use sqlx::PgConnection;
+use std::collections::HashMap;
 
 pub(crate) fn routes() -> Vec<Route> {
-    routes![leaderboard, round]
+    routes![leaderboard, round, round_games, matrix, tipper, ladder, export_leaderboard, export_scores, export_tips,
+             tipping_sheet_pdf, leaderboard_pdf]
 }
 
-#[get("/leaderboard")]
-pub async fn leaderboard(mut pool: Connection<DbTips>) -> Json<Vec<LeaderboardEntry>> {
-    match get_leaderboard(&mut **pool).await {
+/// The leaderboard for a season, or across all seasons when no season is given.
+#[get("/leaderboard?<season_id>")]
+pub async fn leaderboard(season_id: Option<i32>, mut pool: Connection<DbTips>) -> Json<Vec<LeaderboardEntry>> {
+    match get_leaderboard(&mut **pool, season_id).await {
         Ok(entries) => Json(entries),
         Err(e) => {
             error!("Error fetching leaderboard: {}", e);
@@ -52,3 +65,151 @@ pub async fn round(mut pool: Connection<DbTips>, round_id: i32) -> Json<Vec<Lead
         }, // Handle errors gracefully
     }
 }
+
+/// Every round's score, bonus and running total for each tipper in a season.
+#[get("/matrix?<season_id>")]
+pub async fn matrix(season_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Json<ScoreMatrix>, ApiError> {
+    Ok(Json(get_matrix(&mut **pool, season_id).await?))
+}
+
+/// A tipper's form guide for a season, or across all seasons when no season is given.
+#[get("/tipper/<tipper_id>?<season_id>")]
+pub async fn tipper(tipper_id: i32, season_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Json<FormGuide>, ApiError> {
+    match get_form_guide(&mut **pool, tipper_id, season_id).await? {
+        Some(guide) => Ok(Json(guide)),
+        None => Err(ApiError::NotFound("Tipper not found".to_string())),
+    }
+}
+
+/// How many tippers picked each side of every game in a round once it has locked, and who got it right.
+#[get("/round/<round_id>/games")]
+pub async fn round_games(round_id: i32, config: &State<TippingConfig>, mut pool: Connection<DbTips>) -> Result<Json<Vec<GameBreakdown>>, ApiError> {
+    let now = config.local(Utc::now());
+    match get_game_breakdown(&mut **pool, config, round_id, now).await? {
+        Some(games) => Ok(Json(games)),
+        None => Err(ApiError::NotFound("Round not found".to_string())),
+    }
+}
+
+/// The team ladder for a season, up to and including round number `up_to_round` if given.
+/// `win_points` and `draw_points` override the premiership points in the config.
+#[get("/ladder?<season_id>&<up_to_round>&<win_points>&<draw_points>")]
+pub async fn ladder(season_id: Option<i32>, up_to_round: Option<i32>, win_points: Option<i64>, draw_points: Option<i64>,
+                    config: &State<TippingConfig>, mut pool: Connection<DbTips>) -> Result<Json<Vec<LadderEntry>>, ApiError> {
+    // Round numbers start again each season, so they only mean something within one
+    if up_to_round.is_some() && season_id.is_none() {
+        return Err(ApiError::Invalid("up_to_round needs a season_id".to_string()));
+    }
+    let points = LadderPoints {
+        win: win_points.unwrap_or(config.ladder.win),
+        draw: draw_points.unwrap_or(config.ladder.draw),
+    };
+    Ok(Json(get_ladder(&mut **pool, season_id, up_to_round, points).await?))
+}
+
+/// The leaderboard as a CSV file, or as a workbook with the leaderboard and a sheet of scores for
+/// each round with results. The all-time leaderboard has no round sheets.
+#[get("/export/leaderboard?<season_id>&<format>")]
+pub async fn export_leaderboard(season_id: Option<i32>, format: Option<ExportFormat>, mut pool: Connection<DbTips>,
+                                config: &State<TippingConfig>,
+) -> Result<Download, ApiError> {
+    let format = format.unwrap_or_default();
+    let name = match season_id {
+        Some(season_id) => format!("{} leaderboard", season_name(&mut **pool, season_id).await?),
+        None => "leaderboard".to_string(),
+    };
+    let mut sheets = vec![export::leaderboard_sheet(&get_leaderboard(&mut **pool, season_id).await?)];
+    if format == ExportFormat::Xlsx && season_id.is_some() {
+        let reports = get_round_reports(&mut **pool, config, season_id, None).await?;
+        sheets.extend(reports.iter().filter(|r| r.has_results).map(export::scores_sheet));
+    }
+    export::download(&name, format, &sheets).map_err(ApiError::Error)
+}
+
+/// Each tipper's score for a round, or for every round of a season with results, with a sheet per round in a workbook.
+#[get("/export/scores?<season_id>&<round_id>&<format>")]
+pub async fn export_scores(season_id: Option<i32>, round_id: Option<i32>, format: Option<ExportFormat>, mut pool: Connection<DbTips>,
+                           config: &State<TippingConfig>,
+) -> Result<Download, ApiError> {
+    let (name, reports) = round_reports(&mut **pool, config, season_id, round_id).await?;
+    let sheets: Vec<_> = reports.iter()
+        .filter(|r| r.has_results || round_id.is_some())
+        .map(export::scores_sheet)
+        .collect();
+    export::download(&format!("{} scores", name), format.unwrap_or_default(), &sheets).map_err(ApiError::Error)
+}
+
+/// Every tipper's tips for a round, or for every round of a season, with a sheet per round in a workbook.
+/// Only admins can export tips, so nobody sees the others' tips before the games lock.
+#[get("/export/tips?<season_id>&<round_id>&<format>")]
+pub async fn export_tips(_admin: AdminUser, season_id: Option<i32>, round_id: Option<i32>, format: Option<ExportFormat>, mut pool: Connection<DbTips>,
+                         config: &State<TippingConfig>,
+) -> Result<Download, ApiError> {
+    let (name, reports) = round_reports(&mut **pool, config, season_id, round_id).await?;
+    let sheets: Vec<_> = reports.iter().map(export::tips_sheet).collect();
+    export::download(&format!("{} tips", name), format.unwrap_or_default(), &sheets).map_err(ApiError::Error)
+}
+
+/// A printable sheet for tipping a round on paper.
+#[get("/pdf/tipping_sheet/<round_id>")]
+pub async fn tipping_sheet_pdf(round_id: i32, config: &State<TippingConfig>, mut pool: Connection<DbTips>) -> Result<Download, ApiError> {
+    let Some(round) = db::round::get(&mut **pool, round_id).await? else {
+        return Err(ApiError::NotFound("Round not found".to_string()));
+    };
+    let title = format!("{} round {}", season_name(&mut **pool, round.season_id).await?, round.round_number);
+    let mut games = config.localise(db::game::get_for_round(&mut **pool, round_id).await?);
+    games.sort_by_key(|g| (g.kick_off, g.game_id));
+    let margin_game_id = scoring::margin_game(&games).and_then(|g| g.game_id);
+    let teams: HashMap<i32, String> = db::team::get_all(&mut **pool, None).await?
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.name)))
+        .collect();
+    let venues: HashMap<i32, String> = db::venue::get_all(&mut **pool).await?
+        .into_iter()
+        .filter_map(|v| v.id.map(|id| (id, v.name)))
+        .collect();
+
+    let sheet: Vec<SheetGame> = games.iter().map(|g| SheetGame {
+        kick_off: g.kick_off.format("%a %-d %b %-I:%M%P").to_string(),
+        venue: g.venue_id.and_then(|id| venues.get(&id).cloned()),
+        home_team: teams.get(&g.home_team_id).cloned().unwrap_or_default(),
+        away_team: teams.get(&g.away_team_id).cloned().unwrap_or_default(),
+        margin: g.game_id.is_some() && g.game_id == margin_game_id,
+    }).collect();
+    let bytes = pdf::tipping_sheet(&title, &sheet).map_err(ApiError::Error)?;
+    Ok(Download::new(&format!("{} tipping sheet.pdf", title), ContentType::PDF, bytes))
+}
+
+/// The leaderboard for a season, or the all-time leaderboard, as a PDF for the noticeboard.
+#[get("/pdf/leaderboard?<season_id>")]
+pub async fn leaderboard_pdf(season_id: Option<i32>, config: &State<TippingConfig>, mut pool: Connection<DbTips>) -> Result<Download, ApiError> {
+    let title = match season_id {
+        Some(season_id) => format!("{} leaderboard", season_name(&mut **pool, season_id).await?),
+        None => "All time leaderboard".to_string(),
+    };
+    let entries = get_leaderboard(&mut **pool, season_id).await?;
+    let printed = format!("Printed {}", config.local(Utc::now()).format("%-d %B %Y"));
+    let bytes = pdf::leaderboard(&title, &printed, &entries).map_err(ApiError::Error)?;
+    Ok(Download::new(&format!("{}.pdf", title), ContentType::PDF, bytes))
+}
+
+async fn season_name(pool: &mut PgConnection, season_id: i32) -> Result<String, ApiError> {
+    match season::get(pool, season_id).await? {
+        Some(season) => Ok(season.name),
+        None => Err(ApiError::NotFound(format!("Season with ID {} not found", season_id))),
+    }
+}
+
+/// The reports for the round if one is given, otherwise every round of the season, and a file name for them.
+async fn round_reports(pool: &mut PgConnection, config: &TippingConfig, season_id: Option<i32>, round_id: Option<i32>)
+    -> Result<(String, Vec<RoundReport>), ApiError> {
+    let name = match (round_id, season_id) {
+        (Some(round_id), _) => match db::round::get(&mut *pool, round_id).await? {
+            Some(round) => format!("{} round {}", season_name(&mut *pool, round.season_id).await?, round.round_number),
+            None => return Err(ApiError::NotFound("Round not found".to_string())),
+        },
+        (None, Some(season_id)) => season_name(&mut *pool, season_id).await?,
+        (None, None) => return Err(ApiError::Invalid("A season_id or round_id is required".to_string())),
+    };
+    Ok((name, get_round_reports(&mut *pool, config, season_id, round_id).await?))
+}
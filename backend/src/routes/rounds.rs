@@ -21,37 +21,47 @@
  *      Trevor Campbell
  *
  */
-use crate::db::{game, round, team, tip};
+use crate::db::{game, round, season, team, tip};
+use crate::util::config::TippingConfig;
+use crate::util::auth::{AdminUser, AuthTipper};
 use crate::util::{game_allocator, ApiError};
 use crate::DbTips;
+use chrono::Utc;
 use kelpie_models::game::Game;
 use kelpie_models::round::Round;
 use kelpie_models::team::Team;
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
-use rocket::Route;
+use rocket::{Route, State};
 use rocket_db_pools::Connection;
 use sqlx::{Acquire, PgConnection};
 use std::ops::Add;
 
 pub(crate) fn routes() -> Vec<Route> {
-    routes![add_round, list, delete_round, get_round, update_round, template_round]
+    routes![add_round, list, delete_round, get_round, update_round, template_round, current_round]
 }
 
 #[derive(Serialize, Deserialize, Default)]
 pub(crate) struct NewRound {
     pub(crate) round: Round,
     pub(crate) games: Vec<Game>,
+    /// Teams in the season without a game this round
+    #[serde(default)]
+    pub(crate) byes: Vec<i32>,
+    /// Seed the template round's games were drawn with, pass it back to draw them again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) seed: Option<u64>,
 }
 
-#[get("/api/rounds")]
-pub(crate) async fn list(mut pool: Connection<DbTips>) -> Result<Json<Vec<Round>>, ApiError> {
-    let rounds = round::get_all(&mut **pool).await?;
+#[get("/api/rounds?<season_id>")]
+pub(crate) async fn list(season_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Json<Vec<Round>>, ApiError> {
+    let rounds = round::get_all(&mut **pool, season_id).await?;
     Ok(Json(rounds))
 }
 
 #[post("/api/rounds", data = "<new_round>")]
-pub(crate) async fn add_round(mut pool: Connection<DbTips>, new_round: Json<NewRound>,
+pub(crate) async fn add_round(_admin: AdminUser, mut pool: Connection<DbTips>, new_round: Json<NewRound>,
+                              config: &State<TippingConfig>,
 ) -> Result<Json<Round>, ApiError> {
     let mut tx = pool.begin().await?;
 
@@ -60,6 +70,7 @@ pub(crate) async fn add_round(mut pool: Connection<DbTips>, new_round: Json<NewR
     // Insert round
     let round = round::insert(
         &mut tx,
+        new_round.round.season_id,
         new_round.round.round_number,
         new_round.round.start_date,
         new_round.round.end_date,
@@ -73,18 +84,21 @@ pub(crate) async fn add_round(mut pool: Connection<DbTips>, new_round: Json<NewR
             round.round_id.unwrap_or(-1),
             g.home_team_id,
             g.away_team_id,
-            g.game_date,
+            config.normalise(g.kick_off),
+            g.venue_id,
             None,
             None,
         ).await?;
     }
+    round::set_byes(&mut tx, round.round_id.unwrap_or(-1), &new_round.byes).await?;
 
     tx.commit().await?;
     Ok(Json(round))
 }
 
 #[put("/api/rounds", data = "<new_round>")]
-pub(crate) async fn update_round(mut pool: Connection<DbTips>, new_round: Json<NewRound>,
+pub(crate) async fn update_round(_admin: AdminUser, mut pool: Connection<DbTips>, new_round: Json<NewRound>,
+                                 config: &State<TippingConfig>,
 ) -> Result<&'static str, ApiError> {
     let mut tx = pool.begin().await?;
 
@@ -123,7 +137,8 @@ pub(crate) async fn update_round(mut pool: Connection<DbTips>, new_round: Json<N
                     game_id,
                     game.home_team_id,
                     game.away_team_id,
-                    game.game_date,
+                    config.normalise(game.kick_off),
+                    game.venue_id,
                     game.home_team_score,
                     game.away_team_score,
                 ).await?;
@@ -139,7 +154,8 @@ pub(crate) async fn update_round(mut pool: Connection<DbTips>, new_round: Json<N
                 id,
                 game.home_team_id,
                 game.away_team_id,
-                game.game_date,
+                config.normalise(game.kick_off),
+                game.venue_id,
                 game.home_team_score,
                 game.away_team_score,
             ).await?;
@@ -154,13 +170,14 @@ pub(crate) async fn update_round(mut pool: Connection<DbTips>, new_round: Json<N
             }
         }
     }
+    round::set_byes(&mut tx, id, &new_round.byes).await?;
 
     tx.commit().await?;
     Ok("OK")
 }
 
 #[delete("/api/rounds/<id>")]
-pub(crate) async fn delete_round(id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+pub(crate) async fn delete_round(_admin: AdminUser, id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
     let mut tx = pool.begin().await?;
 
     // Delete all tips for the round
@@ -174,17 +191,32 @@ pub(crate) async fn delete_round(id: i32, mut pool: Connection<DbTips>) -> Resul
     Ok("OK")
 }
 
+/// The round the logged in tipper should be tipping on now.
+#[get("/api/rounds/current")]
+pub(crate) async fn current_round(auth: AuthTipper, mut pool: Connection<DbTips>, config: &State<TippingConfig>,
+) -> Result<Json<Round>, ApiError> {
+    let today = config.local(Utc::now()).date_naive();
+    match round::get_current(&mut **pool, auth.tipper_id, today).await? {
+        Some(round) => Ok(Json(round)),
+        None => Err(ApiError::NotFound("You are not taking part in any rounds".to_string())),
+    }
+}
+
 #[get("/api/rounds/<id>")]
-pub(crate) async fn get_round(id: i32, mut pool: Connection<DbTips>) -> Result<Json<NewRound>, ApiError> {
+pub(crate) async fn get_round(id: i32, mut pool: Connection<DbTips>, config: &State<TippingConfig>,
+) -> Result<Json<NewRound>, ApiError> {
     // Get the last defined round and set it as the current round to one week later
     let round = round::get(&mut **pool, id).await?;
     if let Some(round) = round {
 
-        let games = game::get_for_round(&mut **pool, id).await?;
+        let games = config.localise(game::get_for_round(&mut **pool, id).await?);
+        let byes = round::get_byes(&mut **pool, id).await?;
 
         let round = NewRound{
             round,
             games,
+            byes,
+            seed: None,
         };
         Ok(Json(round))
     } else {
@@ -194,52 +226,76 @@ pub(crate) async fn get_round(id: i32, mut pool: Connection<DbTips>) -> Result<J
 
 }
 
-#[get("/api/template_round")]
-pub(crate) async fn template_round(mut pool: Connection<DbTips>) -> Result<Json<NewRound>, ApiError> {
-    // Get the last defined round and set it as the current round to one week later
-    let lr = round::get_last_round(&mut **pool).await?;
-    let round =
+/// The next round of the season with its games drawn at random, or drawn from `seed` to repeat an
+/// earlier draw.
+#[get("/api/template_round?<season_id>&<seed>")]
+pub(crate) async fn template_round(season_id: i32, seed: Option<u64>, mut pool: Connection<DbTips>, config: &State<TippingConfig>,
+) -> Result<Json<NewRound>, ApiError> {
+    let season = match season::get(&mut **pool, season_id).await? {
+        Some(season) => season,
+        None => return Err(ApiError::NotFound(format!("Season with ID {} not found", season_id))),
+    };
+
+    // Get the last defined round in the season and set the new round to one week later
+    let lr = round::get_last_round(&mut **pool, season_id).await?;
+    let (round_number, start, end, bonus_points) =
         if let Some(last_round) = lr {
-            let round_number = &last_round.round_number + 1;
-            let start = last_round.start_date.add(chrono::Duration::days(7));
-            let end = last_round.end_date.add(chrono::Duration::days(7));
-
-            let teams  = team::get_all(&mut **pool)
-                .await?
-                .into_iter()
-                .collect::<Vec<Team>>();
-            let game_list = game_allocator::allocate_games(
-                -1, // No round ID yet
-                &teams,
-                start,
-                end,
-            ).into_iter().map(|g| Game {
-                game_id: None, // No ID yet
-                round_id: None,
-                home_team_id: g.home_team_id,
-                away_team_id: g.away_team_id,
-                game_date: g.game_date,
-                home_team_score: None,
-                away_team_score: None,
-            }).collect();
-
-            let round = Round {
-                round_id: None, // No ID yet
-                round_number,
-                start_date: start,
-                end_date: end,
-                bonus_points: last_round.bonus_points,
-            };
-            NewRound {
-                round,
-                games: game_list,
-            }
+            (last_round.round_number + 1,
+             last_round.start_date.add(chrono::Duration::days(7)),
+             last_round.end_date.add(chrono::Duration::days(7)),
+             last_round.bonus_points)
         } else {
-            // No rounds defined, set default values
-            NewRound::default()
+            // First round of the season, a long weekend from the season start
+            (1, season.start_date, season.start_date.add(chrono::Duration::days(3)), 0)
         };
 
-    Ok(Json(round))
+    // Kept small enough to read back and type in
+    let seed = seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    let teams  = team::get_all(&mut **pool, Some(season_id))
+        .await?
+        .into_iter()
+        .collect::<Vec<Team>>();
+    let (game_list, byes) = if teams.is_empty() {
+        (vec![], vec![])
+    } else {
+        let bye_counts = round::get_bye_counts(&mut **pool, season_id).await?;
+        let (games, byes) = game_allocator::allocate_games(
+            -1, // No round ID yet
+            &teams,
+            &bye_counts,
+            start,
+            end,
+            seed,
+            config,
+        );
+        let games = games.into_iter().map(|g| Game {
+            game_id: None, // No ID yet
+            round_id: None,
+            home_team_id: g.home_team_id,
+            away_team_id: g.away_team_id,
+            kick_off: g.kick_off,
+            venue_id: g.venue_id,
+            home_team_score: None,
+            away_team_score: None,
+        }).collect();
+        (games, byes)
+    };
+
+    let round = Round {
+        round_id: None, // No ID yet
+        season_id,
+        round_number,
+        start_date: start,
+        end_date: end,
+        bonus_points,
+    };
+
+    Ok(Json(NewRound {
+        round,
+        games: game_list,
+        byes,
+        seed: Some(seed),
+    }))
 }
 
 async fn validate_existing(pool: &mut PgConnection, round: &Json<NewRound>) -> Result<(), ApiError> {
@@ -250,11 +306,16 @@ async fn validate_existing(pool: &mut PgConnection, round: &Json<NewRound>) -> R
             return Err(ApiError::Invalid("Round number must be greater than 0".to_string()));
         }
 
-        if round::round_with_number_used(&mut *pool, round_id, r.round_number).await? {
+        let existing = match round::get(&mut *pool, round_id).await? {
+            Some(existing) => existing,
+            None => return Err(ApiError::NotFound(format!("Round with ID {} not found", round_id))),
+        };
+        if round::round_with_number_used(&mut *pool, round_id, existing.season_id, r.round_number).await? {
             return Err(ApiError::Invalid("Round number already exists".to_string()));
         }
 
-        validate_common(pool, round).await?;
+        // The round stays in its season, whatever the payload says
+        validate_common(pool, round, existing.season_id).await?;
     } else {
         // If no round_id is set, we are creating a new round
         return Err(ApiError::Error("No round id, can't update".to_string()));
@@ -269,15 +330,19 @@ async fn validate_new(pool: &mut PgConnection, round: &Json<NewRound>) -> Result
         return Err(ApiError::Invalid("Round number must be greater than 0".to_string()));
     }
 
-    if round::round_with_number_exists(&mut *pool, r.round_number).await? {
+    if season::get(&mut *pool, r.season_id).await?.is_none() {
+        return Err(ApiError::Invalid("Round must belong to a season".to_string()));
+    }
+
+    if round::round_with_number_exists(&mut *pool, r.season_id, r.round_number).await? {
         return Err(ApiError::Invalid("Round number already exists".to_string()));
     }
 
-    validate_common(pool, round).await?;
+    validate_common(pool, round, r.season_id).await?;
     Ok(())
 }
 
-async fn validate_common(pool: &mut PgConnection, round: &Json<NewRound>)  -> Result<(), ApiError>{
+async fn validate_common(pool: &mut PgConnection, round: &Json<NewRound>, season_id: i32)  -> Result<(), ApiError>{
     let r = &round.round;
 
     if r.start_date > r.end_date {
@@ -300,8 +365,9 @@ async fn validate_common(pool: &mut PgConnection, round: &Json<NewRound>)  -> Re
             }
         }
         // also check the game date is between the round start and end dates
-        if game.game_date < r.start_date || game.game_date > r.end_date {
-            let date = game.game_date.format("%Y-%m-%d").to_string();
+        let game_date = game.kick_off.date_naive();
+        if game_date < r.start_date || game_date > r.end_date {
+            let date = game_date.format("%Y-%m-%d").to_string();
             let start_date = r.start_date.format("%Y-%m-%d").to_string();
             let end_date = r.end_date.format("%Y-%m-%d").to_string();
             return Err(ApiError::Invalid(
@@ -309,5 +375,17 @@ async fn validate_common(pool: &mut PgConnection, round: &Json<NewRound>)  -> Re
                         date, start_date, end_date)));
         }
     }
+
+    // A team with a bye can't also be playing, and must be in the season
+    let season_teams = season::get_team_ids(pool, season_id).await?;
+    for &team_id in round.byes.iter() {
+        if !game_teams.insert(team_id) {
+            let name = team::get(pool, team_id).await?.map_or(team_id.to_string(), |t| t.nickname);
+            return Err(ApiError::Invalid(format!("Team {} has a bye but is also in a game", name)));
+        }
+        if !season_teams.contains(&team_id) {
+            return Err(ApiError::Invalid(format!("Team {} with a bye is not in this season", team_id)));
+        }
+    }
     Ok(())
 }
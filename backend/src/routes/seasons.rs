@@ -0,0 +1,231 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+use crate::db::{game, round, season, team};
+use crate::routes::rounds::NewRound;
+use crate::util::auth::AdminUser;
+use crate::util::config::TippingConfig;
+use crate::util::{game_allocator, ApiError};
+use crate::DbTips;
+use chrono::{Duration, NaiveDate};
+use kelpie_models::scoring::ScoringRules;
+use kelpie_models::season::Season;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::{Route, State};
+use rocket_db_pools::Connection;
+use sqlx::Acquire;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![list, get, add, update, delete, get_teams, set_teams, get_tippers, set_tippers, get_scoring, set_scoring, generate_fixture]
+}
+
+#[get("/api/seasons")]
+pub(crate) async fn list(mut pool: Connection<DbTips>) -> Result<Json<Vec<Season>>, ApiError> {
+    let seasons = season::get_all(&mut **pool).await?;
+    Ok(Json(seasons))
+}
+
+#[get("/api/seasons/<id>")]
+pub(crate) async fn get(id: i32, mut pool: Connection<DbTips>) -> Result<Json<Season>, ApiError> {
+    match season::get(&mut **pool, id).await? {
+        Some(season) => Ok(Json(season)),
+        None => Err(ApiError::NotFound("Season not found".to_string())),
+    }
+}
+
+#[post("/api/seasons", data = "<season>")]
+pub(crate) async fn add(_admin: AdminUser, season: Json<Season>, mut pool: Connection<DbTips>) -> Result<Json<Season>, ApiError> {
+    validate(&season)?;
+    let new = season::insert(&mut **pool, season.name.clone(), season.start_date, season.end_date).await?;
+    Ok(Json(new))
+}
+
+#[put("/api/seasons", data = "<season>")]
+pub(crate) async fn update(_admin: AdminUser, season: Json<Season>, mut pool: Connection<DbTips>) -> Result<Json<Season>, ApiError> {
+    validate(&season)?;
+    if let Some(id) = season.id {
+        let count = season::update(&mut **pool, id, season.name.clone(), season.start_date, season.end_date).await?;
+        match count {
+            0 => Err(ApiError::NotFound("Row not found".to_string())),
+            1 => {
+                if let Some(new) = season::get(&mut **pool, id).await? {
+                    Ok(Json(new))
+                } else {
+                    Err(ApiError::NotFound("Row not found".to_string()))
+                }
+            },
+            _ => Err(ApiError::Error("Unexpected row count".to_string()))
+        }
+    } else {
+        Err(ApiError::NotFound("Row not found".to_string()))
+    }
+}
+
+#[delete("/api/seasons/<id>")]
+pub(crate) async fn delete(_admin: AdminUser, id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+    if !round::get_all(&mut **pool, Some(id)).await?.is_empty() {
+        return Err(ApiError::Invalid("The season still has rounds, delete them first".to_string()));
+    }
+    season::delete(&mut **pool, id).await?;
+    Ok("OK")
+}
+
+#[get("/api/seasons/<id>/teams")]
+pub(crate) async fn get_teams(id: i32, mut pool: Connection<DbTips>) -> Result<Json<Vec<i32>>, ApiError> {
+    let teams = season::get_team_ids(&mut **pool, id).await?;
+    Ok(Json(teams))
+}
+
+#[put("/api/seasons/<id>/teams", data = "<team_ids>")]
+pub(crate) async fn set_teams(_admin: AdminUser, id: i32, team_ids: Json<Vec<i32>>, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+    let mut tx = pool.begin().await?;
+    season::set_team_ids(&mut tx, id, &team_ids).await?;
+    tx.commit().await?;
+    Ok("OK")
+}
+
+#[get("/api/seasons/<id>/tippers")]
+pub(crate) async fn get_tippers(id: i32, mut pool: Connection<DbTips>) -> Result<Json<Vec<i32>>, ApiError> {
+    let tippers = season::get_tipper_ids(&mut **pool, id).await?;
+    Ok(Json(tippers))
+}
+
+#[put("/api/seasons/<id>/tippers", data = "<tipper_ids>")]
+pub(crate) async fn set_tippers(_admin: AdminUser, id: i32, tipper_ids: Json<Vec<i32>>, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+    let mut tx = pool.begin().await?;
+    season::set_tipper_ids(&mut tx, id, &tipper_ids).await?;
+    tx.commit().await?;
+    Ok("OK")
+}
+
+#[get("/api/seasons/<id>/scoring")]
+pub(crate) async fn get_scoring(id: i32, mut pool: Connection<DbTips>) -> Result<Json<ScoringRules>, ApiError> {
+    match season::get_scoring_rules(&mut **pool, id).await? {
+        Some(rules) => Ok(Json(rules)),
+        None => Err(ApiError::NotFound("Season not found".to_string())),
+    }
+}
+
+#[put("/api/seasons/<id>/scoring", data = "<rules>")]
+pub(crate) async fn set_scoring(_admin: AdminUser, id: i32, rules: Json<ScoringRules>, mut pool: Connection<DbTips>) -> Result<Json<ScoringRules>, ApiError> {
+    if rules.correct_points < 0 || rules.draw_points.is_some_and(|p| p < 0) || rules.upset_points < 0
+        || rules.margin_points.iter().any(|m| m.min_margin < 0 || m.points < 0) {
+        return Err(ApiError::Invalid("Points and margins must not be negative".to_string()));
+    }
+    match season::set_scoring_rules(&mut **pool, id, &rules).await? {
+        0 => Err(ApiError::NotFound("Season not found".to_string())),
+        _ => Ok(rules),
+    }
+}
+
+/// The most rounds a season can be drawn with, a round a week for a year.
+const MAX_FIXTURE_ROUNDS: usize = 52;
+
+/// How to draw up a season.
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub(crate) struct FixtureOptions {
+    /// Every team plays every other team home and away
+    pub(crate) double: bool,
+    /// Overrides the number of rounds needed for a single or double round-robin
+    pub(crate) rounds: Option<usize>,
+    /// The first round starts on the season start date unless given
+    pub(crate) start_date: Option<NaiveDate>,
+    /// Days in each round after its first, rounds start a week apart
+    pub(crate) round_days: i64,
+    pub(crate) bonus_points: i32,
+    /// Repeats an earlier draw, the seed used is returned with each round
+    pub(crate) seed: Option<u64>,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        FixtureOptions { double: false, rounds: None, start_date: None, round_days: 3, bonus_points: 0, seed: None }
+    }
+}
+
+/// Create every round and game of a season from a round-robin draw of its teams.
+#[post("/api/seasons/<id>/fixture", data = "<options>")]
+pub(crate) async fn generate_fixture(_admin: AdminUser, id: i32, options: Json<FixtureOptions>, mut pool: Connection<DbTips>,
+                                     config: &State<TippingConfig>,
+) -> Result<Json<Vec<NewRound>>, ApiError> {
+    let Some(season) = season::get(&mut **pool, id).await? else {
+        return Err(ApiError::NotFound("Season not found".to_string()));
+    };
+    if !(0..7).contains(&options.round_days) {
+        return Err(ApiError::Invalid("A round must be less than a week long".to_string()));
+    }
+    if options.bonus_points < 0 {
+        return Err(ApiError::Invalid("Bonus points must not be negative".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+    if round::get_last_round(&mut tx, id).await?.is_some() {
+        return Err(ApiError::Invalid("The season already has rounds, delete them to draw it again".to_string()));
+    }
+    let teams = team::get_all(&mut tx, Some(id)).await?;
+    let team_ids: Vec<i32> = teams.iter().filter_map(|t| t.id).collect();
+    let home_venues = game_allocator::home_venues(&teams);
+    if team_ids.len() < 2 {
+        return Err(ApiError::Invalid("The season needs at least two teams".to_string()));
+    }
+
+    let rounds = options.rounds.unwrap_or_else(|| game_allocator::round_robin_length(team_ids.len(), options.double));
+    if !(1..=MAX_FIXTURE_ROUNDS).contains(&rounds) {
+        return Err(ApiError::Invalid(format!("A season can have from 1 to {} rounds", MAX_FIXTURE_ROUNDS)));
+    }
+    let first_start = options.start_date.unwrap_or(season.start_date);
+    let last_end = first_start + Duration::weeks(rounds as i64 - 1) + Duration::days(options.round_days);
+    if first_start < season.start_date || last_end > season.end_date {
+        return Err(ApiError::Invalid(format!("The rounds run from {} to {}, outside the season from {} to {}",
+                                             first_start, last_end, season.start_date, season.end_date)));
+    }
+    let seed = options.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    let mut created = Vec::new();
+    for (i, draw) in game_allocator::round_robin(&team_ids, rounds, seed).into_iter().enumerate() {
+        let start = first_start + Duration::weeks(i as i64);
+        let end = start + Duration::days(options.round_days);
+        let new_round = round::insert(&mut tx, id, i as i32 + 1, start, end, options.bonus_points).await?;
+        let round_id = new_round.round_id.unwrap_or(-1);
+        let mut games = Vec::new();
+        for g in game_allocator::schedule(round_id, draw.games, &home_venues, start, end, config) {
+            games.push(game::insert(&mut tx, round_id, g.home_team_id, g.away_team_id, g.kick_off, g.venue_id, None, None).await?);
+        }
+        round::set_byes(&mut tx, round_id, &draw.byes).await?;
+        created.push(NewRound { round: new_round, games: config.localise(games), byes: draw.byes, seed: Some(seed) });
+    }
+    tx.commit().await?;
+    Ok(Json(created))
+}
+
+fn validate(season: &Season) -> Result<(), ApiError> {
+    if season.name.trim().is_empty() {
+        return Err(ApiError::Invalid("Season name must not be empty".to_string()));
+    }
+    if season.start_date > season.end_date {
+        return Err(ApiError::Invalid("Season start date must not be after the end date".to_string()));
+    }
+    Ok(())
+}
@@ -22,6 +22,7 @@
  *
  */
 use crate::db::team;
+use crate::util::auth::AdminUser;
 use crate::util::ApiError;
 use crate::DbTips;
 use kelpie_models::team::Team;
@@ -32,22 +33,22 @@ use rocket_db_pools::Connection;
 pub(crate) fn routes() -> Vec<Route> {
     routes![list, add, update, delete]
 }
-#[get("/api/teams")]
-pub(crate) async fn list(mut pool: Connection<DbTips>) -> Result<Json<Vec<Team>>, ApiError> {
-    let teams = team::get_all(&mut **pool).await?;
+#[get("/api/teams?<season_id>")]
+pub(crate) async fn list(season_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Json<Vec<Team>>, ApiError> {
+    let teams = team::get_all(&mut **pool, season_id).await?;
     Ok(Json(teams))
 }
 
 #[post("/api/teams", data = "<team>")]
-pub(crate) async fn add(team: Json<Team>, mut pool: Connection<DbTips>) -> Result<Json<Team>, ApiError> {
-    let new = team::insert(&mut **pool, team.name.clone(), team.nickname.clone()).await?;
+pub(crate) async fn add(_admin: AdminUser, team: Json<Team>, mut pool: Connection<DbTips>) -> Result<Json<Team>, ApiError> {
+    let new = team::insert(&mut **pool, team.name.clone(), team.nickname.clone(), team.home_venue_id).await?;
     Ok(Json(new))
 }
 
 #[put("/api/teams", data = "<team>")]
-pub(crate) async fn update(team: Json<Team>, mut pool: Connection<DbTips>) -> Result<Json<Team>, ApiError> {
+pub(crate) async fn update(_admin: AdminUser, team: Json<Team>, mut pool: Connection<DbTips>) -> Result<Json<Team>, ApiError> {
     if let Some(id) = team.id {
-        let count = team::update(&mut **pool, id, team.name.clone(), team.nickname.clone()).await?;
+        let count = team::update(&mut **pool, id, team.name.clone(), team.nickname.clone(), team.home_venue_id).await?;
         match count {
             0 => Err(ApiError::NotFound("Row not found".to_string())),
             1 => {
@@ -65,7 +66,7 @@ pub(crate) async fn update(team: Json<Team>, mut pool: Connection<DbTips>) -> Re
 }
 
 #[delete("/api/teams/<id>")]
-pub(crate) async fn delete(id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+pub(crate) async fn delete(_admin: AdminUser, id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
     team::delete(&mut **pool, id).await?;
     Ok("OK")
 }
\ No newline at end of file
@@ -22,42 +22,65 @@
  *
  */
 
-use crate::db::tipper;
-use crate::util::ApiError;
+use crate::db::{season, tipper};
+use crate::util::auth::{AdminUser, AuthTipper};
+use crate::util::download::Download;
+use crate::util::tipper_import::{self, TipperImportReport};
+use crate::util::{auth, upload, ApiError};
 use crate::DbTips;
 use kelpie_models::tipper::Tipper;
+use rocket::data::Data;
+use rocket::http::ContentType;
 use rocket::serde::json::Json;
 use rocket::Route;
+use sqlx::{Acquire, PgConnection};
 
 use rocket_db_pools::Connection;
 
 pub(crate) fn routes() -> Vec<Route> {
-    routes![list, add, update, delete, get]
+    routes![list, add, update, delete, get, import_tippers, export_tippers]
 }
 
-#[get("/api/tippers")]
-pub(crate) async fn list(mut pool: Connection<DbTips>) ->  Result<Json<Vec<Tipper>>, ApiError> {
+#[get("/api/tippers?<season_id>")]
+pub(crate) async fn list(auth: AuthTipper, season_id: Option<i32>, mut pool: Connection<DbTips>) ->  Result<Json<Vec<Tipper>>, ApiError> {
 
-    let tippers = tipper::get_all(&mut **pool).await?;
-    Ok(Json(tippers))
+    let tippers = tipper::get_all(&mut **pool, season_id).await?;
+    Ok(Json(tippers.into_iter().map(|t| visible_to(&auth, t)).collect()))
 }
 
 #[post("/api/tippers", data = "<tipper>")]
-pub(crate) async fn add(tipper: Json<Tipper>, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
-    let new = tipper::insert(&mut **pool, tipper.name.clone(), tipper.email.clone()).await?;
+pub(crate) async fn add(_admin: AdminUser, tipper: Json<Tipper>, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
+    let password_hash = password_hash(&tipper.password)?;
+    let mut tx = pool.begin().await?;
+    check_email(&mut tx, &tipper.email, None).await?;
+    let new = tipper::insert(&mut tx, tipper.name.clone(), tipper.email.clone(), tipper.is_admin).await?;
+    if let (Some(id), Some(hash)) = (new.id, password_hash) {
+        tipper::set_password_hash(&mut tx, id, hash).await?;
+    }
+    tx.commit().await?;
     Ok(Json(new))
 }
 
 #[put("/api/tippers", data = "<tipper>")]
-pub(crate) async fn update(tipper: Json<Tipper>, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
+pub(crate) async fn update(admin: AdminUser, tipper: Json<Tipper>, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
     if let Some(id) = tipper.id {
-        let count = tipper::update(&mut **pool, id, tipper.name.clone(), tipper.email.clone()).await?;
+        if id == admin.tipper_id && !tipper.is_admin {
+            return Err(ApiError::Invalid("You can't remove your own admin access".to_string()));
+        }
+        let password_hash = password_hash(&tipper.password)?;
+        let mut tx = pool.begin().await?;
+        check_email(&mut tx, &tipper.email, Some(id)).await?;
+        let count = tipper::update(&mut tx, id, tipper.name.clone(), tipper.email.clone(), tipper.is_admin).await?;
         match count {
             0 => {
                 Err(ApiError::NotFound("Row not found".to_string()))
             }
             1 => {
-                if let Some(new) = tipper::get(&mut **pool, id).await? {
+                if let Some(hash) = password_hash {
+                    tipper::set_password_hash(&mut tx, id, hash).await?;
+                }
+                if let Some(new) = tipper::get(&mut tx, id).await? {
+                    tx.commit().await?;
                     Ok(Json(new))
                 } else {
                     Err(ApiError::NotFound("Row not found".to_string()))
@@ -71,15 +94,87 @@ pub(crate) async fn update(tipper: Json<Tipper>, mut pool: Connection<DbTips>) -
 }
 
 #[delete("/api/tippers/<id>")]
-pub(crate) async fn delete(id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+pub(crate) async fn delete(admin: AdminUser, id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+    if id == admin.tipper_id {
+        return Err(ApiError::Invalid("You can't delete yourself".to_string()));
+    }
     tipper::delete(&mut **pool, id).await?;
     Ok("OK")
 }
 
 #[get("/api/tippers/<id>")]
-pub(crate) async fn get(id: i32, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
+pub(crate) async fn get(auth: AuthTipper, id: i32, mut pool: Connection<DbTips>) -> Result<Json<Tipper>, ApiError> {
     match tipper::get(&mut **pool, id).await? {
-        Some(tipper) => Ok(Json(tipper)),
+        Some(tipper) => Ok(Json(visible_to(&auth, tipper))),
         None => Err(ApiError::NotFound("Tipper not found".to_string())),
     }
 }
+
+/// Add tippers from a CSV file with `name` and `email` columns, enrolling them in the season if one is given.
+///
+/// Unless `dry_run=false` is given this only reports what would change.
+#[post("/api/tippers/import?<season_id>&<dry_run>", data = "<file>")]
+pub(crate) async fn import_tippers(_admin: AdminUser, season_id: Option<i32>, dry_run: Option<bool>, file: Data<'_>,
+                                   mut pool: Connection<DbTips>,
+) -> Result<Json<TipperImportReport>, ApiError> {
+    if let Some(season_id) = season_id {
+        if season::get(&mut **pool, season_id).await?.is_none() {
+            return Err(ApiError::NotFound(format!("Season with ID {} not found", season_id)));
+        }
+    }
+    let text = upload::read_text(file).await?;
+    let rows = tipper_import::parse(&text).map_err(ApiError::Invalid)?;
+
+    let mut tx = pool.begin().await?;
+    let existing = tipper::get_all(&mut tx, None).await?;
+    let enrolled = match season_id {
+        Some(season_id) => Some(season::get_tipper_ids(&mut tx, season_id).await?),
+        None => None,
+    };
+    let mut report = tipper_import::plan(&existing, enrolled.as_deref(), &rows);
+    if dry_run.unwrap_or(true) || !report.errors.is_empty() {
+        return Ok(Json(report));
+    }
+    tipper_import::apply(&mut tx, season_id, &mut report).await?;
+    tx.commit().await?;
+    Ok(Json(report))
+}
+
+/// The tippers, or just those in the season, as a CSV file the import accepts.
+#[get("/api/tippers/export?<season_id>")]
+pub(crate) async fn export_tippers(_admin: AdminUser, season_id: Option<i32>, mut pool: Connection<DbTips>) -> Result<Download, ApiError> {
+    let file_name = match season_id {
+        Some(season_id) => match season::get(&mut **pool, season_id).await? {
+            Some(season) => format!("{} tippers.csv", season.name),
+            None => return Err(ApiError::NotFound(format!("Season with ID {} not found", season_id))),
+        },
+        None => "tippers.csv".to_string(),
+    };
+    let tippers = tipper::get_all(&mut **pool, season_id).await?;
+    let csv = tipper_import::export(&tippers).map_err(ApiError::Error)?;
+    Ok(Download::new(&file_name, ContentType::CSV, csv))
+}
+
+/// Only admins and the tippers themselves get to see email addresses.
+fn visible_to(auth: &AuthTipper, mut tipper: Tipper) -> Tipper {
+    if !auth.is_admin && tipper.id != Some(auth.tipper_id) {
+        tipper.email = String::new();
+    }
+    tipper
+}
+
+/// Emails are unique ignoring case, as they are when logging in.
+async fn check_email(pool: &mut PgConnection, email: &str, tipper_id: Option<i32>) -> Result<(), ApiError> {
+    match tipper::get_id_by_email(pool, email).await? {
+        Some(id) if Some(id) != tipper_id => Err(ApiError::Invalid(format!("Email '{}' already belongs to another tipper", email))),
+        _ => Ok(()),
+    }
+}
+
+/// The hash to store for a new password, a blank password leaves the existing one alone.
+fn password_hash(password: &Option<String>) -> Result<Option<String>, ApiError> {
+    match password {
+        Some(password) if !password.is_empty() => Ok(Some(auth::hash_password(password)?)),
+        _ => Ok(None),
+    }
+}
@@ -21,17 +21,20 @@
  *      Trevor Campbell
  *
  */
-use crate::db::tip;
-use crate::util::ApiError;
+use crate::db::{game, round, team, tip};
+use crate::util::config::TippingConfig;
+use crate::util::auth::AuthTipper;
+use crate::util::{scoring, tip_lock, ApiError};
 use crate::DbTips;
+use chrono::Utc;
 use kelpie_models::tip::Tip;
 use rocket::serde::json::Json;
-use rocket::Route;
+use rocket::{Route, State};
 use rocket_db_pools::Connection;
 use sqlx::Acquire;
 
 pub(crate) fn routes() -> Vec<Route> {
-    routes![get_tips_for_round, save_tips_for_round, tips_exist]
+    routes![get_tips_for_round, save_tips_for_round, tips_exist, locked_games]
 }
 
 #[get("/api/tips/exists/round/<round_id>")]
@@ -41,22 +44,76 @@ pub(crate) async fn tips_exist(round_id: i32, mut pool: Connection<DbTips>,
     Ok(Json(tips))
 }
 
+#[get("/api/tips/locked/round/<round_id>")]
+pub(crate) async fn locked_games(round_id: i32, config: &State<TippingConfig>, mut pool: Connection<DbTips>,
+) -> Result<Json<Vec<i32>>, ApiError> {
+    let round = match round::get(&mut **pool, round_id).await? {
+        Some(round) => round,
+        None => return Err(ApiError::NotFound(format!("Round with ID {} not found", round_id))),
+    };
+    let games = game::get_for_round(&mut **pool, round_id).await?;
+    let now = config.local(Utc::now());
+    Ok(Json(tip_lock::locked_games(config, &round, &games, now)))
+}
+
 #[get("/api/tips/<tipper_id>/<round_id>")]
-pub(crate) async fn get_tips_for_round(tipper_id: i32, round_id: i32, mut pool: Connection<DbTips>,
+pub(crate) async fn get_tips_for_round(tipper_id: i32, round_id: i32, auth: AuthTipper, mut pool: Connection<DbTips>,
 ) -> Result<Json<Vec<Tip>>, ApiError> {
+    auth.require(tipper_id)?;
     let tips = tip::get_by_tipper_and_round(&mut **pool, tipper_id, round_id).await?;
     Ok(Json(tips))
 }
 
-#[post("/api/tips/<_tipper_id>/<_round_id>", data = "<tips>")]
+#[post("/api/tips/<tipper_id>/<round_id>", data = "<tips>")]
 pub(crate) async fn save_tips_for_round(
-    _tipper_id: i32,
-    _round_id: i32,
+    tipper_id: i32,
+    round_id: i32,
+    auth: AuthTipper,
+    config: &State<TippingConfig>,
     mut pool: Connection<DbTips>,
     tips: Json<Vec<Tip>>,
 ) -> Result<&'static str, ApiError> {
+    auth.require(tipper_id)?;
+    if tips.iter().any(|t| t.tipper_id != tipper_id) {
+        return Err(ApiError::Forbidden("You can only see and change your own tips".to_string()));
+    }
     let mut tx = pool.begin().await?;
 
+    let round = match round::get(&mut tx, round_id).await? {
+        Some(round) => round,
+        None => return Err(ApiError::NotFound(format!("Round with ID {} not found", round_id))),
+    };
+    let games = game::get_for_round(&mut tx, round_id).await?;
+    let now = config.local(Utc::now());
+
+    // Reject the lot if any tip is for a game outside the round or one that has already locked
+    let mut errors = Vec::new();
+    let margin_game_id = scoring::margin_game(&games).and_then(|g| g.game_id);
+    for t in &tips.0 {
+        if let Some(margin) = t.margin {
+            if Some(t.game_id) != margin_game_id {
+                errors.push("A margin can only be given for the first game of the round".to_string());
+            } else if margin < 0 {
+                errors.push("The margin must not be negative".to_string());
+            }
+        }
+        match games.iter().find(|g| g.game_id == Some(t.game_id)) {
+            None => errors.push(format!("Game {} is not part of round {}", t.game_id, round.round_number)),
+            Some(g) => {
+                if tip_lock::is_locked(config, &round, &games, g, now) {
+                    let home = team_nickname(&mut tx, g.home_team_id).await?;
+                    let away = team_nickname(&mut tx, g.away_team_id).await?;
+                    let lock_time = config.local(tip_lock::lock_time(config, &round, &games, g).to_utc());
+                    errors.push(format!("Tipping for {} v {} closed at {}",
+                                        home, away, lock_time.format("%Y-%m-%d %H:%M")));
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Invalid(errors.join("\n")));
+    }
+
     // Insert games
     for t in &tips.0 {
         // Try update
@@ -69,3 +126,8 @@ pub(crate) async fn save_tips_for_round(
     tx.commit().await?;
     Ok("OK")
 }
+
+async fn team_nickname(pool: &mut sqlx::PgConnection, team_id: i32) -> Result<String, ApiError> {
+    let t = team::get(pool, team_id).await?;
+    Ok(t.map(|t| t.nickname).unwrap_or_else(|| team_id.to_string()))
+}
@@ -0,0 +1,81 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::venue;
+use crate::util::auth::AdminUser;
+use crate::util::ApiError;
+use crate::DbTips;
+use kelpie_models::venue::Venue;
+use rocket::serde::json::Json;
+use rocket::Route;
+use rocket_db_pools::Connection;
+
+pub(crate) fn routes() -> Vec<Route> {
+    routes![list, add, update, delete]
+}
+#[get("/api/venues")]
+pub(crate) async fn list(mut pool: Connection<DbTips>) -> Result<Json<Vec<Venue>>, ApiError> {
+    let venues = venue::get_all(&mut **pool).await?;
+    Ok(Json(venues))
+}
+
+#[post("/api/venues", data = "<venue>")]
+pub(crate) async fn add(_admin: AdminUser, venue: Json<Venue>, mut pool: Connection<DbTips>) -> Result<Json<Venue>, ApiError> {
+    validate(&venue)?;
+    let new = venue::insert(&mut **pool, venue.name.trim().to_string()).await?;
+    Ok(Json(new))
+}
+
+#[put("/api/venues", data = "<venue>")]
+pub(crate) async fn update(_admin: AdminUser, venue: Json<Venue>, mut pool: Connection<DbTips>) -> Result<Json<Venue>, ApiError> {
+    validate(&venue)?;
+    if let Some(id) = venue.id {
+        let count = venue::update(&mut **pool, id, venue.name.trim().to_string()).await?;
+        match count {
+            0 => Err(ApiError::NotFound("Row not found".to_string())),
+            1 => {
+                if let Some(new) = venue::get(&mut **pool, id).await? {
+                    Ok(Json(new))
+                } else {
+                    Err(ApiError::NotFound("Row not found".to_string()))
+                }
+            },
+            _ => Err(ApiError::Error("Unexpected row count".to_string()))
+        }
+    } else {
+        Err(ApiError::NotFound("Row not found".to_string()))
+    }
+}
+
+#[delete("/api/venues/<id>")]
+pub(crate) async fn delete(_admin: AdminUser, id: i32, mut pool: Connection<DbTips>) -> Result<&'static str, ApiError> {
+    venue::delete(&mut **pool, id).await?;
+    Ok("OK")
+}
+
+fn validate(venue: &Venue) -> Result<(), ApiError> {
+    if venue.name.trim().is_empty() {
+        return Err(ApiError::Invalid("Venue name must not be empty".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+use crate::db::tipper;
+use crate::util::config::TippingConfig;
+use crate::util::ApiError;
+use crate::DbTips;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rocket::fairing::AdHoc;
+use rocket::http::{Cookie, CookieJar, SameSite, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use rocket_db_pools::Database;
+
+/// Name of the private cookie holding the logged in tipper's id.
+pub(crate) const SESSION_COOKIE: &str = "tipper_id";
+
+/// The tipper making the request, taken from the session cookie.
+///
+/// Routes using this guard fail with 401 Unauthorized when nobody is logged in,
+/// or the logged in tipper has since been deleted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AuthTipper {
+    pub(crate) tipper_id: i32,
+    pub(crate) is_admin: bool,
+}
+
+impl AuthTipper {
+    /// Only let the logged in tipper act on their own behalf.
+    pub(crate) fn require(&self, tipper_id: i32) -> Result<(), ApiError> {
+        if self.tipper_id == tipper_id {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden("You can only see and change your own tips".to_string()))
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthTipper {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(tipper_id) = request.cookies()
+            .get_private(SESSION_COOKIE)
+            .and_then(|cookie| cookie.value().parse::<i32>().ok())
+        else {
+            return Outcome::Error((Status::Unauthorized, ()));
+        };
+        let Some(db) = DbTips::fetch(request.rocket()) else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+        let mut conn = match db.0.acquire().await {
+            Ok(conn) => conn,
+            Err(_) => return Outcome::Error((Status::ServiceUnavailable, ())),
+        };
+        match tipper::is_admin(&mut conn, tipper_id).await {
+            Ok(Some(is_admin)) => Outcome::Success(AuthTipper { tipper_id, is_admin }),
+            Ok(None) => Outcome::Error((Status::Unauthorized, ())),
+            Err(_) => Outcome::Error((Status::InternalServerError, ())),
+        }
+    }
+}
+
+/// A logged in tipper with the admin role.
+///
+/// Routes using this guard fail with 401 Unauthorized when nobody is logged in and
+/// 403 Forbidden when the tipper isn't an admin.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AdminUser {
+    pub(crate) tipper_id: i32,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminUser {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.guard::<AuthTipper>().await {
+            Outcome::Success(auth) if auth.is_admin => Outcome::Success(AdminUser { tipper_id: auth.tipper_id }),
+            Outcome::Success(_) => Outcome::Error((Status::Forbidden, ())),
+            Outcome::Error(e) => Outcome::Error(e),
+            Outcome::Forward(s) => Outcome::Forward(s),
+        }
+    }
+}
+
+pub(crate) fn start_session(cookies: &CookieJar<'_>, tipper_id: i32) {
+    let cookie = Cookie::build((SESSION_COOKIE, tipper_id.to_string()))
+        .http_only(true)
+        .same_site(SameSite::Strict);
+    cookies.add_private(cookie);
+}
+
+pub(crate) fn end_session(cookies: &CookieJar<'_>) {
+    cookies.remove_private(SESSION_COOKIE);
+}
+
+pub(crate) fn hash_password(password: &str) -> Result<String, ApiError> {
+    let salt = SaltString::generate(&mut OsRng);
+    match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Ok(hash.to_string()),
+        Err(e) => Err(ApiError::Error(format!("Unable to hash password: {}", e))),
+    }
+}
+
+pub(crate) fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Make sure someone can log in to manage the competition.
+///
+/// If no tipper is an admin yet, the `admin` from the tipping config is made one, creating the
+/// tipper if they don't already exist.
+pub(crate) fn bootstrap() -> AdHoc {
+    AdHoc::try_on_ignite("Admin Bootstrap", |rocket| async {
+        let Some(admin) = rocket.state::<TippingConfig>().and_then(|c| c.admin.clone()) else {
+            return Ok(rocket);
+        };
+        let Some(db) = DbTips::fetch(&rocket) else {
+            tracing::error!("Database not available for admin bootstrap");
+            return Err(rocket);
+        };
+        match create_admin(&db.0, &admin.name, &admin.email, &admin.password).await {
+            Ok(()) => Ok(rocket),
+            Err(e) => {
+                tracing::error!("Unable to create admin {}: {:?}", admin.email, e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+async fn create_admin(pool: &sqlx::PgPool, name: &str, email: &str, password: &str) -> Result<(), ApiError> {
+    let mut conn = pool.acquire().await?;
+    if tipper::admin_exists(&mut conn).await? {
+        return Ok(());
+    }
+    let id = match tipper::get_for_login(&mut conn, email).await? {
+        Some((existing, _)) => {
+            let id = existing.id.unwrap_or_default();
+            tipper::update(&mut conn, id, existing.name, existing.email, true).await?;
+            id
+        }
+        None => {
+            let new = tipper::insert(&mut conn, name.to_string(), email.to_string(), true).await?;
+            new.id.unwrap_or_default()
+        }
+    };
+    tipper::set_password_hash(&mut conn, id, hash_password(password)?).await?;
+    tracing::info!("Made {} an admin", email);
+    Ok(())
+}
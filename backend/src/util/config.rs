@@ -0,0 +1,320 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use kelpie_models::game::Game;
+use rocket::fairing::AdHoc;
+use rocket::serde::Deserialize;
+
+/// When tips for a game stop being accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub(crate) enum TipLock {
+    /// Each game locks individually when it starts
+    #[default]
+    GameStart,
+    /// All games in a round lock when the round starts
+    RoundStart,
+    /// All games in a round lock `tip_lock_hours` before the first game of the round
+    BeforeFirstGame,
+}
+
+/// The first admin, created at start up if there are no admins yet.
+#[derive(Clone, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct BootstrapAdmin {
+    pub(crate) name: String,
+    pub(crate) email: String,
+    pub(crate) password: String,
+}
+
+impl std::fmt::Debug for BootstrapAdmin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BootstrapAdmin")
+            .field("name", &self.name)
+            .field("email", &self.email)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Premiership points a team gets for each result on the ladder.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub(crate) struct LadderPoints {
+    pub(crate) win: i64,
+    pub(crate) draw: i64,
+}
+
+impl Default for LadderPoints {
+    fn default() -> Self {
+        LadderPoints { win: 4, draw: 2 }
+    }
+}
+
+/// Where the results provider gets scores from.
+#[derive(Clone, Deserialize)]
+#[serde(crate = "rocket::serde", tag = "kind", rename_all = "snake_case")]
+pub(crate) enum ResultsSource {
+    /// A JSON feed fetched over HTTP, sent `api_key` as a bearer token if given
+    Http { url: String, api_key: Option<String> },
+    /// A JSON file on disk
+    File { path: std::path::PathBuf },
+}
+
+impl std::fmt::Debug for ResultsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultsSource::Http { url, .. } => f.debug_struct("Http").field("url", url).finish_non_exhaustive(),
+            ResultsSource::File { path } => f.debug_struct("File").field("path", path).finish(),
+        }
+    }
+}
+
+/// Polling of a results provider for game scores.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ResultsProviderConfig {
+    #[serde(flatten)]
+    pub(crate) source: ResultsSource,
+    #[serde(default = "default_poll_minutes")]
+    pub(crate) poll_minutes: u64,
+}
+
+fn default_poll_minutes() -> u64 {
+    15
+}
+
+/// How an SMTP connection is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub(crate) enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS, usually on port 587
+    #[default]
+    Starttls,
+    /// TLS from the start, usually on port 465
+    Tls,
+    /// No encryption, only for a local relay
+    None,
+}
+
+/// Where emails go.
+#[derive(Clone, Deserialize)]
+#[serde(crate = "rocket::serde", tag = "kind", rename_all = "snake_case")]
+pub(crate) enum MailTransportConfig {
+    Smtp {
+        host: String,
+        port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    /// Appended to a file instead of being sent, for testing
+    File { path: std::path::PathBuf },
+    /// Written to the log instead of being sent, for testing
+    Log,
+}
+
+impl std::fmt::Debug for MailTransportConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MailTransportConfig::Smtp { host, port, security, username, .. } => f.debug_struct("Smtp")
+                .field("host", host)
+                .field("port", port)
+                .field("security", security)
+                .field("username", username)
+                .finish_non_exhaustive(),
+            MailTransportConfig::File { path } => f.debug_struct("File").field("path", path).finish(),
+            MailTransportConfig::Log => f.write_str("Log"),
+        }
+    }
+}
+
+/// Outgoing email.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct MailConfig {
+    /// The sender, e.g. "Kelpie Tipping <tips@example.com>"
+    pub(crate) from: String,
+    #[serde(flatten)]
+    pub(crate) transport: MailTransportConfig,
+}
+
+/// Reminders to tippers who haven't tipped every game of a round.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub(crate) struct ReminderConfig {
+    /// Hours before the first game of a round that reminders are sent
+    pub(crate) hours_before: i64,
+    /// How often to look for rounds that are due
+    pub(crate) check_minutes: u64,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        ReminderConfig { hours_before: 24, check_minutes: 15 }
+    }
+}
+
+/// The summary email sent to every tipper once a round has been played.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub(crate) struct RoundSummaryConfig {
+    /// A Handlebars template for the email body, the built in one is used if not given
+    pub(crate) template: Option<std::path::PathBuf>,
+    /// Hours after the last game of a round kicks off that the summary is sent, if every game has
+    /// a score by then, giving time to check the preview
+    pub(crate) send_after_hours: i64,
+    /// How often to look for rounds that are due
+    pub(crate) check_minutes: u64,
+}
+
+impl Default for RoundSummaryConfig {
+    fn default() -> Self {
+        RoundSummaryConfig { template: None, send_after_hours: 12, check_minutes: 15 }
+    }
+}
+
+/// Competition settings read from the `tipping` table in Rocket.toml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub(crate) struct TippingConfig {
+    pub(crate) tip_lock: TipLock,
+    pub(crate) tip_lock_hours: i64,
+    /// The time zone kick-off times are entered and shown in, e.g. "Australia/Melbourne"
+    pub(crate) timezone: Tz,
+    /// Kick-off time given to the first game of each day by the game allocator
+    pub(crate) first_kick_off: NaiveTime,
+    pub(crate) admin: Option<BootstrapAdmin>,
+    pub(crate) ladder: LadderPoints,
+    /// Scores are only fetched automatically when a provider is configured
+    pub(crate) results_provider: Option<ResultsProviderConfig>,
+    /// No email is sent unless this is configured
+    pub(crate) mail: Option<MailConfig>,
+    pub(crate) reminders: Option<ReminderConfig>,
+    pub(crate) round_summary: Option<RoundSummaryConfig>,
+}
+
+impl Default for TippingConfig {
+    fn default() -> Self {
+        TippingConfig {
+            tip_lock: TipLock::GameStart,
+            tip_lock_hours: 0,
+            timezone: Tz::UTC,
+            first_kick_off: NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+            admin: None,
+            ladder: LadderPoints::default(),
+            results_provider: None,
+            mail: None,
+            reminders: None,
+            round_summary: None,
+        }
+    }
+}
+
+impl TippingConfig {
+    /// Convert a timestamp to the competition time zone.
+    pub(crate) fn local(&self, dt: DateTime<Utc>) -> DateTime<FixedOffset> {
+        dt.with_timezone(&self.timezone).fixed_offset()
+    }
+
+    /// The competition local time for a wall clock date and time.
+    pub(crate) fn at(&self, dt: NaiveDateTime) -> DateTime<FixedOffset> {
+        // In a daylight saving gap there is no such local time, so move it forward by the hour the clocks skip
+        match self.timezone.from_local_datetime(&dt).earliest() {
+            Some(local) => local.fixed_offset(),
+            None => match self.timezone.from_local_datetime(&(dt + Duration::hours(1))).earliest() {
+                Some(local) => local.fixed_offset(),
+                None => self.local(Utc.from_utc_datetime(&dt)),
+            },
+        }
+    }
+
+    /// Midnight at the start of `date` in the competition time zone.
+    pub(crate) fn start_of(&self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.at(date.and_time(NaiveTime::MIN))
+    }
+
+    /// Kick-off times are entered as competition local time, so whatever offset the client sent
+    /// is replaced by the one in force at that wall clock time.
+    pub(crate) fn normalise(&self, kick_off: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        self.at(kick_off.naive_local())
+    }
+
+    /// Show the kick-off times of games read from the database in the competition time zone.
+    pub(crate) fn localise(&self, games: Vec<Game>) -> Vec<Game> {
+        games.into_iter()
+            .map(|g| Game { kick_off: self.local(g.kick_off.to_utc()), ..g })
+            .collect()
+    }
+}
+
+/// Reads the `[tipping]` config. Without one the defaults are used, but a config that doesn't
+/// parse stops the launch rather than quietly turning off whatever it set up.
+pub(crate) fn stage() -> AdHoc {
+    AdHoc::try_on_ignite("Tipping Config", |rocket| async {
+        if rocket.figment().find_value("tipping").is_err() {
+            tracing::warn!("No tipping config, using defaults");
+            return Ok(rocket.manage(TippingConfig::default()));
+        }
+        match rocket.figment().extract_inner::<TippingConfig>("tipping") {
+            Ok(config) => {
+                tracing::info!("Tipping config: {:?}", config);
+                Ok(rocket.manage(config))
+            }
+            Err(e) => {
+                tracing::error!("Invalid tipping config: {}", e);
+                Err(rocket)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn melbourne() -> TippingConfig {
+        TippingConfig { timezone: chrono_tz::Australia::Melbourne, ..TippingConfig::default() }
+    }
+
+    fn wall_clock(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn wall_clock_times_take_the_offset_in_force() {
+        let config = melbourne();
+        assert_eq!(config.at(wall_clock("2025-03-14 19:40")).to_rfc3339(), "2025-03-14T19:40:00+11:00");
+        assert_eq!(config.at(wall_clock("2025-06-14 19:40")).to_rfc3339(), "2025-06-14T19:40:00+10:00");
+    }
+
+    #[test]
+    fn times_in_the_daylight_saving_gap_move_forward() {
+        // Clocks in Melbourne went from 2:00 to 3:00 on 5 October 2025
+        let config = melbourne();
+        assert_eq!(config.at(wall_clock("2025-10-05 02:30")).to_rfc3339(), "2025-10-05T03:30:00+11:00");
+    }
+}
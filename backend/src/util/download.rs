@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use rocket::http::ContentType;
+use rocket::response::Responder;
+use rocket::{Request, Response};
+use std::io::Cursor;
+
+/// A file for the browser to save rather than show.
+pub(crate) struct Download {
+    file_name: String,
+    content_type: ContentType,
+    body: Vec<u8>,
+}
+
+impl Download {
+    /// Characters other than letters, digits, `-`, `_` and `.` are replaced in the file name
+    /// so it can go straight into the Content-Disposition header.
+    pub(crate) fn new(file_name: &str, content_type: ContentType, body: impl Into<Vec<u8>>) -> Self {
+        let file_name = file_name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+            .collect();
+        Download { file_name, content_type, body: body.into() }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for Download {
+    fn respond_to(self, _: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response::build()
+            .header(self.content_type)
+            .raw_header("Content-Disposition", format!("attachment; filename=\"{}\"", self.file_name))
+            .sized_body(self.body.len(), Cursor::new(self.body))
+            .ok()
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::reporting::{LeaderboardEntry, RoundReport};
+use crate::util::download::Download;
+use rocket::http::ContentType;
+use rust_xlsxwriter::{Format, Workbook};
+
+/// Report exports are CSV unless asked for as an Excel workbook.
+#[derive(Debug, Clone, Copy, PartialEq, Default, FromFormField)]
+pub(crate) enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+pub(crate) enum Cell {
+    Text(String),
+    Number(f64),
+    Blank,
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Number(value as f64)
+    }
+}
+
+impl From<i32> for Cell {
+    fn from(value: i32) -> Self {
+        Cell::Number(value as f64)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::Blank, Into::into)
+    }
+}
+
+/// One table of a report, a worksheet in a workbook.
+pub(crate) struct Sheet {
+    pub(crate) name: String,
+    pub(crate) headers: Vec<&'static str>,
+    pub(crate) rows: Vec<Vec<Cell>>,
+}
+
+pub(crate) fn leaderboard_sheet(entries: &[LeaderboardEntry]) -> Sheet {
+    Sheet {
+        name: "Leaderboard".to_string(),
+        headers: vec!["Rank", "Tipper", "Game Score", "Bonus Score", "Total Score", "Margin Error"],
+        rows: entries.iter().map(|e| vec![
+            e.rank.into(),
+            e.tipper_name.as_str().into(),
+            e.tip_score.into(),
+            e.bonus_score.into(),
+            e.total_score.into(),
+            e.margin_error.into(),
+        ]).collect(),
+    }
+}
+
+pub(crate) fn scores_sheet(report: &RoundReport) -> Sheet {
+    Sheet {
+        name: format!("Round {}", report.round_number),
+        headers: vec!["Round", "Rank", "Tipper", "Game Score", "Bonus Score", "Total Score", "Margin Error"],
+        rows: report.scores.iter().map(|e| vec![
+            report.round_number.into(),
+            e.rank.into(),
+            e.tipper_name.as_str().into(),
+            e.tip_score.into(),
+            e.bonus_score.into(),
+            e.total_score.into(),
+            e.margin_error.into(),
+        ]).collect(),
+    }
+}
+
+pub(crate) fn tips_sheet(report: &RoundReport) -> Sheet {
+    Sheet {
+        name: format!("Round {}", report.round_number),
+        headers: vec!["Round", "Tipper", "Kick-off", "Home", "Away", "Tip", "Margin", "Correct"],
+        rows: report.tips.iter().map(|t| vec![
+            report.round_number.into(),
+            t.tipper_name.as_str().into(),
+            t.kick_off.format("%Y-%m-%d %H:%M").to_string().into(),
+            t.home_team_name.as_str().into(),
+            t.away_team_name.as_str().into(),
+            t.tipped_team_name.as_deref().into(),
+            t.margin.into(),
+            t.correct.map(|c| if c { "Yes" } else { "No" }).into(),
+        ]).collect(),
+    }
+}
+
+/// The rows of every sheet one after the other under the first sheet's headers,
+/// so the sheets should all have the same columns.
+pub(crate) fn to_csv(sheets: &[Sheet]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    if let Some(first) = sheets.first() {
+        writer.write_record(&first.headers).map_err(|e| e.to_string())?;
+    }
+    for sheet in sheets {
+        for row in &sheet.rows {
+            let record = row.iter().map(|cell| match cell {
+                Cell::Text(text) => text.clone(),
+                Cell::Number(number) => number.to_string(),
+                Cell::Blank => String::new(),
+            });
+            writer.write_record(record).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+/// A workbook with a worksheet for each sheet.
+pub(crate) fn to_xlsx(sheets: &[Sheet]) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&sheet.name).map_err(|e| e.to_string())?;
+        for (col, header) in sheet.headers.iter().enumerate() {
+            worksheet.write_string_with_format(0, col as u16, *header, &bold).map_err(|e| e.to_string())?;
+        }
+        for (i, row) in sheet.rows.iter().enumerate() {
+            let row_number = i as u32 + 1;
+            for (col, cell) in row.iter().enumerate() {
+                match cell {
+                    Cell::Text(text) => worksheet.write_string(row_number, col as u16, text),
+                    Cell::Number(number) => worksheet.write_number(row_number, col as u16, *number),
+                    Cell::Blank => continue,
+                }.map_err(|e| e.to_string())?;
+            }
+        }
+        worksheet.set_freeze_panes(1, 0).map_err(|e| e.to_string())?;
+        worksheet.autofit();
+    }
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
+
+/// The sheets as a file called `name` with the format's extension.
+pub(crate) fn download(name: &str, format: ExportFormat, sheets: &[Sheet]) -> Result<Download, String> {
+    match format {
+        ExportFormat::Csv => Ok(Download::new(&format!("{}.csv", name), ContentType::CSV, to_csv(sheets)?)),
+        ExportFormat::Xlsx => {
+            let content_type = ContentType::new("application", "vnd.openxmlformats-officedocument.spreadsheetml.sheet");
+            Ok(Download::new(&format!("{}.xlsx", name), content_type, to_xlsx(sheets)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(name: &str, rows: Vec<Vec<Cell>>) -> Sheet {
+        Sheet { name: name.to_string(), headers: vec!["Round", "Tipper", "Margin"], rows }
+    }
+
+    #[test]
+    fn csv_puts_every_sheet_under_one_header() {
+        let sheets = [
+            sheet("Round 1", vec![vec![1.into(), "Sam, Jr".into(), Some(12).into()]]),
+            sheet("Round 2", vec![vec![2.into(), "Alex".into(), None::<i32>.into()]]),
+        ];
+        let csv = String::from_utf8(to_csv(&sheets).unwrap()).unwrap();
+        assert_eq!(csv, "Round,Tipper,Margin\n1,\"Sam, Jr\",12\n2,Alex,\n");
+    }
+
+    #[test]
+    fn xlsx_is_a_workbook() {
+        let sheets = [
+            sheet("Round 1", vec![vec![1.into(), "Sam".into(), Some(12).into()]]),
+            sheet("Round 2", vec![]),
+        ];
+        let xlsx = to_xlsx(&sheets).unwrap();
+        // An xlsx file is a zip archive
+        assert_eq!(&xlsx[..2], b"PK");
+    }
+}
@@ -22,32 +22,68 @@
  *
  */
 
-use chrono::NaiveDate;
+use crate::util::config::TippingConfig;
+use chrono::{Duration, NaiveDate, Timelike};
 use kelpie_models::game::Game;
 use kelpie_models::team::Team;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::BTreeMap;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeMap, HashMap};
 
-pub(crate) fn allocate_games(round_id: i32, teams: &Vec<Team>, start: NaiveDate, end: NaiveDate) -> Vec<Game> {
-    // Create a list of days between start and end dates
-    let mut days = Vec::new();
-    let mut current_date = start;
-    while current_date <= end {
-        days.push(current_date); // Store day names
-        current_date = current_date.succ_opt().unwrap(); // Move to the next day
-    }
+// Gap between kick-offs when more than one game is played on a day, closer if that would run past midnight
+const KICK_OFF_SPACING_HOURS: i64 = 3;
 
-    let mut rng = thread_rng();
-    let mut shuffled_teams = teams.clone();
+/// Games for a single round between `teams`, with the teams left over given a bye.
+///
+/// With an odd number of teams the bye goes to a team with the fewest byes in `bye_counts`,
+/// so every team has a bye before any team has a second.
+///
+/// The draw is random, but the same `seed` always gives the same draw for the same teams.
+pub(crate) fn allocate_games(round_id: i32, teams: &Vec<Team>, bye_counts: &HashMap<i32, usize>,
+                             start: NaiveDate, end: NaiveDate, seed: u64, config: &TippingConfig) -> (Vec<Game>, Vec<i32>) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut shuffled_teams: Vec<i32> = teams.iter().filter_map(|t| t.id).collect();
     shuffled_teams.shuffle(&mut rng);
 
+    // Ties on byes are broken by the shuffle
+    let mut byes = Vec::new();
+    if shuffled_teams.len() % 2 == 1 {
+        let (i, _) = shuffled_teams.iter()
+            .enumerate()
+            .min_by_key(|(_, id)| bye_counts.get(id).copied().unwrap_or(0))
+            .unwrap();
+        byes.push(shuffled_teams.remove(i));
+    }
+
     // Pair teams into games
-    let mut games = Vec::new();
-    for pair in shuffled_teams.chunks(2) {
-        if let [a, b] = pair {
-            games.push((a.clone(), b.clone()));
-        }
+    let pairs = shuffled_teams.chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    (schedule(round_id, pairs, &home_venues(teams), start, end, config), byes)
+}
+
+/// The home ground of each team that has one.
+pub(crate) fn home_venues(teams: &[Team]) -> HashMap<i32, i32> {
+    teams.iter()
+        .filter_map(|t| Some((t.id?, t.home_venue_id?)))
+        .collect()
+}
+
+/// Spread the `(home, away)` games of a round over its days, busiest in the middle of the round,
+/// staggering the kick-offs through each day.
+///
+/// Each game is played at the home team's ground from `home_venues`. Teams sharing a ground are
+/// put on different days, even if that makes a day busier than planned, and only share a day
+/// when every day already has a game at that ground.
+pub(crate) fn schedule(round_id: i32, games: Vec<(i32, i32)>, home_venues: &HashMap<i32, i32>,
+                       start: NaiveDate, end: NaiveDate, config: &TippingConfig) -> Vec<Game> {
+    // Create a list of days between start and end dates
+    let mut days = Vec::new();
+    let mut current_date = start;
+    while current_date <= end {
+        days.push(current_date);
+        current_date = current_date.succ_opt().unwrap();
     }
 
     let num_games = games.len();
@@ -64,26 +100,37 @@ pub(crate) fn allocate_games(round_id: i32, teams: &Vec<Team>, start: NaiveDate,
         games_per_day[i] += 1;
     }
 
-    // Assign games to days
-    let mut schedule: BTreeMap<NaiveDate, Vec<(Team, Team)>> = BTreeMap::new();
-    let mut game_iter = games.into_iter();
-
-    for (i, &day) in days.iter().enumerate() {
-        let count = games_per_day[i];
-        let day_games: Vec<_> = game_iter.by_ref().take(count).collect();
-        schedule.insert(day.clone(), day_games);
+    // Assign games to days, in order, keeping each ground to one game a day
+    let mut day_games: Vec<Vec<(i32, i32)>> = vec![vec![]; num_days];
+    for (home, away) in games {
+        let venue = home_venues.get(&home);
+        let ground_free = |day: &Vec<(i32, i32)>| {
+            venue.is_none() || !day.iter().any(|(other, _)| home_venues.get(other) == venue)
+        };
+        let i = (0..num_days)
+            .find(|&i| day_games[i].len() < games_per_day[i] && ground_free(&day_games[i]))
+            .or_else(|| (0..num_days).filter(|&i| ground_free(&day_games[i])).min_by_key(|&i| day_games[i].len()))
+            .or_else(|| (0..num_days).find(|&i| day_games[i].len() < games_per_day[i]))
+            .unwrap_or(0);
+        day_games[i].push((home, away));
     }
+    let schedule: BTreeMap<NaiveDate, Vec<(i32, i32)>> = days.into_iter().zip(day_games).collect();
 
-    // convert to Game objects
+    // convert to Game objects, staggering the kick-offs through each day
+    let minutes_left = 24 * 60 - i64::from(config.first_kick_off.num_seconds_from_midnight() / 60);
     let mut game_objects = Vec::new();
     for (day, day_games) in schedule {
-        for (team1, team2) in day_games {
+        let first_kick_off = day.and_time(config.first_kick_off);
+        let spacing = Duration::hours(KICK_OFF_SPACING_HOURS).min(Duration::minutes(minutes_left / day_games.len().max(1) as i64));
+        for (i, (home_team_id, away_team_id)) in day_games.into_iter().enumerate() {
+            let kick_off = first_kick_off + spacing * i as i32;
             game_objects.push(Game {
                 game_id: None,
                 round_id: Some(round_id),
-                home_team_id: team1.id.unwrap(),
-                away_team_id: team2.id.unwrap(),
-                game_date: day.clone(),
+                home_team_id,
+                away_team_id,
+                kick_off: config.at(kick_off),
+                venue_id: home_venues.get(&home_team_id).copied(),
                 home_team_score: None,
                 away_team_score: None,
             });
@@ -91,3 +138,245 @@ pub(crate) fn allocate_games(round_id: i32, teams: &Vec<Team>, start: NaiveDate,
     }
     game_objects
 }
+
+/// One round of a generated draw.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DrawRound {
+    /// `(home, away)` team ids
+    pub(crate) games: Vec<(i32, i32)>,
+    /// Teams without a game this round
+    pub(crate) byes: Vec<i32>,
+}
+
+/// A round-robin draw of `rounds` rounds.
+///
+/// Pairings come from the circle method, so every team meets every other team once in each
+/// `n - 1` rounds (`n` rounded up to even) and repeat match-ups are as far apart as they can be.
+/// With an odd number of teams the missing opponent is a bye, so byes rotate through all the teams
+/// before anyone gets a second. Home and away alternate as far as the circle allows, so they are
+/// balanced to within a game over the first cycle. Each later cycle swaps home and away, so a
+/// double round-robin is every match-up once at each end.
+///
+/// The same `seed` always gives the same draw for the same teams.
+pub(crate) fn round_robin(team_ids: &[i32], rounds: usize, seed: u64) -> Vec<DrawRound> {
+    if team_ids.len() < 2 {
+        return vec![];
+    }
+    let mut slots: Vec<Option<i32>> = team_ids.iter().copied().map(Some).collect();
+    slots.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+    // The bye is the fixed slot, so every team meets it once a cycle
+    if slots.len() % 2 == 1 {
+        slots.insert(0, None);
+    }
+    let n = slots.len();
+
+    let mut cycle = Vec::new();
+    for r in 0..n - 1 {
+        let mut round = DrawRound { games: vec![], byes: vec![] };
+        for i in 0..n / 2 {
+            // Each rotation moves a team to a pair of the other parity, so home and away alternate
+            // for everyone but the fixed slot, which alternates by round instead
+            let first_home = if i == 0 { r % 2 == 0 } else { i % 2 == 0 };
+            match (slots[i], slots[n - 1 - i]) {
+                (Some(a), Some(b)) => round.games.push(if first_home { (a, b) } else { (b, a) }),
+                (Some(team), None) | (None, Some(team)) => round.byes.push(team),
+                (None, None) => {}
+            }
+        }
+        cycle.push(round);
+        // Keep the first slot fixed and rotate the rest
+        slots[1..].rotate_right(1);
+    }
+
+    (0..rounds).map(|r| {
+        let round = &cycle[r % cycle.len()];
+        if (r / cycle.len()) % 2 == 1 {
+            DrawRound { games: round.games.iter().map(|&(home, away)| (away, home)).collect(), byes: round.byes.clone() }
+        } else {
+            round.clone()
+        }
+    }).collect()
+}
+
+/// Rounds needed for every team to meet every other team once, or twice for a double round-robin.
+pub(crate) fn round_robin_length(teams: usize, double: bool) -> usize {
+    let cycle = if teams % 2 == 1 { teams } else { teams.saturating_sub(1) };
+    if double { cycle * 2 } else { cycle }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::team;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn teams(n: usize) -> Vec<Team> {
+        (1..=n as i32).map(|id| team(id, &format!("Team {}", id), "")).collect()
+    }
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 14).unwrap()
+    }
+
+    /// Games on each day from `start` to `end`
+    fn games_per_day(games: &[Game], start: NaiveDate, end: NaiveDate) -> Vec<usize> {
+        start.iter_days()
+            .take_while(|day| *day <= end)
+            .map(|day| games.iter().filter(|g| g.kick_off.date_naive() == day).count())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn every_team_plays_at_most_once(n in 0usize..24, days in 0i64..7, seed in any::<u64>()) {
+            let teams = teams(n);
+            let (games, byes) = allocate_games(1, &teams, &HashMap::new(), start(), start() + Duration::days(days),
+                                               seed, &TippingConfig::default());
+            let mut seen = HashSet::new();
+            for g in &games {
+                prop_assert_ne!(g.home_team_id, g.away_team_id);
+                prop_assert!(seen.insert(g.home_team_id), "team {} plays twice", g.home_team_id);
+                prop_assert!(seen.insert(g.away_team_id), "team {} plays twice", g.away_team_id);
+            }
+            for team_id in &byes {
+                prop_assert!(seen.insert(*team_id), "team {} plays and has a bye", team_id);
+            }
+            prop_assert_eq!(seen.len(), n);
+            prop_assert_eq!(byes.len(), n % 2);
+        }
+
+        #[test]
+        fn every_team_has_a_bye_before_a_second(n in 1usize..12, seed in any::<u64>()) {
+            let n = n * 2 + 1;
+            let teams = teams(n);
+            let mut bye_counts: HashMap<i32, usize> = HashMap::new();
+            for round in 0..n as u64 * 3 {
+                let (_, byes) = allocate_games(1, &teams, &bye_counts, start(), start(), seed.wrapping_add(round), &TippingConfig::default());
+                for team_id in byes {
+                    *bye_counts.entry(team_id).or_default() += 1;
+                }
+                let counts: Vec<usize> = (1..=n as i32).map(|id| bye_counts.get(&id).copied().unwrap_or(0)).collect();
+                let most = counts.iter().max().copied().unwrap_or(0);
+                let fewest = counts.iter().min().copied().unwrap_or(0);
+                prop_assert!(most - fewest <= 1, "after round {} byes are {:?}", round + 1, counts);
+            }
+        }
+
+        #[test]
+        fn games_are_spread_across_days(n in 0usize..17, days in 0i64..7, seed in any::<u64>()) {
+            let end = start() + Duration::days(days);
+            let (games, _) = allocate_games(1, &teams(n), &HashMap::new(), start(), end, seed, &TippingConfig::default());
+            let per_day = games_per_day(&games, start(), end);
+            prop_assert_eq!(per_day.iter().sum::<usize>(), games.len());
+            let busiest = per_day.iter().max().copied().unwrap_or(0);
+            let quietest = per_day.iter().min().copied().unwrap_or(0);
+            prop_assert!(busiest - quietest <= 1, "games per day {:?}", per_day);
+        }
+
+        #[test]
+        fn kick_offs_stay_on_their_day(n in 0usize..24, days in 0i64..7, seed in any::<u64>()) {
+            let config = TippingConfig::default();
+            let end = start() + Duration::days(days);
+            let (games, _) = allocate_games(1, &teams(n), &HashMap::new(), start(), end, seed, &config);
+            for g in &games {
+                let kick_off = g.kick_off.naive_local();
+                prop_assert!(kick_off.date() >= start() && kick_off.date() <= end, "{} is outside the round", kick_off);
+                prop_assert!(kick_off.time() >= config.first_kick_off, "{} is before the first kick-off", kick_off);
+            }
+        }
+
+        #[test]
+        fn same_seed_same_draw(n in 0usize..24, seed in any::<u64>()) {
+            let teams = teams(n);
+            let draw = || {
+                let (games, byes) = allocate_games(1, &teams, &HashMap::new(), start(), start() + Duration::days(3),
+                                                   seed, &TippingConfig::default());
+                (games.iter().map(|g| (g.home_team_id, g.away_team_id, g.kick_off)).collect::<Vec<_>>(), byes)
+            };
+            prop_assert_eq!(draw(), draw());
+        }
+
+        #[test]
+        fn shared_grounds_are_on_different_days(n in 0usize..17, days in 0i64..7, seed in any::<u64>(),
+                                                 grounds in prop::collection::vec(0i32..4, 16)) {
+            // No more teams at a ground than there are days, so every ground can have a game each day
+            let end = start() + Duration::days(days);
+            let mut home_venues = HashMap::new();
+            for (team_id, venue_id) in (1..=n as i32).zip(grounds) {
+                if home_venues.values().filter(|&&v| v == venue_id).count() <= days as usize {
+                    home_venues.insert(team_id, venue_id);
+                }
+            }
+            let teams: Vec<Team> = teams(n).into_iter()
+                .map(|t| Team { home_venue_id: t.id.and_then(|id| home_venues.get(&id).copied()), ..t })
+                .collect();
+            let (games, _) = allocate_games(1, &teams, &HashMap::new(), start(), end, seed, &TippingConfig::default());
+            let mut used = HashSet::new();
+            for g in &games {
+                prop_assert_eq!(g.venue_id, home_venues.get(&g.home_team_id).copied());
+                if let Some(venue_id) = g.venue_id {
+                    prop_assert!(used.insert((g.kick_off.date_naive(), venue_id)), "venue {} used twice on a day", venue_id);
+                }
+            }
+        }
+
+        #[test]
+        fn round_robin_teams_play_at_most_once_a_round(n in 0usize..20, rounds in 0usize..40, seed in any::<u64>()) {
+            let team_ids: Vec<i32> = (1..=n as i32).collect();
+            for round in round_robin(&team_ids, rounds, seed) {
+                let mut seen = HashSet::new();
+                for (home, away) in &round.games {
+                    prop_assert!(seen.insert(*home));
+                    prop_assert!(seen.insert(*away));
+                }
+                for team_id in &round.byes {
+                    prop_assert!(seen.insert(*team_id));
+                }
+                prop_assert_eq!(seen.len(), if n < 2 { 0 } else { n });
+            }
+        }
+
+        #[test]
+        fn round_robin_pairs_meet_once_a_cycle(n in 2usize..20, cycles in 1usize..4, seed in any::<u64>()) {
+            let team_ids: Vec<i32> = (1..=n as i32).collect();
+            let cycle = round_robin_length(n, false);
+            let draw = round_robin(&team_ids, cycle * cycles, seed);
+            for rounds in draw.chunks(cycle) {
+                let mut pairs = HashSet::new();
+                for &(home, away) in rounds.iter().flat_map(|r| &r.games) {
+                    prop_assert!(pairs.insert((home.min(away), home.max(away))), "{} and {} meet twice", home, away);
+                }
+                prop_assert_eq!(pairs.len(), n * (n - 1) / 2);
+            }
+        }
+
+        #[test]
+        fn round_robin_balances_home_and_away(n in 2usize..20, double in any::<bool>(), seed in any::<u64>()) {
+            let team_ids: Vec<i32> = (1..=n as i32).collect();
+            let draw = round_robin(&team_ids, round_robin_length(n, double), seed);
+            for team_id in &team_ids {
+                let home = draw.iter().flat_map(|r| &r.games).filter(|g| g.0 == *team_id).count();
+                let away = draw.iter().flat_map(|r| &r.games).filter(|g| g.1 == *team_id).count();
+                prop_assert!(home.abs_diff(away) <= 1, "team {} has {} home and {} away", team_id, home, away);
+            }
+        }
+
+        #[test]
+        fn round_robin_byes_rotate(n in 1usize..10, seed in any::<u64>()) {
+            let n = n * 2 + 1;
+            let team_ids: Vec<i32> = (1..=n as i32).collect();
+            let draw = round_robin(&team_ids, n * 2, seed);
+            for rounds in draw.chunks(n) {
+                let byes: HashSet<i32> = rounds.iter().flat_map(|r| r.byes.iter().copied()).collect();
+                prop_assert_eq!(byes.len(), n, "byes {:?}", rounds.iter().map(|r| &r.byes).collect::<Vec<_>>());
+            }
+        }
+
+        #[test]
+        fn round_robin_same_seed_same_draw(n in 0usize..20, seed in any::<u64>()) {
+            let team_ids: Vec<i32> = (1..=n as i32).collect();
+            prop_assert_eq!(round_robin(&team_ids, n, seed), round_robin(&team_ids, n, seed));
+        }
+    }
+}
@@ -0,0 +1,383 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::{game, round, team};
+use crate::util::config::TippingConfig;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+use kelpie_models::team::Team;
+use rocket::serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+
+/// One line of a fixtures or results file. Scores are left out for fixtures.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ImportRow {
+    pub(crate) round: i32,
+    /// "2025-03-14 19:40", or just the date to use the configured first kick-off
+    pub(crate) date: String,
+    /// Team name or nickname
+    pub(crate) home: String,
+    pub(crate) away: String,
+    #[serde(default)]
+    pub(crate) home_score: Option<i32>,
+    #[serde(default)]
+    pub(crate) away_score: Option<i32>,
+}
+
+/// Read the rows of a JSON array, or a CSV file with a `round,date,home,away,home_score,away_score` header.
+pub(crate) fn parse(text: &str, json: bool) -> Result<Vec<ImportRow>, String> {
+    if json {
+        return rocket::serde::json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e));
+    }
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(text.as_bytes());
+    reader.deserialize()
+        .enumerate()
+        .map(|(i, row)| row.map_err(|e| format!("Row {}: {}", i + 1, e)))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub(crate) enum ImportAction {
+    AddGame,
+    /// The game is already in the round but kicks off at a different time
+    MoveGame,
+    SetResult,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ImportChange {
+    /// 1 for the first row after any header
+    pub(crate) row: usize,
+    pub(crate) action: ImportAction,
+    pub(crate) round_number: i32,
+    pub(crate) home_team: String,
+    pub(crate) away_team: String,
+    pub(crate) kick_off: DateTime<FixedOffset>,
+    pub(crate) home_team_score: Option<i32>,
+    pub(crate) away_team_score: Option<i32>,
+    #[serde(skip)]
+    game_id: Option<i32>,
+    /// The existing game's venue is kept, new games are at the home team's ground
+    #[serde(skip)]
+    venue_id: Option<i32>,
+    #[serde(skip)]
+    home_team_id: i32,
+    #[serde(skip)]
+    away_team_id: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RowError {
+    pub(crate) row: usize,
+    pub(crate) message: String,
+}
+
+/// What an import will do, or did. Nothing is applied if any row has an error.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ImportReport {
+    pub(crate) applied: bool,
+    /// Numbers of the rounds to be created
+    pub(crate) new_rounds: Vec<i32>,
+    pub(crate) changes: Vec<ImportChange>,
+    pub(crate) errors: Vec<RowError>,
+}
+
+/// Look up table of team ids and names by name or nickname, see [`find_team`].
+pub(crate) fn team_names(teams: Vec<Team>) -> HashMap<String, (i32, String)> {
+    let mut names = HashMap::new();
+    for team in teams {
+        let Some(id) = team.id else { continue };
+        names.insert(team.nickname.trim().to_lowercase(), (id, team.name.clone()));
+        names.insert(team.name.trim().to_lowercase(), (id, team.name));
+    }
+    names
+}
+
+/// The id and name of the team with `name` as its name or nickname, ignoring case.
+pub(crate) fn find_team(names: &HashMap<String, (i32, String)>, name: &str) -> Option<(i32, String)> {
+    names.get(&name.trim().to_lowercase()).cloned()
+}
+
+fn parse_kick_off(date: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(date, format) {
+            return Some((dt.date(), Some(dt.time())));
+        }
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok().map(|d| (d, None))
+}
+
+/// The season's rounds and their games, by round number.
+async fn season_rounds(pool: &mut PgConnection, config: &TippingConfig, season_id: i32)
+    -> Result<HashMap<i32, (Round, Vec<Game>)>, sqlx::Error> {
+    let mut rounds = HashMap::new();
+    for r in round::get_all(pool, Some(season_id)).await? {
+        let Some(round_id) = r.round_id else { continue };
+        let games = config.localise(game::get_for_round(pool, round_id).await?);
+        rounds.insert(r.round_number, (r, games));
+    }
+    Ok(rounds)
+}
+
+/// The checks made when a round is saved, see `routes::rounds::validate_common`, applied to the rounds
+/// as they will be after the changes. A team can only play once in a round, and games in an existing
+/// round must fall between its start and end dates. New rounds are sized to fit their games.
+fn round_errors(rounds: &HashMap<i32, (Round, Vec<Game>)>, changes: &[ImportChange]) -> Vec<RowError> {
+    let mut errors = vec![];
+    let mut playing: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (&round_number, (_, games)) in rounds {
+        let teams = playing.entry(round_number).or_default();
+        for game in games.iter().filter(|g| !changes.iter().any(|c| c.game_id.is_some() && c.game_id == g.game_id)) {
+            teams.extend([game.home_team_id, game.away_team_id]);
+        }
+    }
+    for change in changes {
+        let teams = playing.entry(change.round_number).or_default();
+        for (id, name) in [(change.home_team_id, &change.home_team), (change.away_team_id, &change.away_team)] {
+            if teams.contains(&id) {
+                errors.push(RowError {
+                    row: change.row,
+                    message: format!("{} is used more than once in round {}", name, change.round_number),
+                });
+            }
+        }
+        teams.extend([change.home_team_id, change.away_team_id]);
+
+        let Some((round, _)) = rounds.get(&change.round_number) else { continue };
+        let date = change.kick_off.date_naive();
+        if date < round.start_date || date > round.end_date {
+            errors.push(RowError {
+                row: change.row,
+                message: format!("Game date {} is not between the round {} start date {} and end date {}",
+                                 date.format("%Y-%m-%d"), round.round_number,
+                                 round.start_date.format("%Y-%m-%d"), round.end_date.format("%Y-%m-%d")),
+            });
+        }
+    }
+    errors
+}
+
+/// Work out the changes the rows make to a season, without changing anything.
+pub(crate) async fn plan(pool: &mut PgConnection, config: &TippingConfig, season_id: i32, rows: &[ImportRow])
+    -> Result<ImportReport, sqlx::Error> {
+    let teams = team_names(team::get_all(pool, Some(season_id)).await?);
+    let rounds = season_rounds(pool, config, season_id).await?;
+
+    let mut report = ImportReport { applied: false, new_rounds: vec![], changes: vec![], errors: vec![] };
+    let mut seen: HashMap<(i32, i32, i32), usize> = HashMap::new();
+    for (i, import) in rows.iter().enumerate() {
+        let row = i + 1;
+        let mut error = |message: String| report.errors.push(RowError { row, message });
+
+        let team = |name: &str| find_team(&teams, name);
+        let (Some((home_team_id, home_team)), Some((away_team_id, away_team))) = (team(&import.home), team(&import.away)) else {
+            for name in [&import.home, &import.away] {
+                if team(name).is_none() {
+                    error(format!("No team called \"{}\" in this season", name));
+                }
+            }
+            continue;
+        };
+        if home_team_id == away_team_id {
+            error(format!("{} can't play itself", home_team));
+            continue;
+        }
+        if import.round < 1 {
+            error(format!("Round {} is not a round number, rounds start at 1", import.round));
+            continue;
+        }
+        let Some((date, time)) = parse_kick_off(&import.date) else {
+            error(format!("Unrecognised date \"{}\", use YYYY-MM-DD or YYYY-MM-DD HH:MM", import.date));
+            continue;
+        };
+        if import.home_score.is_some() != import.away_score.is_some() {
+            error("Give both scores, or neither for a fixture".to_string());
+            continue;
+        }
+        if import.home_score.is_some_and(|s| s < 0) || import.away_score.is_some_and(|s| s < 0) {
+            error("Scores can't be negative".to_string());
+            continue;
+        }
+        if let Some(first) = seen.insert((import.round, home_team_id, away_team_id), row) {
+            error(format!("The same game is in row {}", first));
+            continue;
+        }
+
+        let existing = rounds.get(&import.round)
+            .and_then(|(_, games)| games.iter().find(|g| g.home_team_id == home_team_id && g.away_team_id == away_team_id));
+        if !rounds.contains_key(&import.round) && !report.new_rounds.contains(&import.round) {
+            report.new_rounds.push(import.round);
+        }
+
+        // A date without a time keeps an existing game's kick-off on that day
+        let kick_off = match (time, existing) {
+            (Some(time), _) => config.at(date.and_time(time)),
+            (None, Some(game)) if game.kick_off.date_naive() == date => game.kick_off,
+            (None, _) => config.at(date.and_time(config.first_kick_off)),
+        };
+        let (home_team_score, away_team_score) = match existing {
+            Some(game) if import.home_score.is_none() => (game.home_team_score, game.away_team_score),
+            _ => (import.home_score, import.away_score),
+        };
+        let action = match existing {
+            None => ImportAction::AddGame,
+            Some(game) if game.kick_off != kick_off => ImportAction::MoveGame,
+            Some(game) if (game.home_team_score, game.away_team_score) != (home_team_score, away_team_score) => ImportAction::SetResult,
+            Some(_) => ImportAction::Unchanged,
+        };
+        report.changes.push(ImportChange {
+            row,
+            action,
+            round_number: import.round,
+            home_team,
+            away_team,
+            kick_off,
+            home_team_score,
+            away_team_score,
+            game_id: existing.and_then(|g| g.game_id),
+            venue_id: existing.and_then(|g| g.venue_id),
+            home_team_id,
+            away_team_id,
+        });
+    }
+    let errors = round_errors(&rounds, &report.changes);
+    report.errors.extend(errors);
+    report.errors.sort_by_key(|e| e.row);
+    report.new_rounds.sort();
+    Ok(report)
+}
+
+/// Make the planned changes, the caller is expected to run this in a transaction.
+///
+/// The round checks are made again against the season as it is now, nothing is changed if they fail.
+pub(crate) async fn apply(pool: &mut PgConnection, config: &TippingConfig, season_id: i32, report: &mut ImportReport)
+    -> Result<(), sqlx::Error> {
+    let rounds = season_rounds(pool, config, season_id).await?;
+    report.errors.extend(round_errors(&rounds, &report.changes));
+    if !report.errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut round_ids: HashMap<i32, i32> = rounds.values()
+        .filter_map(|(r, _)| r.round_id.map(|id| (r.round_number, id)))
+        .collect();
+
+    // New rounds run from their first game to their last
+    for &round_number in &report.new_rounds {
+        let dates: Vec<NaiveDate> = report.changes.iter()
+            .filter(|c| c.round_number == round_number)
+            .map(|c| c.kick_off.date_naive())
+            .collect();
+        let (Some(&start), Some(&end)) = (dates.iter().min(), dates.iter().max()) else { continue };
+        let new_round = round::insert(pool, season_id, round_number, start, end, 0).await?;
+        if let Some(id) = new_round.round_id {
+            round_ids.insert(round_number, id);
+        }
+    }
+
+    for change in &report.changes {
+        match (change.action, change.game_id) {
+            (ImportAction::AddGame, _) => {
+                let Some(&round_id) = round_ids.get(&change.round_number) else { continue };
+                game::insert(pool, round_id, change.home_team_id, change.away_team_id, change.kick_off,
+                             change.venue_id, change.home_team_score, change.away_team_score).await?;
+            }
+            (ImportAction::MoveGame | ImportAction::SetResult, Some(game_id)) => {
+                game::update(pool, game_id, change.home_team_id, change.away_team_id, change.kick_off,
+                             change.venue_id, change.home_team_score, change.away_team_score).await?;
+            }
+            _ => {}
+        }
+    }
+    report.applied = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, round};
+
+    fn change(row: usize, round_number: i32, home: (i32, &str), away: (i32, &str), kick_off: &str, game_id: Option<i32>) -> ImportChange {
+        ImportChange {
+            row,
+            action: if game_id.is_some() { ImportAction::MoveGame } else { ImportAction::AddGame },
+            round_number,
+            home_team: home.1.to_string(),
+            away_team: away.1.to_string(),
+            kick_off: DateTime::parse_from_rfc3339(kick_off).unwrap(),
+            home_team_score: None,
+            away_team_score: None,
+            game_id,
+            venue_id: None,
+            home_team_id: home.0,
+            away_team_id: away.0,
+        }
+    }
+
+    fn rounds() -> HashMap<i32, (Round, Vec<Game>)> {
+        let games = vec![game(1, 1, 2, "2025-03-14T19:40:00+11:00", None)];
+        HashMap::from([(1, (round(1, "2025-03-13", "2025-03-16"), games))])
+    }
+
+    #[test]
+    fn a_team_plays_once_a_round() {
+        let changes = vec![
+            change(1, 1, (3, "Carlton"), (1, "Geelong"), "2025-03-15T19:40:00+11:00", None),
+            change(2, 2, (3, "Carlton"), (4, "Essendon"), "2025-03-21T19:40:00+11:00", None),
+            change(3, 2, (4, "Essendon"), (5, "Richmond"), "2025-03-22T19:40:00+11:00", None),
+        ];
+        let errors = round_errors(&rounds(), &changes);
+        let rows: Vec<(usize, &str)> = errors.iter().map(|e| (e.row, e.message.as_str())).collect();
+        assert_eq!(rows, vec![(1, "Geelong is used more than once in round 1"), (3, "Essendon is used more than once in round 2")]);
+    }
+
+    #[test]
+    fn a_moved_game_frees_its_teams() {
+        let changes = vec![
+            change(1, 1, (1, "Geelong"), (2, "Collingwood"), "2025-03-16T15:20:00+11:00", Some(1)),
+        ];
+        assert!(round_errors(&rounds(), &changes).is_empty());
+    }
+
+    #[test]
+    fn games_stay_within_their_round() {
+        let changes = vec![
+            change(1, 1, (1, "Geelong"), (2, "Collingwood"), "2025-03-17T19:40:00+11:00", Some(1)),
+            change(2, 1, (3, "Carlton"), (4, "Essendon"), "2025-03-12T19:40:00+11:00", None),
+            change(3, 2, (3, "Carlton"), (4, "Essendon"), "2025-04-30T19:40:00+10:00", None),
+        ];
+        let rows: Vec<usize> = round_errors(&rounds(), &changes).iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![1, 2]);
+        assert_eq!(round_errors(&rounds(), &changes)[0].message,
+                   "Game date 2025-03-17 is not between the round 1 start date 2025-03-13 and end date 2025-03-16");
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::util::config::{MailConfig, MailTransportConfig, SmtpSecurity};
+use chrono::Utc;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rocket::serde::Serialize;
+use rocket::tokio::io::AsyncWriteExt;
+use std::path::PathBuf;
+
+/// A plain text email to one tipper.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Email {
+    pub(crate) to_name: String,
+    pub(crate) to: String,
+    pub(crate) subject: String,
+    pub(crate) body: String,
+}
+
+/// Somewhere to send email.
+#[rocket::async_trait]
+pub(crate) trait MailTransport: Send + Sync {
+    /// Used in the log
+    fn name(&self) -> String;
+    async fn send(&self, email: &Email) -> Result<(), String>;
+}
+
+/// Email sent through an SMTP server.
+pub(crate) struct SmtpMailer {
+    from: Mailbox,
+    host: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub(crate) fn new(from: Mailbox, host: &str, port: Option<u16>, security: SmtpSecurity,
+                      username: Option<String>, password: Option<String>) -> Result<Self, String> {
+        let mut builder = match security {
+            SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(|e| e.to_string())?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(|e| e.to_string())?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        if let Some(port) = port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = username.filter(|u| !u.is_empty()) {
+            builder = builder.credentials(Credentials::new(username, password.unwrap_or_default()));
+        }
+        Ok(SmtpMailer { from, host: host.to_string(), transport: builder.build() })
+    }
+}
+
+#[rocket::async_trait]
+impl MailTransport for SmtpMailer {
+    fn name(&self) -> String {
+        format!("SMTP {}", self.host)
+    }
+
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        let to = Mailbox::new(Some(email.to_name.clone()), email.to.parse().map_err(|e| format!("{}: {}", email.to, e))?);
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.clone())
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())
+            .map_err(|e| e.to_string())?;
+        self.transport.send(message).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// Email appended to a file rather than sent.
+pub(crate) struct FileMailer {
+    from: String,
+    path: PathBuf,
+}
+
+impl FileMailer {
+    pub(crate) fn new(from: String, path: PathBuf) -> Self {
+        FileMailer { from, path }
+    }
+}
+
+#[rocket::async_trait]
+impl MailTransport for FileMailer {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        let mut file = rocket::tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| e.to_string())?;
+        let text = format!("Date: {}\nFrom: {}\nTo: {} <{}>\nSubject: {}\n\n{}\n\n",
+                           Utc::now().to_rfc2822(), self.from, email.to_name, email.to, email.subject, email.body);
+        file.write_all(text.as_bytes()).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Email written to the log rather than sent.
+pub(crate) struct LogMailer;
+
+#[rocket::async_trait]
+impl MailTransport for LogMailer {
+    fn name(&self) -> String {
+        "the log".to_string()
+    }
+
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        tracing::info!("Email to {} <{}>: {}\n{}", email.to_name, email.to, email.subject, email.body);
+        Ok(())
+    }
+}
+
+pub(crate) fn transport(config: &MailConfig) -> Result<Box<dyn MailTransport>, String> {
+    Ok(match &config.transport {
+        MailTransportConfig::Smtp { host, port, security, username, password } => {
+            let from = config.from.parse::<Mailbox>().map_err(|e| format!("Invalid from address {}: {}", config.from, e))?;
+            Box::new(SmtpMailer::new(from, host, *port, *security, username.clone(), password.clone())?)
+        }
+        MailTransportConfig::File { path } => Box::new(FileMailer::new(config.from.clone(), path.clone())),
+        MailTransportConfig::Log => Box::new(LogMailer),
+    })
+}
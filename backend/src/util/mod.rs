@@ -24,6 +24,22 @@
 
 pub(crate) mod logging;
 pub(crate) mod game_allocator;
+pub(crate) mod config;
+pub(crate) mod tip_lock;
+pub(crate) mod scoring;
+pub(crate) mod auth;
+pub(crate) mod import;
+pub(crate) mod tipper_import;
+pub(crate) mod download;
+pub(crate) mod upload;
+pub(crate) mod export;
+pub(crate) mod pdf;
+pub(crate) mod results_provider;
+pub(crate) mod mail;
+pub(crate) mod reminders;
+pub(crate) mod round_summary;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 use rocket::http::Status;
 use rocket::response::Responder;
@@ -38,6 +54,8 @@ pub(crate) enum ApiError {
     Error(String),
     Invalid(String),
     NotFound(String),
+    Unauthorized(String),
+    Forbidden(String),
 }
 
 impl From<sqlx::Error> for ApiError {
@@ -58,6 +76,8 @@ impl<'r> Responder<'r, 'static> for ApiError {
             ApiError::Error(msg) => (Status::InternalServerError, msg),
             ApiError::Invalid(msg) => (Status::BadRequest, msg),
             ApiError::Db(e) => (Status::Conflict, e.to_string()),
+            ApiError::Unauthorized(msg) => (Status::Unauthorized, msg),
+            ApiError::Forbidden(msg) => (Status::Forbidden, msg),
         };
         let body = Json(ApiErrorMessage { error: msg });
         Response::build()
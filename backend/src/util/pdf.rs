@@ -0,0 +1,211 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::reporting::LeaderboardEntry;
+use printpdf::path::PaintMode;
+use printpdf::{BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rect};
+
+/// A4 portrait, in millimetres.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+
+/// Writes lines down A4 pages, starting a new page when one is full.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold: IndirectFontRef,
+    pages: usize,
+    /// Baseline of the current line, from the bottom of the page
+    y: f32,
+}
+
+impl Writer {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Page 1");
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+        let layer = doc.get_page(page).get_layer(layer);
+        layer.set_outline_thickness(0.5);
+        Ok(Writer { doc, layer, font, bold, pages: 1, y: PAGE_HEIGHT - MARGIN })
+    }
+
+    /// Move down to the next line, `height` below the current one. Returns true if it is on a new page.
+    fn next_line(&mut self, height: f32) -> bool {
+        if self.y - height >= MARGIN {
+            self.y -= height;
+            return false;
+        }
+        self.pages += 1;
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), format!("Page {}", self.pages));
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.layer.set_outline_thickness(0.5);
+        self.y = PAGE_HEIGHT - MARGIN - height;
+        true
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.font };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    /// A rule from `x1` to `x2`, `above` the baseline of the current line.
+    fn rule(&self, x1: f32, x2: f32, above: f32) {
+        self.layer.add_line(Line {
+            points: vec![(Point::new(Mm(x1), Mm(self.y + above)), false), (Point::new(Mm(x2), Mm(self.y + above)), false)],
+            is_closed: false,
+        });
+    }
+
+    /// An empty box sitting on the baseline of the current line.
+    fn boxed(&self, x: f32, width: f32, height: f32) {
+        let rect = Rect::new(Mm(x), Mm(self.y - 1.0), Mm(x + width), Mm(self.y - 1.0 + height));
+        self.layer.add_rect(rect.with_mode(PaintMode::Stroke));
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        self.doc.save_to_bytes().map_err(|e| e.to_string())
+    }
+}
+
+/// A game as printed on a tipping sheet.
+pub(crate) struct SheetGame {
+    pub(crate) kick_off: String,
+    pub(crate) venue: Option<String>,
+    pub(crate) home_team: String,
+    pub(crate) away_team: String,
+    /// Whether tippers predict the margin of this game
+    pub(crate) margin: bool,
+}
+
+/// A sheet for tipping a round on paper: a line for the tipper's name, then each game with a box
+/// to tick beside each team, and a box for the margin on the margin game.
+pub(crate) fn tipping_sheet(title: &str, games: &[SheetGame]) -> Result<Vec<u8>, String> {
+    let mut pdf = Writer::new(title)?;
+    pdf.next_line(6.0);
+    pdf.text(title, 18.0, MARGIN, true);
+    pdf.next_line(14.0);
+    pdf.text("Name", 12.0, MARGIN, false);
+    pdf.rule(MARGIN + 14.0, PAGE_WIDTH - MARGIN, -1.0);
+    pdf.next_line(10.0);
+    pdf.text("Tick the team you think will win each game, and give a winning margin where there is a box for one.", 9.0, MARGIN, false);
+    pdf.next_line(4.0);
+
+    for game in games {
+        pdf.next_line(14.0);
+        pdf.text(&game.kick_off, 10.0, MARGIN, true);
+        pdf.boxed(57.0, 5.0, 5.0);
+        pdf.text(&game.home_team, 11.0, 64.0, false);
+        pdf.text("v", 11.0, 110.0, false);
+        pdf.boxed(117.0, 5.0, 5.0);
+        pdf.text(&game.away_team, 11.0, 124.0, false);
+        if game.margin {
+            pdf.text("Margin", 8.0, 170.0, false);
+            pdf.boxed(180.0, PAGE_WIDTH - MARGIN - 180.0, 7.0);
+        }
+        if let Some(venue) = &game.venue {
+            pdf.next_line(4.5);
+            pdf.text(venue, 8.0, MARGIN, false);
+        }
+        pdf.rule(MARGIN, PAGE_WIDTH - MARGIN, -4.0);
+    }
+    pdf.finish()
+}
+
+/// Columns of the leaderboard, the x position and heading of each.
+const LEADERBOARD_COLUMNS: [(f32, &str); 6] = [
+    (MARGIN, "Rank"),
+    (30.0, "Tipper"),
+    (92.0, "Game Score"),
+    (117.0, "Bonus Score"),
+    (142.0, "Total Score"),
+    (167.0, "Margin Error"),
+];
+
+/// The leaderboard as a table for the noticeboard, with the headings repeated on each page.
+pub(crate) fn leaderboard(title: &str, subtitle: &str, entries: &[LeaderboardEntry]) -> Result<Vec<u8>, String> {
+    let headings = |pdf: &Writer| {
+        for (x, heading) in LEADERBOARD_COLUMNS {
+            pdf.text(heading, 10.0, x, true);
+        }
+        pdf.rule(MARGIN, PAGE_WIDTH - MARGIN, -2.0);
+    };
+
+    let mut pdf = Writer::new(title)?;
+    pdf.next_line(6.0);
+    pdf.text(title, 18.0, MARGIN, true);
+    pdf.next_line(8.0);
+    pdf.text(subtitle, 10.0, MARGIN, false);
+    pdf.next_line(12.0);
+    headings(&pdf);
+    pdf.next_line(2.0);
+
+    for entry in entries {
+        if pdf.next_line(7.0) {
+            headings(&pdf);
+            pdf.next_line(9.0);
+        }
+        let cells = [
+            entry.rank.to_string(),
+            entry.tipper_name.clone(),
+            entry.tip_score.to_string(),
+            entry.bonus_score.to_string(),
+            entry.total_score.to_string(),
+            entry.margin_error.to_string(),
+        ];
+        for ((x, _), cell) in LEADERBOARD_COLUMNS.iter().zip(&cells) {
+            pdf.text(cell, 11.0, *x, false);
+        }
+    }
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::entry;
+
+    #[test]
+    fn long_leaderboards_run_onto_more_pages() {
+        let entries: Vec<_> = (1..=100).map(|i| entry(i as i32, &format!("Tipper {}", i), 10, 0, i)).collect();
+        let pdf = leaderboard("2025 leaderboard", "After round 3", &entries).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+        // Each page is a /Type/Page object, the page tree is /Type/Pages
+        let pages = pdf.windows(11).filter(|w| w.starts_with(b"/Type/Page") && w[10] != b's').count();
+        assert_eq!(pages, 3);
+    }
+
+    #[test]
+    fn tipping_sheet_is_a_pdf() {
+        let games = [SheetGame {
+            kick_off: "Fri 14 Mar 7:40pm".to_string(),
+            venue: Some("MCG".to_string()),
+            home_team: "Geelong".to_string(),
+            away_team: "Collingwood".to_string(),
+            margin: true,
+        }];
+        let pdf = tipping_sheet("2025 round 1", &games).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::{game, mail, round, team};
+use crate::util::config::{ReminderConfig, TippingConfig};
+use crate::util::mail::{transport, Email, MailTransport};
+use crate::util::tip_lock::lock_time;
+use crate::DbTips;
+use chrono::{DateTime, FixedOffset, Utc};
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+use kelpie_models::tipper::Tipper;
+use rocket::fairing::AdHoc;
+use rocket_db_pools::Database;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const TIME_FORMAT: &str = "%a %-d %b %-I:%M%P";
+
+/// The reminder to `tipper` about the `untipped` games of `round` that can still be tipped at `now`,
+/// `None` when there are none. Games are in competition local time.
+pub(crate) fn reminder(config: &TippingConfig, tipper: &Tipper, round: &Round, games: &[Game], untipped: &[i32],
+                       team_names: &HashMap<i32, String>, now: DateTime<FixedOffset>) -> Option<Email> {
+    let open: Vec<(&Game, DateTime<FixedOffset>)> = games.iter()
+        .filter(|g| g.game_id.is_some_and(|id| untipped.contains(&id)))
+        .map(|g| (g, lock_time(config, round, games, g)))
+        .filter(|(_, lock)| *lock > now)
+        .collect();
+    let closes = open.iter().map(|(_, lock)| *lock).min()?;
+
+    let team = |id: i32| team_names.get(&id).map(String::as_str).unwrap_or("?");
+    let mut body = format!("Hi {},\n\nYou haven't tipped every game in round {} yet, tips close {}.\n\nStill to tip:\n",
+                           tipper.name, round.round_number, closes.format(TIME_FORMAT));
+    for (g, _) in &open {
+        body.push_str(&format!("  {} v {}, {}\n", team(g.home_team_id), team(g.away_team_id), g.kick_off.format(TIME_FORMAT)));
+    }
+    Some(Email {
+        to_name: tipper.name.clone(),
+        to: tipper.email.clone(),
+        subject: format!("Round {} tips are due", round.round_number),
+        body,
+    })
+}
+
+/// Remind tippers about rounds starting within `hours_before` of `now`, at most once each a round.
+pub(crate) async fn send_reminders(pool: &mut PgConnection, transport: &dyn MailTransport, config: &TippingConfig,
+                                   reminders: &ReminderConfig, now: DateTime<Utc>) -> Result<usize, String> {
+    let round_ids = mail::rounds_starting(pool, now, now + chrono::Duration::hours(reminders.hours_before)).await
+        .map_err(|e| e.to_string())?;
+    if round_ids.is_empty() {
+        return Ok(0);
+    }
+    let team_names: HashMap<i32, String> = team::get_all(pool, None).await.map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|t| Some((t.id?, t.name)))
+        .collect();
+
+    let mut sent = 0;
+    for round_id in round_ids {
+        let Some(round) = round::get(pool, round_id).await.map_err(|e| e.to_string())? else { continue };
+        let games = config.localise(game::get_for_round(pool, round_id).await.map_err(|e| e.to_string())?);
+        for (tipper, untipped) in mail::untipped_games(pool, round_id, mail::REMINDER).await.map_err(|e| e.to_string())? {
+            let (Some(tipper_id), Some(email)) = (tipper.id, reminder(config, &tipper, &round, &games, &untipped,
+                                                                      &team_names, config.local(now))) else { continue };
+            match transport.send(&email).await {
+                Ok(()) => {
+                    mail::record_sent(pool, mail::REMINDER, round_id, tipper_id, &email.to, &email.subject).await
+                        .map_err(|e| e.to_string())?;
+                    sent += 1;
+                }
+                Err(e) => tracing::warn!("Unable to send a reminder to {} through {}: {}", email.to, transport.name(), e),
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Check for rounds needing reminders in the background once the server is up.
+pub(crate) fn stage() -> AdHoc {
+    AdHoc::on_liftoff("Tip Reminders", |rocket| Box::pin(async move {
+        let Some(config) = rocket.state::<TippingConfig>().cloned() else { return };
+        let (Some(mail_config), Some(reminders)) = (config.mail.clone(), config.reminders.clone()) else { return };
+        let transport = match transport(&mail_config) {
+            Ok(transport) => transport,
+            Err(e) => {
+                tracing::error!("Unable to set up mail for reminders: {}", e);
+                return;
+            }
+        };
+        let Some(db) = DbTips::fetch(rocket) else {
+            tracing::error!("Database not available for reminders");
+            return;
+        };
+        let pool = db.0.clone();
+        tracing::info!("Sending tip reminders through {} {} hours before each round", transport.name(), reminders.hours_before);
+        rocket::tokio::spawn(async move {
+            let mut interval = rocket::tokio::time::interval(Duration::from_secs(reminders.check_minutes.max(1) * 60));
+            loop {
+                interval.tick().await;
+                let sent = match pool.acquire().await {
+                    Ok(mut conn) => send_reminders(&mut conn, transport.as_ref(), &config, &reminders, Utc::now()).await,
+                    Err(e) => Err(e.to_string()),
+                };
+                match sent {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("Sent {} tip reminders", n),
+                    Err(e) => tracing::warn!("Unable to send tip reminders: {}", e),
+                }
+            }
+        });
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, round, tipper};
+
+    fn team_names() -> HashMap<i32, String> {
+        [(1, "Geelong"), (2, "Collingwood"), (3, "Carlton"), (4, "Essendon")]
+            .into_iter()
+            .map(|(id, name)| (id, name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reminder_lists_untipped_games() {
+        let games = vec![game(1, 1, 2, "2025-03-14T19:40:00+11:00", None), game(2, 3, 4, "2025-03-15T13:45:00+11:00", None)];
+        let now = DateTime::parse_from_rfc3339("2025-03-13T19:40:00+11:00").unwrap();
+        let email = reminder(&TippingConfig::default(), &tipper(7, "Sam"), &round(3, "2025-03-14", "2025-03-14"), &games, &[2], &team_names(), now).unwrap();
+        assert_eq!(email.to, "sam@example.com");
+        assert_eq!(email.subject, "Round 3 tips are due");
+        assert!(email.body.contains("Carlton v Essendon"));
+        assert!(!email.body.contains("Geelong v Collingwood"));
+        assert!(email.body.contains("tips close Sat 15 Mar 1:45pm"), "{}", email.body);
+    }
+
+    #[test]
+    fn no_reminder_once_games_are_locked() {
+        let games = vec![game(1, 1, 2, "2025-03-14T19:40:00+11:00", None), game(2, 3, 4, "2025-03-15T13:45:00+11:00", None)];
+        let now = DateTime::parse_from_rfc3339("2025-03-14T20:00:00+11:00").unwrap();
+        let config = TippingConfig::default();
+        assert!(reminder(&config, &tipper(7, "Sam"), &round(3, "2025-03-14", "2025-03-14"), &games, &[1], &team_names(), now).is_none());
+        assert!(reminder(&config, &tipper(7, "Sam"), &round(3, "2025-03-14", "2025-03-14"), &games, &[1, 2], &team_names(), now).is_some());
+    }
+}
@@ -0,0 +1,339 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::{game, team};
+use crate::util::config::{ResultsProviderConfig, ResultsSource, TippingConfig};
+use crate::util::import::{find_team, team_names};
+use crate::DbTips;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use kelpie_models::game::Game;
+use kelpie_models::team::Team;
+use rocket::fairing::AdHoc;
+use rocket::serde::Deserialize;
+use rocket_db_pools::Database;
+use sqlx::PgConnection;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A final score reported by a results provider.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ProvidedResult {
+    /// Team name or nickname
+    pub(crate) home: String,
+    pub(crate) away: String,
+    /// The local date of the game, needed when the teams meet more than once
+    #[serde(default)]
+    pub(crate) date: Option<NaiveDate>,
+    pub(crate) home_score: i32,
+    pub(crate) away_score: i32,
+}
+
+/// Somewhere to get game scores from.
+#[rocket::async_trait]
+pub(crate) trait ResultsProvider: Send + Sync {
+    /// Used in the log
+    fn name(&self) -> String;
+    async fn fetch(&self) -> Result<Vec<ProvidedResult>, String>;
+}
+
+/// Results from a JSON array served over HTTP.
+pub(crate) struct HttpProvider {
+    url: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpProvider {
+    pub(crate) fn new(url: String, api_key: Option<String>) -> Self {
+        HttpProvider { url, api_key, client: reqwest::Client::new() }
+    }
+}
+
+#[rocket::async_trait]
+impl ResultsProvider for HttpProvider {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn fetch(&self) -> Result<Vec<ProvidedResult>, String> {
+        let mut request = self.client.get(&self.url).timeout(Duration::from_secs(30));
+        if let Some(key) = self.api_key.as_ref().filter(|k| !k.is_empty()) {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        response.json::<Vec<ProvidedResult>>().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Results from a JSON file, read afresh each time so it can be edited while running.
+pub(crate) struct FileProvider {
+    path: PathBuf,
+}
+
+impl FileProvider {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        FileProvider { path }
+    }
+}
+
+#[rocket::async_trait]
+impl ResultsProvider for FileProvider {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    async fn fetch(&self) -> Result<Vec<ProvidedResult>, String> {
+        let text = rocket::tokio::fs::read_to_string(&self.path).await.map_err(|e| e.to_string())?;
+        rocket::serde::json::from_str(&text).map_err(|e| e.to_string())
+    }
+}
+
+pub(crate) fn provider(config: &ResultsProviderConfig) -> Box<dyn ResultsProvider> {
+    match &config.source {
+        ResultsSource::Http { url, api_key } => Box::new(HttpProvider::new(url.clone(), api_key.clone())),
+        ResultsSource::File { path } => Box::new(FileProvider::new(path.clone())),
+    }
+}
+
+/// A game whose score the provider has changed.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ResultChange {
+    pub(crate) game_id: i32,
+    pub(crate) description: String,
+    pub(crate) home_team_score: i32,
+    pub(crate) away_team_score: i32,
+}
+
+/// Match provided results to `games` (in competition local time), keeping those that change a score.
+///
+/// A result is only ever for a game that has kicked off by `now`. Without a date it is for the latest
+/// such game between the teams.
+pub(crate) fn changes(games: &[Game], teams: Vec<Team>, results: &[ProvidedResult], now: DateTime<FixedOffset>)
+    -> Vec<ResultChange> {
+    let names = team_names(teams);
+    results.iter().filter_map(|result| {
+        let (home_id, home) = find_team(&names, &result.home)?;
+        let (away_id, away) = find_team(&names, &result.away)?;
+        let mut meetings = games.iter()
+            .filter(|g| g.home_team_id == home_id && g.away_team_id == away_id && g.kick_off <= now);
+        let game = match result.date {
+            Some(date) => meetings.find(|g| g.kick_off.date_naive() == date)?,
+            None => meetings.max_by_key(|g| g.kick_off)?,
+        };
+        let new_score = (Some(result.home_score), Some(result.away_score));
+        if (game.home_team_score, game.away_team_score) == new_score {
+            return None;
+        }
+        let was = match (game.home_team_score, game.away_team_score) {
+            (Some(h), Some(a)) => format!(" (was {}-{})", h, a),
+            _ => String::new(),
+        };
+        Some(ResultChange {
+            game_id: game.game_id?,
+            description: format!("{} {} v {} {}{}", home, result.home_score, away, result.away_score, was),
+            home_team_score: result.home_score,
+            away_team_score: result.away_score,
+        })
+    }).collect()
+}
+
+/// Fetch the latest results and save any that change a game's score.
+pub(crate) async fn update_results(pool: &mut PgConnection, provider: &dyn ResultsProvider, config: &TippingConfig)
+    -> Result<usize, String> {
+    let results = provider.fetch().await?;
+    let games = config.localise(game::get_all(pool).await.map_err(|e| e.to_string())?);
+    let teams = team::get_all(pool, None).await.map_err(|e| e.to_string())?;
+    let changes = changes(&games, teams, &results, config.local(Utc::now()));
+    for change in &changes {
+        game::set_result(pool, change.game_id, Some(change.home_team_score), Some(change.away_team_score)).await
+            .map_err(|e| e.to_string())?;
+        tracing::info!("Results from {}: {}", provider.name(), change.description);
+    }
+    Ok(changes.len())
+}
+
+/// Poll the configured results provider in the background once the server is up.
+pub(crate) fn stage() -> AdHoc {
+    AdHoc::on_liftoff("Results Provider", |rocket| Box::pin(async move {
+        let Some(config) = rocket.state::<TippingConfig>().cloned() else { return };
+        let Some(provider_config) = config.results_provider.clone() else { return };
+        let Some(db) = DbTips::fetch(rocket) else {
+            tracing::error!("Database not available for the results provider");
+            return;
+        };
+        let pool = db.0.clone();
+        let provider = provider(&provider_config);
+        tracing::info!("Polling {} for results every {} minutes", provider.name(), provider_config.poll_minutes);
+        rocket::tokio::spawn(async move {
+            let mut interval = rocket::tokio::time::interval(Duration::from_secs(provider_config.poll_minutes.max(1) * 60));
+            loop {
+                interval.tick().await;
+                let updated = match pool.acquire().await {
+                    Ok(mut conn) => update_results(&mut conn, provider.as_ref(), &config).await,
+                    Err(e) => Err(e.to_string()),
+                };
+                match updated {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("Updated {} game results from {}", n, provider.name()),
+                    Err(e) => tracing::warn!("Unable to fetch results from {}: {}", provider.name(), e),
+                }
+            }
+        });
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, team};
+
+    fn teams() -> Vec<Team> {
+        vec![team(1, "Geelong", "Cats"), team(2, "Collingwood", "Magpies"), team(3, "Carlton", "Blues")]
+    }
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2025-08-01T12:00:00+10:00").unwrap()
+    }
+
+    /// A results file in the temp directory, removed when dropped.
+    struct ResultsFile(PathBuf);
+
+    impl ResultsFile {
+        fn new(name: &str, json: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("kelpie-{}-{}.json", name, std::process::id()));
+            std::fs::write(&path, json).unwrap();
+            ResultsFile(path)
+        }
+    }
+
+    impl Drop for ResultsFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn file_provider_reads_results() {
+        let file = ResultsFile::new("read", r#"[{"home": "Cats", "away": "Magpies", "date": "2025-03-14", "home_score": 90, "away_score": 72}]"#);
+        let results = FileProvider::new(file.0.clone()).fetch().await.unwrap();
+        assert_eq!(results, vec![ProvidedResult {
+            home: "Cats".to_string(),
+            away: "Magpies".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 3, 14),
+            home_score: 90,
+            away_score: 72,
+        }]);
+    }
+
+    #[rocket::async_test]
+    async fn file_provider_reports_bad_files() {
+        let file = ResultsFile::new("bad", "not json");
+        assert!(FileProvider::new(file.0.clone()).fetch().await.is_err());
+        assert!(FileProvider::new(std::env::temp_dir().join("kelpie-missing.json")).fetch().await.is_err());
+    }
+
+    #[rocket::async_test]
+    async fn only_changed_scores_are_updated() {
+        let file = ResultsFile::new("changes", r#"[
+            {"home": "geelong", "away": "Magpies", "home_score": 90, "away_score": 72},
+            {"home": "Blues", "away": "Cats", "home_score": 50, "away_score": 60},
+            {"home": "Blues", "away": "Magpies", "home_score": 80, "away_score": 81},
+            {"home": "Saints", "away": "Cats", "home_score": 1, "away_score": 2}
+        ]"#);
+        let games = vec![
+            game(10, 1, 2, "2025-03-14T19:40:00+11:00", None),
+            game(11, 3, 1, "2025-03-15T13:45:00+11:00", Some((50, 60))),
+            game(12, 3, 2, "2025-03-16T15:20:00+11:00", Some((80, 80))),
+        ];
+        let results = FileProvider::new(file.0.clone()).fetch().await.unwrap();
+        let changes = changes(&games, teams(), &results, now());
+        assert_eq!(changes, vec![
+            ResultChange {
+                game_id: 10,
+                description: "Geelong 90 v Collingwood 72".to_string(),
+                home_team_score: 90,
+                away_team_score: 72,
+            },
+            ResultChange {
+                game_id: 12,
+                description: "Carlton 80 v Collingwood 81 (was 80-80)".to_string(),
+                home_team_score: 80,
+                away_team_score: 81,
+            },
+        ]);
+    }
+
+    #[test]
+    fn date_picks_between_games_of_the_same_teams() {
+        let games = vec![
+            game(10, 1, 2, "2025-03-14T19:40:00+11:00", Some((90, 72))),
+            game(20, 1, 2, "2025-07-20T15:20:00+10:00", None),
+        ];
+        let result = ProvidedResult {
+            home: "Cats".to_string(),
+            away: "Magpies".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 7, 20),
+            home_score: 70,
+            away_score: 71,
+        };
+        let changes = changes(&games, teams(), &[result], now());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].game_id, 20);
+    }
+
+    #[test]
+    fn dated_results_skip_games_yet_to_start() {
+        // Kicks off later on the day of `now`
+        let games = vec![game(30, 1, 2, "2025-08-01T19:40:00+10:00", None)];
+        let result = ProvidedResult {
+            home: "Cats".to_string(),
+            away: "Magpies".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 8, 1),
+            home_score: 70,
+            away_score: 71,
+        };
+        assert!(changes(&games, teams(), &[result], now()).is_empty());
+    }
+
+    #[test]
+    fn undated_results_are_for_the_latest_game_played() {
+        let games = vec![
+            game(10, 1, 2, "2025-03-14T19:40:00+11:00", Some((90, 72))),
+            game(20, 1, 2, "2025-07-20T15:20:00+10:00", Some((60, 61))),
+            game(30, 1, 2, "2025-09-27T14:30:00+10:00", None),
+        ];
+        let result = ProvidedResult {
+            home: "Cats".to_string(),
+            away: "Magpies".to_string(),
+            date: None,
+            home_score: 70,
+            away_score: 71,
+        };
+        let changes = changes(&games, teams(), &[result], now());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].game_id, 20);
+    }
+}
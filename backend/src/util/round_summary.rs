@@ -0,0 +1,234 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::reporting::{self, LeaderboardEntry, RoundSummary};
+use crate::db::{mail, tipper};
+use crate::util::config::{RoundSummaryConfig, TippingConfig};
+use crate::util::mail::{transport, Email, MailTransport};
+use crate::DbTips;
+use chrono::{DateTime, Utc};
+use handlebars::Handlebars;
+use kelpie_models::tipper::Tipper;
+use rocket::fairing::AdHoc;
+use rocket::serde::Serialize;
+use rocket_db_pools::Database;
+use sqlx::PgConnection;
+use std::time::Duration;
+
+const TEMPLATE: &str = "round_summary";
+const DEFAULT_TEMPLATE: &str = include_str!("../../templates/round_summary.hbs");
+
+/// Rounds finished longer ago than this never get a summary, so turning summaries on doesn't
+/// send one for every round already played
+const SEND_WITHIN_DAYS: i64 = 7;
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Placing {
+    rank: i64,
+    name: String,
+    score: i64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct MyResult {
+    round_score: i64,
+    bonus: i64,
+    round_rank: i64,
+    season_total: i64,
+    season_rank: i64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct SummaryContext<'a> {
+    name: &'a str,
+    season: &'a str,
+    round_number: i32,
+    winners: Vec<Placing>,
+    winning_score: i64,
+    perfect: Vec<Placing>,
+    top_ten: Vec<Placing>,
+    me: Option<MyResult>,
+}
+
+/// The round summary template, read from the configured file so it can be edited while running.
+pub(crate) fn template(config: Option<&RoundSummaryConfig>) -> Result<Handlebars<'static>, String> {
+    let mut handlebars = Handlebars::new();
+    // Plain text, so nothing needs escaping
+    handlebars.register_escape_fn(handlebars::no_escape);
+    match config.and_then(|c| c.template.as_ref()) {
+        Some(path) => handlebars.register_template_file(TEMPLATE, path),
+        None => handlebars.register_template_string(TEMPLATE, DEFAULT_TEMPLATE),
+    }.map_err(|e| e.to_string())?;
+    Ok(handlebars)
+}
+
+fn placing(entry: &LeaderboardEntry, score: i64) -> Placing {
+    Placing { rank: entry.rank, name: entry.tipper_name.clone(), score }
+}
+
+/// The summary of a round for `tipper`.
+pub(crate) fn compose(handlebars: &Handlebars<'_>, summary: &RoundSummary, tipper: &Tipper) -> Result<Email, String> {
+    let winning_score = summary.round.first().map(|e| e.total_score).unwrap_or(0);
+    let round_entry = summary.round.iter().find(|e| Some(e.tipper_id) == tipper.id);
+    let season_entry = summary.season.iter().find(|e| Some(e.tipper_id) == tipper.id);
+    let me = match (round_entry, season_entry) {
+        (Some(round), Some(season)) => Some(MyResult {
+            round_score: round.total_score,
+            bonus: round.bonus_score,
+            round_rank: round.rank,
+            season_total: season.total_score,
+            season_rank: season.rank,
+        }),
+        _ => None,
+    };
+    let context = SummaryContext {
+        name: &tipper.name,
+        season: &summary.season_name,
+        round_number: summary.round_number,
+        winners: summary.round.iter().filter(|e| e.rank == 1).map(|e| placing(e, e.total_score)).collect(),
+        winning_score,
+        perfect: summary.round.iter().filter(|e| e.bonus_score > 0).map(|e| placing(e, e.bonus_score)).collect(),
+        top_ten: summary.season.iter().take(10).map(|e| placing(e, e.total_score)).collect(),
+        me,
+    };
+    Ok(Email {
+        to_name: tipper.name.clone(),
+        to: tipper.email.clone(),
+        subject: format!("{} round {} results", summary.season_name, summary.round_number),
+        body: handlebars.render(TEMPLATE, &context).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Send the summary of a round to every tipper in its season who hasn't already had it.
+pub(crate) async fn send_summary(pool: &mut PgConnection, transport: &dyn MailTransport, handlebars: &Handlebars<'_>,
+                                 round_id: i32) -> Result<usize, String> {
+    let Some(summary) = reporting::get_round_summary(pool, round_id).await.map_err(|e| e.to_string())? else {
+        return Ok(0);
+    };
+    let season_id = crate::db::round::get(pool, round_id).await.map_err(|e| e.to_string())?.map(|r| r.season_id);
+    let sent_to = mail::sent_to(pool, mail::ROUND_SUMMARY, round_id).await.map_err(|e| e.to_string())?;
+    let mut sent = 0;
+    for tipper in tipper::get_all(pool, season_id).await.map_err(|e| e.to_string())? {
+        let Some(tipper_id) = tipper.id.filter(|id| !sent_to.contains(id)) else { continue };
+        let email = compose(handlebars, &summary, &tipper)?;
+        match transport.send(&email).await {
+            Ok(()) => {
+                mail::record_sent(pool, mail::ROUND_SUMMARY, round_id, tipper_id, &email.to, &email.subject).await
+                    .map_err(|e| e.to_string())?;
+                sent += 1;
+            }
+            Err(e) => tracing::warn!("Unable to send a round summary to {} through {}: {}", email.to, transport.name(), e),
+        }
+    }
+    Ok(sent)
+}
+
+/// Send the summary of every round that finished `send_after_hours` before `now`.
+pub(crate) async fn send_due_summaries(pool: &mut PgConnection, transport: &dyn MailTransport, config: &RoundSummaryConfig,
+                                       now: DateTime<Utc>) -> Result<usize, String> {
+    let until = now - chrono::Duration::hours(config.send_after_hours);
+    let round_ids = mail::rounds_completed(pool, until - chrono::Duration::days(SEND_WITHIN_DAYS), until).await
+        .map_err(|e| e.to_string())?;
+    if round_ids.is_empty() {
+        return Ok(0);
+    }
+    let handlebars = template(Some(config))?;
+    let mut sent = 0;
+    for round_id in round_ids {
+        sent += send_summary(pool, transport, &handlebars, round_id).await?;
+    }
+    Ok(sent)
+}
+
+/// Check for finished rounds in the background once the server is up.
+pub(crate) fn stage() -> AdHoc {
+    AdHoc::on_liftoff("Round Summaries", |rocket| Box::pin(async move {
+        let Some(config) = rocket.state::<TippingConfig>().cloned() else { return };
+        let (Some(mail_config), Some(summary_config)) = (config.mail.clone(), config.round_summary.clone()) else { return };
+        let transport = match transport(&mail_config) {
+            Ok(transport) => transport,
+            Err(e) => {
+                tracing::error!("Unable to set up mail for round summaries: {}", e);
+                return;
+            }
+        };
+        let Some(db) = DbTips::fetch(rocket) else {
+            tracing::error!("Database not available for round summaries");
+            return;
+        };
+        let pool = db.0.clone();
+        tracing::info!("Sending round summaries through {} {} hours after each round", transport.name(), summary_config.send_after_hours);
+        rocket::tokio::spawn(async move {
+            let mut interval = rocket::tokio::time::interval(Duration::from_secs(summary_config.check_minutes.max(1) * 60));
+            loop {
+                interval.tick().await;
+                let sent = match pool.acquire().await {
+                    Ok(mut conn) => send_due_summaries(&mut conn, transport.as_ref(), &summary_config, Utc::now()).await,
+                    Err(e) => Err(e.to_string()),
+                };
+                match sent {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("Sent {} round summaries", n),
+                    Err(e) => tracing::warn!("Unable to send round summaries: {}", e),
+                }
+            }
+        });
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{entry, tipper};
+
+    fn summary() -> RoundSummary {
+        RoundSummary {
+            season_name: "2025".to_string(),
+            round_number: 4,
+            round: vec![entry(1, "Alex", 9, 5, 1), entry(2, "Sam", 6, 0, 2)],
+            season: vec![entry(2, "Sam", 30, 0, 1), entry(1, "Alex", 24, 5, 2)],
+        }
+    }
+
+    #[test]
+    fn summary_has_winners_bonuses_top_ten_and_own_result() {
+        let email = compose(&template(None).unwrap(), &summary(), &tipper(2, "Sam")).unwrap();
+        assert_eq!(email.to, "sam@example.com");
+        assert_eq!(email.subject, "2025 round 4 results");
+        assert!(email.body.starts_with("Hi Sam,"), "{}", email.body);
+        assert!(email.body.contains("You scored 6 this round, and you're ranked 1 with 30 points."), "{}", email.body);
+        assert!(email.body.contains("Round winner with 14: Alex\n"), "{}", email.body);
+        assert!(email.body.contains("Perfect round: Alex (+5)\n"), "{}", email.body);
+        assert!(email.body.contains("  1. Sam 30\n  2. Alex 29\n"), "{}", email.body);
+    }
+
+    #[test]
+    fn summary_for_someone_outside_the_season() {
+        let email = compose(&template(None).unwrap(), &summary(), &tipper(3, "Jo")).unwrap();
+        assert!(!email.body.contains("You scored"), "{}", email.body);
+        assert!(email.body.contains("Top ten after round 4:"), "{}", email.body);
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use kelpie_models::game::Game;
+use kelpie_models::scoring::{ScoringRules, Untipped};
+use kelpie_models::tip::Tip;
+use std::collections::HashMap;
+
+/// What a tipper's tips for one round earned them.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct RoundScore {
+    /// Points for the games tipped, including upset and margin points
+    pub(crate) tip_score: i64,
+    /// The round's bonus points, if the round was perfect
+    pub(crate) bonus_score: i64,
+    /// How far the predicted margin for the round's first game was out, the leaderboard tie-breaker
+    pub(crate) margin_error: i64,
+}
+
+impl RoundScore {
+    pub(crate) fn total(&self) -> i64 {
+        self.tip_score + self.bonus_score
+    }
+}
+
+impl std::ops::AddAssign for RoundScore {
+    fn add_assign(&mut self, other: Self) {
+        self.tip_score += other.tip_score;
+        self.bonus_score += other.bonus_score;
+        self.margin_error += other.margin_error;
+    }
+}
+
+/// The winner of a game, `None` for a draw. Games without a result have no outcome.
+pub(crate) fn outcome(game: &Game) -> Option<Option<i32>> {
+    match (game.home_team_score, game.away_team_score) {
+        (Some(home), Some(away)) if home > away => Some(Some(game.home_team_id)),
+        (Some(home), Some(away)) if away > home => Some(Some(game.away_team_id)),
+        (Some(_), Some(_)) => Some(None),
+        _ => None,
+    }
+}
+
+/// The game margins are predicted for, the first game of the round.
+pub(crate) fn margin_game(games: &[Game]) -> Option<&Game> {
+    games.iter().min_by_key(|g| (g.kick_off, g.game_id))
+}
+
+/// The team a tipper is taken to have tipped, allowing for the untipped rule.
+fn tipped_team(rules: &ScoringRules, game: &Game, tip: Option<i32>) -> Option<i32> {
+    match (tip, rules.untipped) {
+        (Some(team_id), _) => Some(team_id),
+        (None, Untipped::HomeTeam) => Some(game.home_team_id),
+        (None, _) => None,
+    }
+}
+
+/// Score every tipper's tips for one round.
+///
+/// `tippers` are the tippers to score, so untipped games can be scored for tippers without any tips,
+/// and `bonus_points` is the round's bonus for a perfect round.
+pub(crate) fn score_round(rules: &ScoringRules, bonus_points: i64, games: &[Game], tips: &[Tip], tippers: &[i32])
+    -> HashMap<i32, RoundScore> {
+    let tipped: HashMap<(i32, i32), i32> = tips.iter()
+        .filter_map(|t| t.team_id.map(|team_id| ((t.tipper_id, t.game_id), team_id)))
+        .collect();
+
+    // How many tippers picked each team, for upsets
+    let mut picks: HashMap<(i32, i32), usize> = HashMap::new();
+    for game in games {
+        let Some(game_id) = game.game_id else { continue };
+        for &tipper_id in tippers {
+            if let Some(team_id) = tipped_team(rules, game, tipped.get(&(tipper_id, game_id)).copied()) {
+                *picks.entry((game_id, team_id)).or_default() += 1;
+            }
+        }
+    }
+
+    let first_game = margin_game(games);
+
+    tippers.iter().map(|&tipper_id| {
+        let mut score = RoundScore::default();
+        if let Some(game) = first_game {
+            let tip = game.game_id.and_then(|game_id| tips.iter().find(|t| t.tipper_id == tipper_id && t.game_id == game_id));
+            score.margin_error = margin_error(game, tip);
+        }
+        let mut counted = 0;
+        let mut perfect = true;
+        for game in games {
+            let Some(game_id) = game.game_id else { continue };
+            let team_id = match tipped_team(rules, game, tipped.get(&(tipper_id, game_id)).copied()) {
+                Some(team_id) => team_id,
+                None => {
+                    if rules.untipped == Untipped::Miss {
+                        counted += 1;
+                        perfect = false;
+                    }
+                    continue;
+                }
+            };
+            counted += 1;
+            match outcome(game) {
+                Some(Some(winner)) if winner == team_id => {
+                    score.tip_score += rules.correct_points + extra_points(rules, game, &picks, tippers.len());
+                }
+                Some(None) => {
+                    score.tip_score += rules.draw();
+                    perfect &= rules.draw() > 0;
+                }
+                _ => perfect = false,
+            }
+        }
+        if rules.perfect_round_bonus && perfect && counted > 0 {
+            score.bonus_score = bonus_points;
+        }
+        (tipper_id, score)
+    }).collect()
+}
+
+/// How far out a margin prediction was. No prediction counts as a margin of 0 and no tip
+/// as a tip for the home team, so leaving it out is never better than guessing.
+fn margin_error(game: &Game, tip: Option<&Tip>) -> i64 {
+    let (Some(home), Some(away)) = (game.home_team_score, game.away_team_score) else {
+        return 0;
+    };
+    let team_id = tip.and_then(|t| t.team_id).unwrap_or(game.home_team_id);
+    let actual = if team_id == game.home_team_id { home - away } else { away - home };
+    let predicted = tip.and_then(|t| t.margin).unwrap_or(0);
+    (predicted as i64 - actual as i64).abs()
+}
+
+/// Upset and margin points for correctly tipping the winner of `game`, out of `tippers` tippers.
+fn extra_points(rules: &ScoringRules, game: &Game, picks: &HashMap<(i32, i32), usize>, tippers: usize) -> i64 {
+    let (Some(game_id), Some(home), Some(away)) = (game.game_id, game.home_team_score, game.away_team_score) else {
+        return 0;
+    };
+    let winner = if home > away { game.home_team_id } else { game.away_team_id };
+    let picked = picks.get(&(game_id, winner)).copied().unwrap_or(0);
+
+    let mut points = 0;
+    // Fewer than half of all the tippers, so tippers who didn't tip count against the winner
+    if picked * 2 < tippers {
+        points += rules.upset_points;
+    }
+    let margin = (home - away).abs();
+    points += rules.margin_points.iter()
+        .filter(|m| margin >= m.min_margin)
+        .max_by_key(|m| m.min_margin)
+        .map(|m| m.points)
+        .unwrap_or(0);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, tip};
+    use kelpie_models::scoring::MarginPoints;
+
+    /// Home wins by 10, away wins by 30, then a draw
+    fn games() -> Vec<Game> {
+        vec![
+            game(1, 1, 2, "2025-03-14T19:40:00+11:00", Some((80, 70))),
+            game(2, 3, 4, "2025-03-15T13:45:00+11:00", Some((60, 90))),
+            game(3, 5, 6, "2025-03-15T16:35:00+11:00", Some((70, 70))),
+        ]
+    }
+
+    fn score(rules: &ScoringRules, tips: &[Tip], tippers: &[i32]) -> HashMap<i32, RoundScore> {
+        score_round(rules, 5, &games(), tips, tippers)
+    }
+
+    #[test]
+    fn correct_tips_score_and_a_perfect_round_gets_the_bonus() {
+        let tips = [
+            tip(1, 1, 1, None), tip(1, 2, 4, None), tip(1, 3, 5, None),
+            tip(2, 1, 2, None), tip(2, 2, 4, None), tip(2, 3, 6, None),
+        ];
+        let scores = score(&ScoringRules::default(), &tips, &[1, 2]);
+        assert_eq!((scores[&1].tip_score, scores[&1].bonus_score), (3, 5));
+        assert_eq!((scores[&2].tip_score, scores[&2].bonus_score), (2, 0));
+    }
+
+    #[test]
+    fn draws_score_draw_points() {
+        let tips = [tip(1, 1, 1, None), tip(1, 2, 4, None), tip(1, 3, 5, None)];
+        let half = ScoringRules { correct_points: 2, draw_points: Some(1), ..ScoringRules::default() };
+        assert_eq!(score(&half, &tips, &[1])[&1].tip_score, 5);
+
+        // A draw worth nothing isn't a perfect round either
+        let nothing = ScoringRules { draw_points: Some(0), ..ScoringRules::default() };
+        let scores = score(&nothing, &tips, &[1]);
+        assert_eq!((scores[&1].tip_score, scores[&1].bonus_score), (2, 0));
+    }
+
+    #[test]
+    fn untipped_games_follow_the_untipped_rule() {
+        // Game 2, which the away team won, isn't tipped
+        let tips = [tip(1, 1, 1, None), tip(1, 3, 5, None)];
+        let rules = |untipped| ScoringRules { untipped, ..ScoringRules::default() };
+
+        let ignore = score(&rules(Untipped::Ignore), &tips, &[1]);
+        assert_eq!((ignore[&1].tip_score, ignore[&1].bonus_score), (2, 5));
+        let miss = score(&rules(Untipped::Miss), &tips, &[1]);
+        assert_eq!((miss[&1].tip_score, miss[&1].bonus_score), (2, 0));
+        let home = score(&rules(Untipped::HomeTeam), &tips, &[1]);
+        assert_eq!((home[&1].tip_score, home[&1].bonus_score), (2, 0));
+
+        // Tipping the home team in game 2 would have been no different
+        let tipped_home = [tip(1, 1, 1, None), tip(1, 2, 3, None), tip(1, 3, 5, None)];
+        assert_eq!(score(&rules(Untipped::Miss), &tipped_home, &[1])[&1], home[&1]);
+    }
+
+    #[test]
+    fn upsets_and_margins_earn_extra_points() {
+        let rules = ScoringRules {
+            upset_points: 2,
+            margin_points: vec![MarginPoints { min_margin: 10, points: 1 }, MarginPoints { min_margin: 30, points: 3 }],
+            ..ScoringRules::default()
+        };
+        // Only tipper 1 picked the away win in game 2
+        let tips = [tip(1, 2, 4, None), tip(2, 2, 3, None), tip(3, 2, 3, None), tip(2, 1, 1, None), tip(3, 1, 1, None)];
+        let scores = score(&rules, &tips, &[1, 2, 3]);
+        // Correct, upset and the 30 point margin, but not the 10 point one as well
+        assert_eq!(scores[&1].tip_score, 1 + 2 + 3);
+        // A ten point win by the favourite
+        assert_eq!(scores[&2].tip_score, 1 + 1);
+        assert_eq!(scores[&3].tip_score, 1 + 1);
+    }
+
+    #[test]
+    fn untipped_tippers_count_towards_an_upset() {
+        let rules = ScoringRules { upset_points: 2, ..ScoringRules::default() };
+        // One tip each way on game 2, won by the away team
+        let tips = [tip(1, 2, 4, None), tip(2, 2, 3, None)];
+        // Picked by half the tippers isn't an upset
+        assert_eq!(score(&rules, &tips, &[1, 2])[&1].tip_score, 1);
+        // But it is once tippers who didn't tip are counted
+        assert_eq!(score(&rules, &tips, &[1, 2, 3, 4])[&1].tip_score, 1 + 2);
+    }
+
+    #[test]
+    fn margin_error_is_measured_on_the_first_game() {
+        // Game 1 was won by the home team by 10
+        let tips = [tip(1, 1, 1, Some(4)), tip(2, 1, 2, Some(5)), tip(3, 2, 4, Some(30))];
+        let scores = score(&ScoringRules::default(), &tips, &[1, 2, 3]);
+        assert_eq!(scores[&1].margin_error, 6);
+        assert_eq!(scores[&2].margin_error, 15);
+        // No tip for the first game counts as the home team by 0
+        assert_eq!(scores[&3].margin_error, 10);
+    }
+
+    #[test]
+    fn margin_game_is_the_first_to_kick_off() {
+        let games = [
+            game(5, 1, 2, "2025-03-14T19:40:00+11:00", None),
+            game(2, 3, 4, "2025-03-14T19:40:00+11:00", None),
+            game(1, 5, 6, "2025-03-15T13:45:00+11:00", None),
+        ];
+        // Games kicking off together are broken by id
+        assert_eq!(margin_game(&games).and_then(|g| g.game_id), Some(2));
+        assert!(margin_game(&[]).is_none());
+    }
+}
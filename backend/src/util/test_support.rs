@@ -0,0 +1,86 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::reporting::LeaderboardEntry;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+use kelpie_models::team::Team;
+use kelpie_models::tip::Tip;
+use kelpie_models::tipper::Tipper;
+
+fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+}
+
+pub(crate) fn team(id: i32, name: &str, nickname: &str) -> Team {
+    Team { id: Some(id), name: name.to_string(), nickname: nickname.to_string(), home_venue_id: None, can_delete: None }
+}
+
+/// A game in round 1, `kick_off` is RFC 3339.
+pub(crate) fn game(id: i32, home: i32, away: i32, kick_off: &str, score: Option<(i32, i32)>) -> Game {
+    Game {
+        game_id: Some(id),
+        round_id: Some(1),
+        home_team_id: home,
+        away_team_id: away,
+        kick_off: DateTime::<FixedOffset>::parse_from_rfc3339(kick_off).unwrap(),
+        venue_id: None,
+        home_team_score: score.map(|s| s.0),
+        away_team_score: score.map(|s| s.1),
+    }
+}
+
+/// Round 1 of season 1, the dates are `YYYY-MM-DD`.
+pub(crate) fn round(round_number: i32, start_date: &str, end_date: &str) -> Round {
+    Round {
+        round_id: Some(1),
+        season_id: 1,
+        round_number,
+        start_date: date(start_date),
+        end_date: date(end_date),
+        bonus_points: 0,
+    }
+}
+
+/// A tipper with an email address made from their name, "Sam" is sam@example.com.
+pub(crate) fn tipper(id: i32, name: &str) -> Tipper {
+    Tipper { id: Some(id), name: name.to_string(), email: format!("{}@example.com", name.to_lowercase()), password: None, is_admin: false }
+}
+
+pub(crate) fn entry(tipper_id: i32, name: &str, tip_score: i64, bonus_score: i64, rank: i64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        tipper_id,
+        tipper_name: name.to_string(),
+        tip_score,
+        bonus_score,
+        total_score: tip_score + bonus_score,
+        margin_error: 0,
+        rank,
+        previous_rank: None,
+    }
+}
+
+pub(crate) fn tip(tipper_id: i32, game_id: i32, team_id: i32, margin: Option<i32>) -> Tip {
+    Tip { tipper_id, game_id, team_id: Some(team_id), margin }
+}
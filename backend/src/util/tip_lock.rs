@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+use crate::util::config::{TipLock, TippingConfig};
+use chrono::{DateTime, Duration, FixedOffset};
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+
+/// The time after which tips for `game` are no longer accepted.
+pub(crate) fn lock_time(config: &TippingConfig, round: &Round, games: &[Game], game: &Game) -> DateTime<FixedOffset> {
+    match config.tip_lock {
+        TipLock::GameStart => game.kick_off,
+        TipLock::RoundStart => config.start_of(round.start_date),
+        TipLock::BeforeFirstGame => {
+            let first_game = games.iter()
+                .map(|g| g.kick_off)
+                .min()
+                .unwrap_or(game.kick_off);
+            first_game - Duration::hours(config.tip_lock_hours)
+        }
+    }
+}
+
+pub(crate) fn is_locked(config: &TippingConfig, round: &Round, games: &[Game], game: &Game, now: DateTime<FixedOffset>) -> bool {
+    now >= lock_time(config, round, games, game)
+}
+
+/// The ids of all games in the round that can no longer be tipped.
+pub(crate) fn locked_games(config: &TippingConfig, round: &Round, games: &[Game], now: DateTime<FixedOffset>) -> Vec<i32> {
+    games.iter()
+        .filter(|g| is_locked(config, round, games, g, now))
+        .filter_map(|g| g.game_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{game, round};
+
+    fn games() -> Vec<Game> {
+        vec![game(1, 1, 2, "2025-03-14T19:40:00+11:00", None), game(2, 3, 4, "2025-03-15T13:45:00+11:00", None)]
+    }
+
+    fn locked_at(config: &TippingConfig, now: &str) -> Vec<i32> {
+        let now = DateTime::parse_from_rfc3339(now).unwrap();
+        locked_games(config, &round(1, "2025-03-14", "2025-03-16"), &games(), now)
+    }
+
+    #[test]
+    fn games_lock_as_they_start() {
+        let config = TippingConfig { tip_lock: TipLock::GameStart, ..TippingConfig::default() };
+        assert_eq!(locked_at(&config, "2025-03-14T19:39:00+11:00"), Vec::<i32>::new());
+        assert_eq!(locked_at(&config, "2025-03-14T19:40:00+11:00"), [1]);
+        assert_eq!(locked_at(&config, "2025-03-15T13:45:00+11:00"), [1, 2]);
+    }
+
+    #[test]
+    fn games_lock_when_the_round_starts() {
+        let config = TippingConfig {
+            tip_lock: TipLock::RoundStart,
+            timezone: chrono_tz::Australia::Melbourne,
+            ..TippingConfig::default()
+        };
+        assert_eq!(locked_at(&config, "2025-03-13T23:59:00+11:00"), Vec::<i32>::new());
+        assert_eq!(locked_at(&config, "2025-03-14T00:00:00+11:00"), [1, 2]);
+    }
+
+    #[test]
+    fn games_lock_hours_before_the_first_game() {
+        let config = TippingConfig { tip_lock: TipLock::BeforeFirstGame, tip_lock_hours: 2, ..TippingConfig::default() };
+        assert_eq!(locked_at(&config, "2025-03-14T17:39:00+11:00"), Vec::<i32>::new());
+        assert_eq!(locked_at(&config, "2025-03-14T17:40:00+11:00"), [1, 2]);
+    }
+}
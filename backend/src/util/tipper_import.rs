@@ -0,0 +1,255 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::db::{season, tipper};
+use crate::util::import::RowError;
+use kelpie_models::tipper::Tipper;
+use rocket::serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+
+/// Longest name and email the tippers table will hold.
+const MAX_NAME: usize = 50;
+const MAX_EMAIL: usize = 100;
+
+/// One line of a tippers file.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct TipperRow {
+    pub(crate) name: String,
+    pub(crate) email: String,
+}
+
+/// Read the rows of a CSV file with a `name,email` header.
+pub(crate) fn parse(text: &str) -> Result<Vec<TipperRow>, String> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(text.as_bytes());
+    reader.deserialize()
+        .enumerate()
+        .map(|(i, row)| row.map_err(|e| format!("Row {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// The tippers as a CSV file that [`parse`] can read back in.
+pub(crate) fn export(tippers: &[Tipper]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for tipper in tippers {
+        writer.serialize(TipperRow { name: tipper.name.clone(), email: tipper.email.clone() })
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub(crate) enum TipperAction {
+    AddTipper,
+    /// The tipper already exists but isn't in the season yet
+    Enrol,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct TipperChange {
+    /// 1 for the first row after the header
+    pub(crate) row: usize,
+    pub(crate) action: TipperAction,
+    pub(crate) name: String,
+    pub(crate) email: String,
+    #[serde(skip)]
+    tipper_id: Option<i32>,
+}
+
+/// What a tipper import will do, or did. Nothing is applied if any row has an error.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct TipperImportReport {
+    pub(crate) applied: bool,
+    pub(crate) changes: Vec<TipperChange>,
+    pub(crate) errors: Vec<RowError>,
+}
+
+/// Check the rows against each other and the existing tippers, the same way the
+/// unique name and email constraints would. A row naming an existing tipper by
+/// both name and email is not an error, it just enrols them in the season.
+///
+/// Emails are compared ignoring case, as they are when logging in.
+///
+/// `enrolled` is `None` when the tippers aren't being added to a season.
+pub(crate) fn plan(existing: &[Tipper], enrolled: Option<&[i32]>, rows: &[TipperRow]) -> TipperImportReport {
+    let by_name: HashMap<&str, &Tipper> = existing.iter().map(|t| (t.name.as_str(), t)).collect();
+    let by_email: HashMap<String, &Tipper> = existing.iter().map(|t| (t.email.to_lowercase(), t)).collect();
+    let mut names_seen = HashMap::new();
+    let mut emails_seen = HashMap::new();
+    let mut report = TipperImportReport { applied: false, changes: vec![], errors: vec![] };
+
+    for (i, row) in rows.iter().enumerate() {
+        let row_number = i + 1;
+        let mut messages = vec![];
+        if row.name.is_empty() {
+            messages.push("Name is required".to_string());
+        } else if row.name.chars().count() > MAX_NAME {
+            messages.push(format!("Name is longer than {} characters", MAX_NAME));
+        }
+        if row.email.is_empty() {
+            messages.push("Email is required".to_string());
+        } else if row.email.chars().count() > MAX_EMAIL {
+            messages.push(format!("Email is longer than {} characters", MAX_EMAIL));
+        } else if !row.email.contains('@') {
+            messages.push(format!("'{}' is not an email address", row.email));
+        }
+        if !row.name.is_empty() {
+            if let Some(first) = names_seen.insert(row.name.as_str(), row_number) {
+                names_seen.insert(row.name.as_str(), first);
+                messages.push(format!("Name '{}' is also on row {}", row.name, first));
+            }
+        }
+        let email = row.email.to_lowercase();
+        if !row.email.is_empty() {
+            if let Some(first) = emails_seen.insert(email.clone(), row_number) {
+                emails_seen.insert(email.clone(), first);
+                messages.push(format!("Email '{}' is also on row {}", row.email, first));
+            }
+        }
+
+        let same_name = by_name.get(row.name.as_str());
+        let same_email = by_email.get(&email);
+        let tipper_id = match (same_name, same_email) {
+            (Some(a), Some(b)) if a.id == b.id => a.id,
+            (name, email) => {
+                if let Some(other) = email {
+                    messages.push(format!("Email '{}' already belongs to {}", row.email, other.name));
+                }
+                if name.is_some() {
+                    messages.push(format!("Name '{}' is already taken by another tipper", row.name));
+                }
+                None
+            }
+        };
+
+        if !messages.is_empty() {
+            report.errors.extend(messages.into_iter().map(|message| RowError { row: row_number, message }));
+            continue;
+        }
+        let action = match (tipper_id, enrolled) {
+            (None, _) => TipperAction::AddTipper,
+            (Some(id), Some(enrolled)) if !enrolled.contains(&id) => TipperAction::Enrol,
+            _ => TipperAction::Unchanged,
+        };
+        report.changes.push(TipperChange {
+            row: row_number,
+            action,
+            name: row.name.clone(),
+            email: row.email.clone(),
+            tipper_id,
+        });
+    }
+    report
+}
+
+/// Add the tippers in the report and enrol them in the season, if one is given.
+pub(crate) async fn apply(pool: &mut PgConnection, season_id: Option<i32>, report: &mut TipperImportReport)
+    -> Result<(), sqlx::Error> {
+    for change in &mut report.changes {
+        if change.action == TipperAction::AddTipper {
+            let new = tipper::insert(&mut *pool, change.name.clone(), change.email.clone(), false).await?;
+            change.tipper_id = new.id;
+        }
+        if let (Some(season_id), Some(tipper_id)) = (season_id, change.tipper_id) {
+            season::add_tipper(&mut *pool, season_id, tipper_id).await?;
+        }
+    }
+    report.applied = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::tipper;
+
+    fn row(name: &str, email: &str) -> TipperRow {
+        TipperRow { name: name.to_string(), email: email.to_string() }
+    }
+
+    #[test]
+    fn existing_tippers_are_enrolled_and_new_ones_added() {
+        let existing = [tipper(1, "Sam"), tipper(2, "Alex")];
+        let rows = [row("Sam", "sam@example.com"), row("Alex", "alex@example.com"), row("Jo", "jo@example.com")];
+        let report = plan(&existing, Some(&[2]), &rows);
+        assert!(report.errors.is_empty());
+        let actions: Vec<_> = report.changes.iter().map(|c| c.action).collect();
+        assert_eq!(actions, [TipperAction::Enrol, TipperAction::Unchanged, TipperAction::AddTipper]);
+    }
+
+    #[test]
+    fn clashing_rows_are_reported() {
+        let existing = [tipper(1, "Sam")];
+        let rows = [
+            row("Sam", "sammy@example.com"),
+            row("Samantha", "sam@example.com"),
+            row("Jo", "jo@example.com"),
+            row("Jo", "jo2@example.com"),
+            row("", "nobody"),
+        ];
+        let report = plan(&existing, None, &rows);
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.row, e.message.as_str())).collect();
+        assert_eq!(errors, [
+            (1, "Name 'Sam' is already taken by another tipper"),
+            (2, "Email 'sam@example.com' already belongs to Sam"),
+            (4, "Name 'Jo' is also on row 3"),
+            (5, "Name is required"),
+            (5, "'nobody' is not an email address"),
+        ]);
+        assert_eq!(report.changes.len(), 1);
+    }
+
+    #[test]
+    fn emails_are_compared_ignoring_case() {
+        let existing = [tipper(1, "Sam"), tipper(2, "Alex")];
+        let rows = [
+            row("Sam", "Sam@Example.com"),
+            row("Samantha", "ALEX@example.com"),
+            row("Jo", "jo@example.com"),
+            row("Joanne", "Jo@example.com"),
+        ];
+        let report = plan(&existing, Some(&[]), &rows);
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.row, e.message.as_str())).collect();
+        assert_eq!(errors, [
+            (2, "Email 'ALEX@example.com' already belongs to Alex"),
+            (4, "Email 'Jo@example.com' is also on row 3"),
+        ]);
+        let actions: Vec<_> = report.changes.iter().map(|c| (c.row, c.action)).collect();
+        assert_eq!(actions, [(1, TipperAction::Enrol), (3, TipperAction::AddTipper)]);
+    }
+
+    #[test]
+    fn export_reads_back_in() {
+        let tippers = [tipper(1, "Sam, Jr")];
+        let rows = parse(&export(&tippers).unwrap()).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "Sam, Jr");
+        assert_eq!(rows[0].email, tippers[0].email);
+    }
+}
@@ -0,0 +1,38 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::util::ApiError;
+use rocket::data::{Data, ToByteUnit};
+
+/// Largest file accepted by the import routes.
+const MAX_UPLOAD_MIB: u64 = 2;
+
+/// Read an uploaded file as text, rejecting it if it's larger than [`MAX_UPLOAD_MIB`].
+pub(crate) async fn read_text(file: Data<'_>) -> Result<String, ApiError> {
+    let text = file.open(MAX_UPLOAD_MIB.mebibytes()).into_string().await
+        .map_err(|e| ApiError::Invalid(format!("Unable to read the file: {}", e)))?;
+    if !text.is_complete() {
+        return Err(ApiError::Invalid(format!("File is larger than {} MiB", MAX_UPLOAD_MIB)));
+    }
+    Ok(text.into_inner())
+}
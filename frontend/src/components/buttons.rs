@@ -57,6 +57,8 @@ pub struct TipSelectorProps {
     pub selected_team_id: Option<i32>,
     pub on_change: Callback<i32>,
     pub name: String, // Add a name prop for radio group
+    #[prop_or_default]
+    pub disabled: bool,
 }
 
 #[function_component(TipSelector)]
@@ -78,6 +80,7 @@ pub fn tip_selector(props: &TipSelectorProps) -> Html {
                 id={format!("home-{}-{}", home_id, props.name)}
                 name={props.name.clone()}
                 checked={props.selected_team_id == Some(home_id)}
+                disabled={props.disabled}
                 oninput={Callback::from({
                     let handle_select = handle_select.clone();
                     move |_| handle_select(home_id)
@@ -94,6 +97,7 @@ pub fn tip_selector(props: &TipSelectorProps) -> Html {
                 id={format!("away-{}-{}", away_id, props.name)}
                 name={props.name.clone()}
                 checked={props.selected_team_id == Some(away_id)}
+                disabled={props.disabled}
                 oninput={Callback::from({
                     let handle_select = handle_select.clone();
                     move |_| handle_select(away_id)
@@ -22,14 +22,15 @@
  *
  */
 use crate::components::buttons::IconButton;
-use crate::components::icons::{cancel_icon, delete_icon, games_icon, save_icon};
+use crate::components::icons::{cancel_icon, delete_icon, games_icon, reset_icon, save_icon};
 use crate::{View, ViewContext};
-use chrono::NaiveDate;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime};
 use futures::join;
 use gloo_net::http::Request;
 use kelpie_models::game::Game;
 use kelpie_models::round::Round;
 use kelpie_models::team::Team;
+use kelpie_models::venue::Venue;
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use yew::prelude::*;
@@ -38,6 +39,19 @@ use yew::prelude::*;
 struct NewRound {
     round: Round,
     games: Vec<Game>,
+    #[serde(default)]
+    byes: Vec<i32>,
+    /// Seed the server drew a template round's games with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+/// The season's teams that aren't in any of the games.
+fn byes(teams: &[Team], games: &[Game]) -> Vec<i32> {
+    teams.iter()
+        .filter_map(|t| t.id)
+        .filter(|id| !games.iter().any(|g| g.home_team_id == *id || g.away_team_id == *id))
+        .collect()
 }
 
 #[derive(Properties, PartialEq)]
@@ -46,6 +60,30 @@ pub struct EditRoundProps {
     pub round_id: Option<i32>,
 }
 
+/// A new round for the season with games drawn by the server, the same games each time for the same `seed`.
+async fn fetch_template(season_id: Option<i32>, seed: Option<u64>) -> Option<NewRound> {
+    match Request::get("/api/template_round")
+        .query(season_id.map(|id| ("season_id", id.to_string())))
+        .query(seed.map(|seed| ("seed", seed.to_string())))
+        .send()
+        .await
+    {
+        Ok(response) => {
+            if let Ok(data) = response.json::<NewRound>().await {
+                info!("Fetched template round");
+                Some(data)
+            } else {
+                debug!("Failed to parse template round response");
+                None
+            }
+        }
+        Err(e) => {
+            debug!("Error fetching template round: {}", e);
+            None
+        }
+    }
+}
+
 #[function_component(EditRound)]
 pub fn edit_round(props: &EditRoundProps) -> Html {
     let view_context = use_context::<ViewContext>().expect("ViewContext not found");
@@ -53,15 +91,19 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
     let round = use_state(NewRound::default);
     let games = use_state(|| vec![]);
     let teams = use_state(|| Vec::<Team>::new());
+    let venues = use_state(|| Vec::<Venue>::new());
     let round_id = props.round_id.clone();
     let tips_exist = use_state(|| false);
+    let seed = use_state(String::new);
 
     // Fetch the teams when the component mounts
     {
         let teams = teams.clone();
+        let season_id = *view_context.season_id;
         use_effect_with((), move |_| {
             wasm_bindgen_futures::spawn_local(async move {
                 match Request::get("/api/teams")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
                     .send()
                     .await
                 {
@@ -80,10 +122,23 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
             || ()
         });
 
+        let venues = venues.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get("/api/venues").send().await {
+                    Ok(response) => venues.set(response.json::<Vec<Venue>>().await.unwrap_or_default()),
+                    Err(e) => debug!("Error fetching venues: {}", e),
+                }
+            });
+            || ()
+        });
+
         let round = round.clone();
         let games = games.clone();
         let id = props.round_id.clone();
         let tips_exist = tips_exist.clone();
+        let seed = seed.clone();
+        let season_id = *view_context.season_id;
         use_effect_with((), move |_| {
             if let Some(id) = id {
                 // Fetch round and tips_exist concurrently
@@ -118,22 +173,10 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
                 || ()
             } else {
                 wasm_bindgen_futures::spawn_local(async move {
-                    match Request::get("/api/template_round")
-                        .send()
-                        .await
-                    {
-                        Ok(response) => {
-                            if let Ok(data) = response.json::<NewRound>().await {
-                                games.set(data.games.clone());
-                                round.set(data);
-                                info!("Fetched template round");
-                            } else {
-                                debug!("Failed to parse template round response");
-                            }
-                        }
-                        Err(e) => {
-                            debug!("Error fetching template round: {}", e);
-                        }
+                    if let Some(data) = fetch_template(season_id, None).await {
+                        games.set(data.games.clone());
+                        seed.set(data.seed.map(|s| s.to_string()).unwrap_or_default());
+                        round.set(data);
                     }
                 });
                 || ()
@@ -146,8 +189,13 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
         let round = round.clone();
         Callback::from(move |_| {
             let mut g = (*games).clone();
+            // Default to the start date, in the same time zone as the other games
+            let offset = g.last().map(|last: &Game| *last.kick_off.offset())
+                .unwrap_or(FixedOffset::east_opt(0).unwrap());
+            let kick_off = round.round.start_date.and_hms_opt(14, 0, 0).unwrap()
+                .and_local_timezone(offset).unwrap();
             let game = Game {
-                game_date: round.round.start_date, // Default to start date
+                kick_off,
                 ..Default::default()
             };
             g.push(game);
@@ -167,6 +215,7 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
     let on_submit = {
         let round = round.clone();
         let games = games.clone();
+        let teams = teams.clone();
         let set_error_msg = props.set_error_msg.clone();
         let view_context = view_context.clone();
 
@@ -174,6 +223,7 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
             let set_error_msg = set_error_msg.clone();
             let mut round_data = (*round).clone();
             round_data.games = (*games).clone();
+            round_data.byes = byes(&teams, &games);
             let view_context = view_context.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
@@ -218,6 +268,32 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
         })
     };
 
+    // Draw the template round again, from the seed shown so an earlier draw can be repeated
+    let on_redraw = {
+        let round = round.clone();
+        let games = games.clone();
+        let seed = seed.clone();
+        let season_id = *view_context.season_id;
+        let set_error_msg = props.set_error_msg.clone();
+        Callback::from(move |_| {
+            let round = round.clone();
+            let games = games.clone();
+            let seed = seed.clone();
+            let set_error_msg = set_error_msg.clone();
+            let Ok(value) = seed.trim().parse::<u64>() else {
+                set_error_msg.emit(Some("The draw seed must be a whole number".to_string()));
+                return;
+            };
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(data) = fetch_template(season_id, Some(value)).await {
+                    set_error_msg.emit(None);
+                    games.set(data.games.clone());
+                    round.set(data);
+                }
+            });
+        })
+    };
+
     let on_cancel = {
         let view_context = view_context.clone();
         Callback::from(move |_| view_context.set_view(View::Rounds))
@@ -302,7 +378,8 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
                         <th>{ "score" }</th>
                         <th>{ "Away team" }</th>
                         <th>{ "score" }</th>
-                        <th>{ "Date" }</th>
+                        <th>{ "Kick-off" }</th>
+                        <th>{ "Venue" }</th>
                     </tr>
                 </thead>
                 <tbody>
@@ -314,10 +391,15 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
                                     disabled={*tips_exist}
                                     onchange={Callback::from({
                                         let games = games.clone();
+                                        let teams = teams.clone();
                                         move |e: Event| {
                                             let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
                                             let mut g = (*games).clone();
                                             g[i].home_team_id = value.parse().unwrap_or(0);
+                                            // Played at the new home team's ground
+                                            g[i].venue_id = teams.iter()
+                                                .find(|t| t.id == Some(g[i].home_team_id))
+                                                .and_then(|t| t.home_venue_id);
                                             games.set(g);
                                         }
                                     })}>
@@ -390,21 +472,43 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
                                 />
                             </td>
                             <td>
-                                <input type="date" placeholder="Game Date"
-                                    value={game.game_date.format("%Y-%m-%d").to_string()}
-                                    min={current_round.start_date.format("%Y-%m-%d").to_string()}
-                                    max={current_round.end_date.format("%Y-%m-%d").to_string()}
+                                <input type="datetime-local" placeholder="Kick-off"
+                                    value={game.kick_off.format("%Y-%m-%dT%H:%M").to_string()}
+                                    min={current_round.start_date.format("%Y-%m-%dT00:00").to_string()}
+                                    max={current_round.end_date.format("%Y-%m-%dT23:59").to_string()}
                                     oninput={Callback::from({
                                         let games = games.clone();
                                         move |e: InputEvent| {
                                             let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
                                             let mut g = (*games).clone();
-                                            g[i].game_date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").expect("Invalid date format");
-                                            games.set(g);
+                                            // Kick-off is entered as competition local time, keep the game's offset
+                                            if let Ok(local) = NaiveDateTime::parse_from_str(value.as_str(), "%Y-%m-%dT%H:%M") {
+                                                if let Some(kick_off) = local.and_local_timezone(*g[i].kick_off.offset()).single() {
+                                                    g[i].kick_off = kick_off;
+                                                    games.set(g);
+                                                }
+                                            }
                                         }
                                     })}
                                 />
                             </td>
+                            <td>
+                                <select
+                                    onchange={Callback::from({
+                                        let games = games.clone();
+                                        move |e: Event| {
+                                            let value = e.target_unchecked_into::<web_sys::HtmlSelectElement>().value();
+                                            let mut g = (*games).clone();
+                                            g[i].venue_id = value.parse().ok();
+                                            games.set(g);
+                                        }
+                                    })}>
+                                    <option value="" selected={game.venue_id.is_none()}>{ "None" }</option>
+                                    { for venues.iter().filter_map(|v| v.id.map(|id| (id, v))).map(|(id, venue)| html! {
+                                        <option value={id.to_string()} selected={game.venue_id == Some(id)}>{ &venue.name }</option>
+                                    })}
+                                </select>
+                            </td>
                             <td>
                                 <div class="button-row">
                                     <IconButton onclick={Callback::from({
@@ -419,6 +523,38 @@ pub fn edit_round(props: &EditRoundProps) -> Html {
                     })}
                 </tbody>
             </table>
+            {
+                match byes(&teams, &games).as_slice() {
+                    [] => html! {},
+                    bye_teams => html! {
+                        <p class="byes">
+                            { "Bye: " }
+                            { bye_teams.iter()
+                                .filter_map(|id| teams.iter().find(|t| t.id == Some(*id)))
+                                .map(|t| t.nickname.clone())
+                                .collect::<Vec<_>>()
+                                .join(", ") }
+                        </p>
+                    },
+                }
+            }
+            if round_id.is_none() {
+                <div style="display: flex; align-items: center; gap: 0.5rem; margin: 0.5rem 0;">
+                    <label>
+                        { "Draw seed " }
+                        <input type="number" min="0" style="width: 12ch;"
+                            value={(*seed).clone()}
+                            oninput={Callback::from({
+                                let seed = seed.clone();
+                                move |e: InputEvent| seed.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value())
+                            })}
+                        />
+                    </label>
+                    <IconButton label="Redraw" onclick={on_redraw}>
+                        { reset_icon() }
+                    </IconButton>
+                </div>
+            }
                 <div style="display: flex; width: 100%; gap: 1rem;">
                     <div class="button-row">
                         <IconButton label="Add Game" onclick={on_add_game}>
@@ -14,6 +14,17 @@ pub fn teams_icon() -> Html {
     }
 }
 
+// Seasons Icon
+pub fn seasons_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <rect x="3" y="5" width="18" height="16" rx="2" />
+            <path d="M16 3v4M8 3v4M3 10h18" />
+        </svg>
+    }
+}
+
 // Tippers Icon
 pub fn tippers_icon() -> Html {
     html! {
@@ -178,3 +189,92 @@ pub fn csv_icon() -> Html {
         </svg>
     }
 }
+
+pub fn xlsx_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <rect x="3" y="3" width="18" height="18" rx="2" ry="2"/>
+            <text x="7" y="17" font-size="8" fill="currentColor" font-family="monospace">{"XLS"}</text>
+            <line x1="3" y1="7" x2="21" y2="7"/>
+            <line x1="9" y1="7" x2="9" y2="21"/>
+        </svg>
+    }
+}
+
+// Logout Icon
+pub fn logout_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <path d="M9 21H5a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h4" />
+            <polyline points="16 17 21 12 16 7" />
+            <line x1="21" y1="12" x2="9" y2="12" />
+        </svg>
+    }
+}
+
+// Print Icon
+pub fn print_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <polyline points="6 9 6 2 18 2 18 9" />
+            <path d="M6 18H4a2 2 0 0 1-2-2v-5a2 2 0 0 1 2-2h16a2 2 0 0 1 2 2v5a2 2 0 0 1-2 2h-2" />
+            <rect x="6" y="14" width="12" height="8" />
+        </svg>
+    }
+}
+
+// Matrix Icon
+pub fn matrix_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <rect x="3" y="3" width="18" height="18" rx="2" ry="2"/>
+            <line x1="3" y1="9" x2="21" y2="9"/>
+            <line x1="3" y1="15" x2="21" y2="15"/>
+            <line x1="9" y1="3" x2="9" y2="21"/>
+            <line x1="15" y1="3" x2="15" y2="21"/>
+        </svg>
+    }
+}
+
+// Ladder Icon
+pub fn ladder_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <line x1="8" y1="6" x2="21" y2="6"/>
+            <line x1="8" y1="12" x2="21" y2="12"/>
+            <line x1="8" y1="18" x2="21" y2="18"/>
+            <text x="2" y="8" font-size="6" fill="currentColor" font-family="monospace">{"1"}</text>
+            <text x="2" y="14" font-size="6" fill="currentColor" font-family="monospace">{"2"}</text>
+            <text x="2" y="20" font-size="6" fill="currentColor" font-family="monospace">{"3"}</text>
+        </svg>
+    }
+}
+
+// Results Icon
+pub fn results_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <rect x="2" y="5" width="20" height="14" rx="2" ry="2"/>
+            <line x1="12" y1="5" x2="12" y2="19"/>
+            <text x="4" y="15" font-size="7" fill="currentColor" font-family="monospace">{"3"}</text>
+            <text x="15" y="15" font-size="7" fill="currentColor" font-family="monospace">{"1"}</text>
+        </svg>
+    }
+}
+
+// Venues Icon
+pub fn venues_icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none"
+            stroke="currentColor" stroke-width="2.2" stroke-linecap="round" stroke-linejoin="round">
+            <path d="M12 21s-7-6.2-7-11.5a7 7 0 0 1 14 0C19 14.8 12 21 12 21z"/>
+            <circle cx="12" cy="9.5" r="2.5"/>
+        </svg>
+    }
+}
@@ -0,0 +1,222 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::{csv_icon, save_icon};
+use gloo_net::http::Request;
+use serde::Deserialize;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ImportReport {
+    applied: bool,
+    new_rounds: Vec<i32>,
+    changes: Vec<ImportChange>,
+    errors: Vec<RowError>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ImportChange {
+    row: usize,
+    action: String,
+    round_number: i32,
+    home_team: String,
+    away_team: String,
+    kick_off: chrono::DateTime<chrono::FixedOffset>,
+    home_team_score: Option<i32>,
+    away_team_score: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct RowError {
+    pub(crate) row: usize,
+    pub(crate) message: String,
+}
+
+fn action_label(action: &str) -> &'static str {
+    match action {
+        "add_game" => "Add game",
+        "move_game" => "Change kick-off",
+        "set_result" => "Enter result",
+        _ => "No change",
+    }
+}
+
+/// The uploaded file, and whether it is JSON rather than CSV.
+#[derive(Clone)]
+struct ImportFile {
+    text: String,
+    json: bool,
+}
+
+async fn send(season_id: i32, file: &ImportFile, dry_run: bool) -> Result<ImportReport, String> {
+    let url = format!("/api/import?season_id={}&dry_run={}", season_id, dry_run);
+    let content_type = if file.json { "application/json" } else { "text/csv" };
+    let resp = Request::post(&url)
+        .header("Content-Type", content_type)
+        .body(file.text.clone())
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.ok() {
+        resp.json::<ImportReport>().await.map_err(|e| e.to_string())
+    } else {
+        Err(resp.text().await.unwrap_or_default())
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct ImportProps {
+    pub(crate) season_id: i32,
+    /// Called once an import has been applied
+    pub(crate) on_import: Callback<()>,
+}
+
+/// Upload a fixtures or results file, preview what it changes, then apply it.
+#[function_component(ImportPanel)]
+pub(crate) fn import_panel(props: &ImportProps) -> Html {
+    let file = use_mut_ref(|| None::<ImportFile>);
+    let report = use_state(|| None::<ImportReport>);
+    let error_msg = use_state(|| None::<String>);
+
+    let on_file = {
+        let file = file.clone();
+        let report = report.clone();
+        let error_msg = error_msg.clone();
+        let season_id = props.season_id;
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            let Some(selected) = input.files().and_then(|files| files.get(0)) else { return };
+            let file = file.clone();
+            let report = report.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            report.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let json = selected.name().to_lowercase().ends_with(".json");
+                let text = match JsFuture::from(selected.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(_) => {
+                        error_msg.set(Some("Unable to read the file".to_string()));
+                        return;
+                    }
+                };
+                let import = ImportFile { text, json };
+                match send(season_id, &import, true).await {
+                    Ok(preview) => report.set(Some(preview)),
+                    Err(e) => error_msg.set(Some(format!("Import failed: {}", e))),
+                }
+                *file.borrow_mut() = Some(import);
+            });
+        })
+    };
+
+    let on_apply = {
+        let file = file.clone();
+        let report = report.clone();
+        let error_msg = error_msg.clone();
+        let on_import = props.on_import.clone();
+        let season_id = props.season_id;
+        Callback::from(move |_| {
+            let file = file.clone();
+            let report = report.clone();
+            let error_msg = error_msg.clone();
+            let on_import = on_import.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(import) = file.borrow().clone() else { return };
+                match send(season_id, &import, false).await {
+                    Ok(applied) => {
+                        report.set(Some(applied));
+                        on_import.emit(());
+                    }
+                    Err(e) => error_msg.set(Some(format!("Import failed: {}", e))),
+                }
+            });
+        })
+    };
+
+    let can_apply = report.as_ref().is_some_and(|r| !r.applied && r.errors.is_empty()
+        && r.changes.iter().any(|c| c.action != "unchanged"));
+
+    html! {
+        <div class="card">
+            <h3>{ "Import fixtures and results" }</h3>
+            <p>{ "CSV with the columns round, date, home, away, home_score, away_score, or a JSON array of the same." }</p>
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <div style="display: flex; align-items: center; gap: 1rem;">
+                { csv_icon() }
+                <input type="file" accept=".csv,.json" onchange={on_file} />
+                <IconButton label="Apply" onclick={on_apply} disabled={!can_apply}>{ save_icon() }</IconButton>
+            </div>
+            if let Some(report) = &*report {
+                if report.applied {
+                    <p style="color: #388e3c; font-weight: bold;">{ "Import applied." }</p>
+                }
+                if !report.new_rounds.is_empty() {
+                    <p>{ format!("New rounds: {}", report.new_rounds.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")) }</p>
+                }
+                if !report.errors.is_empty() {
+                    <div class="alert">
+                        { for report.errors.iter().map(|e| html! { <div>{ format!("Row {}: {}", e.row, e.message) }</div> }) }
+                    </div>
+                }
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{ "Row" }</th>
+                            <th>{ "Change" }</th>
+                            <th>{ "Round" }</th>
+                            <th>{ "Kick-off" }</th>
+                            <th>{ "Home" }</th>
+                            <th>{ "Away" }</th>
+                            <th>{ "Score" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.changes.iter().map(|c| html! {
+                            <tr key={c.row}>
+                                <td>{ c.row }</td>
+                                <td>{ action_label(&c.action) }</td>
+                                <td>{ c.round_number }</td>
+                                <td>{ c.kick_off.format("%a %d %b %H:%M").to_string() }</td>
+                                <td>{ &c.home_team }</td>
+                                <td>{ &c.away_team }</td>
+                                <td>{
+                                    match (c.home_team_score, c.away_team_score) {
+                                        (Some(home), Some(away)) => format!("{} - {}", home, away),
+                                        _ => String::new(),
+                                    }
+                                }</td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::submit_icon;
+use gloo_net::http::Request;
+use kelpie_models::tipper::Tipper;
+use serde_json::json;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct LoginProps {
+    pub on_login: Callback<Tipper>,
+}
+
+#[function_component(Login)]
+pub fn login(props: &LoginProps) -> Html {
+    let email = use_state(String::new);
+    let password = use_state(String::new);
+    let error_msg = use_state(|| None::<String>);
+
+    let submit = {
+        let email = email.clone();
+        let password = password.clone();
+        let error_msg = error_msg.clone();
+        let on_login = props.on_login.clone();
+        Callback::from(move |_: MouseEvent| {
+            let payload = json!({ "email": (*email).clone(), "password": (*password).clone() });
+            let password = password.clone();
+            let error_msg = error_msg.clone();
+            let on_login = on_login.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::post("/api/login").json(&payload).unwrap().send().await {
+                    Ok(resp) if resp.ok() => match resp.json::<Tipper>().await {
+                        Ok(tipper) => {
+                            password.set(String::new());
+                            on_login.emit(tipper);
+                        }
+                        Err(e) => error_msg.set(Some(format!("Failed to parse tipper: {}", e))),
+                    },
+                    Ok(resp) => {
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(text));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error logging in: {}", e))),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="card" style="max-width: 24rem; margin: 2rem auto;">
+            <h2>{ "Log in" }</h2>
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <input type="email" placeholder="Email" value={(*email).clone()}
+                oninput={{
+                    let email = email.clone();
+                    Callback::from(move |e: InputEvent| {
+                        email.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+                    })
+                }}
+            />
+            <input type="password" placeholder="Password" value={(*password).clone()}
+                oninput={{
+                    let password = password.clone();
+                    Callback::from(move |e: InputEvent| {
+                        password.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+                    })
+                }}
+            />
+            <IconButton label="Log in" onclick={submit}>
+                { submit_icon() }
+            </IconButton>
+        </div>
+    }
+}
@@ -26,8 +26,15 @@ pub(crate) mod icons;
 pub(crate) mod buttons;
 pub(crate) mod tipper_list;
 pub(crate) mod team_list;
+pub(crate) mod venue_list;
 pub(crate) mod edit_round;
+pub(crate) mod import;
+pub(crate) mod tipper_import;
 pub(crate) mod round_list;
+pub(crate) mod results;
+pub(crate) mod season_list;
+pub(crate) mod scoring_rules;
 pub(crate) mod tip_view;
 pub(crate) mod tips;
+pub(crate) mod login;
 pub(crate) mod reports;
@@ -0,0 +1,195 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use gloo_net::http::Request;
+use log::debug;
+use serde::Deserialize;
+use yew::prelude::*;
+use crate::ViewContext;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct FormGuide {
+    tipper_name: String,
+    rounds: Vec<RoundForm>,
+    decided: i64,
+    correct: i64,
+    strike_rate: f64,
+    perfect_rounds: i64,
+    longest_streak: i64,
+    best_team: Option<TeamForm>,
+    worst_team: Option<TeamForm>,
+    teams: Vec<TeamForm>,
+    tips: i64,
+    home_tips: i64,
+    home_rate: f64,
+    favourite_games: i64,
+    favourite_tips: i64,
+    favourite_rate: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct RoundForm {
+    round_id: i32,
+    round_number: i32,
+    tip_score: i64,
+    bonus_score: i64,
+    decided: i64,
+    correct: i64,
+    perfect: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct TeamForm {
+    team_id: i32,
+    team_name: String,
+    tipped: i64,
+    correct: i64,
+}
+
+fn team_summary(team: &Option<TeamForm>) -> String {
+    match team {
+        Some(team) => format!("{} ({} of {})", team.team_name, team.correct, team.tipped),
+        None => "–".to_string(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct FormGuideProps {
+    pub(crate) tipper_id: i32,
+}
+
+/// How a single tipper has been going this season.
+#[function_component(FormGuideView)]
+pub(crate) fn form_guide(props: &FormGuideProps) -> Html {
+    let guide = use_state(|| None::<FormGuide>);
+
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+
+    {
+        let guide = guide.clone();
+        use_effect_with((props.tipper_id, season_id), move |(tipper_id, season_id)| {
+            let (tipper_id, season_id) = (*tipper_id, *season_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                let resp = Request::get(&format!("/reports/tipper/{}", tipper_id))
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await;
+                match resp {
+                    Ok(response) => guide.set(response.json::<FormGuide>().await.ok()),
+                    Err(e) => {
+                        debug!("Error fetching form guide: {}", e);
+                        guide.set(None);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let Some(guide) = &*guide else {
+        return html! { <div><h1>{ "Form Guide" }</h1><span>{ "Loading..." }</span></div> };
+    };
+
+    html! {
+        <div>
+            <h1>{ format!("Form Guide: {}", guide.tipper_name) }</h1>
+            <div class="card">
+                <table>
+                    <tbody>
+                        <tr>
+                            <th>{ "Strike rate" }</th>
+                            <td>{ format!("{:.1}% ({} of {})", guide.strike_rate, guide.correct, guide.decided) }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Perfect rounds" }</th>
+                            <td>{ guide.perfect_rounds }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Longest correct streak" }</th>
+                            <td>{ guide.longest_streak }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Best team to tip" }</th>
+                            <td>{ team_summary(&guide.best_team) }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Worst team to tip" }</th>
+                            <td>{ team_summary(&guide.worst_team) }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Tipped the home team" }</th>
+                            <td>{ format!("{:.1}% ({} of {})", guide.home_rate, guide.home_tips, guide.tips) }</td>
+                        </tr>
+                        <tr>
+                            <th>{ "Tipped the favourite" }</th>
+                            <td>{ format!("{:.1}% ({} of {})", guide.favourite_rate, guide.favourite_tips, guide.favourite_games) }</td>
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+            <h2>{ "Rounds" }</h2>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ "Round" }</th>
+                        <th>{ "Correct" }</th>
+                        <th>{ "Game Score" }</th>
+                        <th>{ "Bonus Score" }</th>
+                        <th>{ "Total Score" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for guide.rounds.iter().map(|round| html! {
+                        <tr key={round.round_id}>
+                            <td>{ round.round_number }{ if round.perfect { " ★" } else { "" } }</td>
+                            <td>{ format!("{} / {}", round.correct, round.decided) }</td>
+                            <td>{ round.tip_score }</td>
+                            <td>{ round.bonus_score }</td>
+                            <td>{ round.tip_score + round.bonus_score }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+            <h2>{ "Teams" }</h2>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ "Team" }</th>
+                        <th>{ "Tipped" }</th>
+                        <th>{ "Correct" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for guide.teams.iter().map(|team| html! {
+                        <tr key={team.team_id}>
+                            <td>{ &team.team_name }</td>
+                            <td>{ team.tipped }</td>
+                            <td>{ team.correct }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use gloo_net::http::Request;
+use log::debug;
+use serde::Deserialize;
+use yew::prelude::*;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct GameBreakdown {
+    game_id: i32,
+    home_team_name: String,
+    away_team_name: String,
+    home_team_score: Option<i32>,
+    away_team_score: Option<i32>,
+    /// Hidden until the game locks
+    home_picks: Option<i64>,
+    away_picks: Option<i64>,
+    untipped: Option<i64>,
+    correct_tippers: Vec<String>,
+    nobody_correct: bool,
+    everybody_correct: bool,
+}
+
+/// A pick count, or a dash while the game is still open for tipping.
+fn picks(count: Option<i64>) -> String {
+    count.map_or("–".to_string(), |c| c.to_string())
+}
+
+fn result(game: &GameBreakdown) -> String {
+    match (game.home_team_score, game.away_team_score) {
+        (Some(home), Some(away)) => format!("{} - {}", home, away),
+        _ => String::new(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct GameBreakdownProps {
+    pub(crate) round_id: i32,
+}
+
+/// How the tippers picked each game of a round.
+#[function_component(GameBreakdownView)]
+pub(crate) fn game_breakdown(props: &GameBreakdownProps) -> Html {
+    let games = use_state(Vec::<GameBreakdown>::new);
+
+    {
+        let games = games.clone();
+        use_effect_with(props.round_id, move |round_id| {
+            let round_id = *round_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get(&format!("/reports/round/{}/games", round_id)).send().await {
+                    Ok(response) => {
+                        if let Ok(json) = response.json::<Vec<GameBreakdown>>().await {
+                            games.set(json);
+                        } else {
+                            games.set(vec![]);
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Error fetching game breakdown: {}", e);
+                        games.set(vec![]);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    html! {
+        <div>
+            <h2>{ "Games" }</h2>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ "Home" }</th>
+                        <th>{ "Picks" }</th>
+                        <th>{ "Away" }</th>
+                        <th>{ "Picks" }</th>
+                        <th>{ "Not Tipped" }</th>
+                        <th>{ "Result" }</th>
+                        <th>{ "Correct" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for games.iter().map(|game| {
+                        let flag = if game.nobody_correct {
+                            html! { <span class="nobody-correct">{ "Nobody got this one" }</span> }
+                        } else if game.everybody_correct {
+                            html! { <span class="everybody-correct">{ "Everybody got this one" }</span> }
+                        } else {
+                            html! { { game.correct_tippers.join(", ") } }
+                        };
+                        html! {
+                            <tr key={game.game_id}>
+                                <td>{ &game.home_team_name }</td>
+                                <td>{ picks(game.home_picks) }</td>
+                                <td>{ &game.away_team_name }</td>
+                                <td>{ picks(game.away_picks) }</td>
+                                <td>{ picks(game.untipped) }</td>
+                                <td>{ result(game) }</td>
+                                <td>{ flag }</td>
+                            </tr>
+                        }
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use gloo_net::http::Request;
+use kelpie_models::round::Round;
+use log::debug;
+use serde::Deserialize;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::ViewContext;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct LadderEntry {
+    position: i64,
+    team_id: i32,
+    team_name: String,
+    played: i64,
+    won: i64,
+    lost: i64,
+    drawn: i64,
+    points_for: i64,
+    points_against: i64,
+    /// `None` until the team has conceded a point
+    percentage: Option<f64>,
+    premiership_points: i64,
+}
+
+/// The team ladder for the selected season.
+#[function_component(Ladder)]
+pub(crate) fn ladder() -> Html {
+    let ladder = use_state(Vec::<LadderEntry>::new);
+    let rounds = use_state(Vec::<Round>::new);
+    let up_to_round = use_state(|| None::<i32>);
+    // Blank uses the premiership points from the server config
+    let win_points = use_state(|| None::<i64>);
+    let draw_points = use_state(|| None::<i64>);
+
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+
+    {
+        let rounds = rounds.clone();
+        let up_to_round = up_to_round.clone();
+        use_effect_with(season_id, move |season_id| {
+            let season_id = *season_id;
+            up_to_round.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let resp = Request::get("api/rounds")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await;
+                match resp {
+                    Ok(response) => rounds.set(response.json::<Vec<Round>>().await.unwrap_or_default()),
+                    Err(_) => rounds.set(vec![]),
+                }
+            });
+            || ()
+        });
+    }
+
+    {
+        let ladder = ladder.clone();
+        use_effect_with((season_id, *up_to_round, *win_points, *draw_points), move |(season_id, up_to_round, win_points, draw_points)| {
+            let params = [
+                season_id.map(|id| ("season_id", id.to_string())),
+                season_id.and(*up_to_round).map(|n| ("up_to_round", n.to_string())),
+                win_points.map(|p| ("win_points", p.to_string())),
+                draw_points.map(|p| ("draw_points", p.to_string())),
+            ];
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get("/reports/ladder").query(params.into_iter().flatten()).send().await {
+                    Ok(response) => {
+                        if let Ok(json) = response.json::<Vec<LadderEntry>>().await {
+                            ladder.set(json);
+                        } else {
+                            ladder.set(vec![]);
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Error fetching ladder: {}", e);
+                        ladder.set(vec![]);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_round_select = {
+        let up_to_round = up_to_round.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                up_to_round.set(select.value().parse::<i32>().ok());
+            }
+        })
+    };
+
+    let on_points = |points: UseStateHandle<Option<i64>>| {
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                points.set(input.value().parse::<i64>().ok());
+            }
+        })
+    };
+
+    html! {
+        <div>
+            <h1>{ "Ladder" }</h1>
+            <div style="display: flex; flex-direction:row; padding: 10px; border-bottom: 1px solid #ccc; align-items: center; gap: 1rem;">
+                <span>{ "After:" }</span>
+                <select onchange={on_round_select} style="width: 10rem;">
+                    <option value="" selected={up_to_round.is_none()}>{ "Latest round" }</option>
+                    { for rounds.iter().map(|round| html! {
+                        <option value={round.round_number.to_string()} selected={Some(round.round_number) == *up_to_round}>
+                            { format!("Round {}", round.round_number) }
+                        </option>
+                    }) }
+                </select>
+                <span>{ "Points for a win:" }</span>
+                <input type="number" min="0" style="width: 4rem;" placeholder="Default" onchange={on_points(win_points.clone())} />
+                <span>{ "for a draw:" }</span>
+                <input type="number" min="0" style="width: 4rem;" placeholder="Default" onchange={on_points(draw_points.clone())} />
+            </div>
+            <table>
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>{ "Team" }</th>
+                        <th>{ "P" }</th>
+                        <th>{ "W" }</th>
+                        <th>{ "L" }</th>
+                        <th>{ "D" }</th>
+                        <th>{ "For" }</th>
+                        <th>{ "Against" }</th>
+                        <th>{ "%" }</th>
+                        <th>{ "Pts" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for ladder.iter().map(|entry| html! {
+                        <tr key={entry.team_id}>
+                            <td>{ entry.position }</td>
+                            <td>{ &entry.team_name }</td>
+                            <td>{ entry.played }</td>
+                            <td>{ entry.won }</td>
+                            <td>{ entry.lost }</td>
+                            <td>{ entry.drawn }</td>
+                            <td>{ entry.points_for }</td>
+                            <td>{ entry.points_against }</td>
+                            <td>{ entry.percentage.map_or("–".to_string(), |p| format!("{:.1}", p)) }</td>
+                            <td>{ entry.premiership_points }</td>
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -26,19 +26,36 @@ use log::debug;
 use yew::prelude::*;
 use serde::Deserialize;
 use kelpie_models::round::Round;
-use web_sys::{js_sys, Blob, Url};
-use wasm_bindgen::JsCast;
-use wasm_bindgen::JsValue;
-use gloo_utils::document;
 use crate::components::buttons::IconButton;
-use crate::components::icons::{csv_icon, save_icon};
+use crate::components::icons::{csv_icon, print_icon, tips_icon, xlsx_icon};
+use crate::components::reports::download_url;
+use crate::components::reports::game_breakdown::GameBreakdownView;
+use crate::{View, ViewContext};
 
 #[derive(Deserialize, Debug, Clone)]
 struct LeaderboardEntry {
+    tipper_id: i32,
     tipper_name: String,
     tip_score: i64,
     bonus_score: i64,
     total_score: i64,
+    margin_error: i64,
+    rank: i64,
+    previous_rank: Option<i64>,
+}
+
+/// An arrow showing how far a tipper has moved since the previous round.
+fn movement(entry: &LeaderboardEntry) -> Html {
+    match entry.previous_rank {
+        Some(previous) if previous > entry.rank => html! {
+            <span class="movement-up" title={format!("Up from {}", previous)}>{ format!("▲{}", previous - entry.rank) }</span>
+        },
+        Some(previous) if previous < entry.rank => html! {
+            <span class="movement-down" title={format!("Down from {}", previous)}>{ format!("▼{}", entry.rank - previous) }</span>
+        },
+        Some(_) => html! { <span class="movement-none">{ "–" }</span> },
+        None => html! {},
+    }
 }
 
 #[function_component(Leaderboard)]
@@ -46,13 +63,23 @@ pub(crate) fn leaderboard() -> Html {
     let leaderboard = use_state(|| vec![]);
     let rounds = use_state(|| Option::<Vec<Round>>::None);
     let selected_round = use_state(|| None::<i32>);
+    let all_time = use_state(|| false);
 
-    // Fetch rounds on mount
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+
+    // Fetch the season's rounds whenever the selected season changes
     {
         let rounds = rounds.clone();
-        use_effect_with((), move |_| {
+        let selected_round = selected_round.clone();
+        use_effect_with(season_id, move |season_id| {
+            let season_id = *season_id;
+            selected_round.set(None);
             wasm_bindgen_futures::spawn_local(async move {
-                let resp = Request::get("api/rounds").send().await;
+                let resp = Request::get("api/rounds")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await;
                 match resp {
                     Ok(response) => {
                         if let Ok(json) = response.json::<Vec<Round>>().await {
@@ -70,18 +97,17 @@ pub(crate) fn leaderboard() -> Html {
         });
     }
 
-    // Fetch leaderboard when selected_round changes
+    // Fetch leaderboard when the selection changes
     {
         let leaderboard = leaderboard.clone();
-        let selected_round = selected_round.clone();
-        use_effect_with(selected_round, move |selected_round| {
+        use_effect_with((*selected_round, *all_time, season_id), move |(selected_round, all_time, season_id)| {
             let leaderboard = leaderboard.clone();
-            let selected_round = selected_round.clone();
+            let (selected_round, all_time, season_id) = (*selected_round, *all_time, *season_id);
             wasm_bindgen_futures::spawn_local(async move {
-                let url = if let Some(round_id) = *selected_round {
-                    format!("/reports/round/{}", round_id)
-                } else {
-                    "/reports/leaderboard".to_string()
+                let url = match (selected_round, season_id) {
+                    (Some(round_id), _) => format!("/reports/round/{}", round_id),
+                    (None, Some(season_id)) if !all_time => format!("/reports/leaderboard?season_id={}", season_id),
+                    _ => "/reports/leaderboard".to_string(),
                 };
                 match Request::get(&url).send().await {
                     Ok(response) => {
@@ -104,50 +130,52 @@ pub(crate) fn leaderboard() -> Html {
     // Handle round selection
     let on_round_select = {
         let selected_round = selected_round.clone();
+        let all_time = all_time.clone();
         Callback::from(move |e: Event| {
             let input = e.target_dyn_into::<web_sys::HtmlSelectElement>();
             if let Some(select) = input {
                 let value = select.value();
                 let round_num = value.parse::<i32>().ok();
+                all_time.set(value == "all");
                 selected_round.set(round_num);
             }
         })
     };
 
-    // Export leaderboard to CSV (Excel-compatible)
-    let export_to_excel = {
-        let leaderboard = leaderboard.clone();
-        Callback::from(move |_| {
-            let mut csv = String::from("Tipper,Game Score,Bonus Score,Total Score\n");
-            for entry in leaderboard.iter() {
-                csv.push_str(&format!(
-                    "\"{}\",{},{},{}\n",
-                    entry.tipper_name.replace('"', "\"\""),
-                    entry.tip_score,
-                    entry.bonus_score,
-                    entry.total_score
-                ));
-            }
-
-            // Create a Blob and trigger download
-            let window = web_sys::window().unwrap();
-            let array = js_sys::Array::new();
-            array.push(&JsValue::from_str(&csv));
-            let blob = Blob::new_with_str_sequence(&array).unwrap();
-            let url = Url::create_object_url_with_blob(&blob).unwrap();
+    // The server renders the export of whatever is selected, a workbook also has a sheet for each round
+    let export = {
+        let selected_round = selected_round.clone();
+        let all_time = all_time.clone();
+        move |format: &'static str| {
+            let url = match (*selected_round, season_id) {
+                (Some(round_id), _) => format!("/reports/export/scores?round_id={}&format={}", round_id, format),
+                (None, Some(season_id)) if !*all_time => format!("/reports/export/leaderboard?season_id={}&format={}", season_id, format),
+                _ => format!("/reports/export/leaderboard?format={}", format),
+            };
+            Callback::from(move |_| download_url(&url))
+        }
+    };
 
-            let document = document();
-            let a = document.create_element("a").unwrap();
-            a.set_attribute("href", &url).unwrap();
-            a.set_attribute("download", "leaderboard.csv").unwrap();
-            a.set_attribute("style", "display: none;").unwrap();
-            document.body().unwrap().append_child(&a).unwrap();
-            let a_html = a.dyn_ref::<web_sys::HtmlElement>().unwrap();
-            a_html.click();
-            document.body().unwrap().remove_child(&a).unwrap();
-            Url::revoke_object_url(&url).unwrap();
+    // Everyone's tips for the selected round, or each round of the season
+    let export_tips = match (*selected_round, season_id) {
+        (Some(round_id), _) => Some(format!("/reports/export/tips?round_id={}&format=xlsx", round_id)),
+        (None, Some(season_id)) if !*all_time => Some(format!("/reports/export/tips?season_id={}&format=xlsx", season_id)),
+        _ => None,
+    };
+    // A PDF of the season or all-time leaderboard for the noticeboard
+    let print = {
+        let all_time = all_time.clone();
+        Callback::from(move |_| match season_id {
+            Some(season_id) if !*all_time => download_url(&format!("/reports/pdf/leaderboard?season_id={}", season_id)),
+            _ => download_url("/reports/pdf/leaderboard"),
         })
     };
+    let is_admin = view_context.tipper.as_ref().is_some_and(|t| t.is_admin);
+
+    let show_form = {
+        let view_context = view_context.clone();
+        Callback::from(move |tipper_id: i32| view_context.set_view(View::FormGuide { tipper_id }))
+    };
 
     html! {
         <div>
@@ -157,10 +185,10 @@ pub(crate) fn leaderboard() -> Html {
                 {
                     match &*rounds {
                         None => html! { <span>{ "Loading..." }</span> },
-                        Some(list) if list.is_empty() => html! { <span>{ "No rounds found" }</span> },
                         Some(list) => html! {
                             <select id="round-select" onchange={on_round_select.clone()} style="width: 15rem;">
-                                <option value="" selected={selected_round.is_none()}>{ "Overall" }</option>
+                                <option value="" selected={selected_round.is_none() && !*all_time}>{ "Season" }</option>
+                                <option value="all" selected={*all_time}>{ "All time" }</option>
                                 { for list.iter().map(|round| {
                                     let selected = Some(round.round_id.unwrap_or(0)) == *selected_round;
                                     html! {
@@ -173,35 +201,55 @@ pub(crate) fn leaderboard() -> Html {
                         }
                     }
                 }
-                <IconButton
-                    label={Some("Export".to_string())}
-                    onclick={export_to_excel}
-                    disabled={false}
-                >
+                <IconButton label={Some("Export".to_string())} onclick={export("csv")} disabled={false}>
                     { csv_icon() }
                 </IconButton>
+                <IconButton label={Some("Excel".to_string())} onclick={export("xlsx")} disabled={false}>
+                    { xlsx_icon() }
+                </IconButton>
+                <IconButton label={Some("PDF".to_string())} onclick={print} disabled={false}>
+                    { print_icon() }
+                </IconButton>
+                if let (true, Some(url)) = (is_admin, export_tips) {
+                    <IconButton label={Some("Tips".to_string())} onclick={Callback::from(move |_| download_url(&url))} disabled={false}>
+                        { tips_icon() }
+                    </IconButton>
+                }
             </div>
             <a href="/" style="display: inline-block; margin-bottom: 1rem;">{ "Back to Main Page" }</a>
             <table>
                 <thead>
                     <tr>
+                        <th>{ "Rank" }</th>
+                        <th></th>
                         <th>{ "Tipper" }</th>
                         <th>{ "Game Score" }</th>
                         <th>{ "Bonus Score" }</th>
                         <th>{ "Total Score" }</th>
+                        <th>{ "Margin Error" }</th>
                     </tr>
                 </thead>
                 <tbody>
                     { for leaderboard.iter().map(|entry| html! {
-                        <tr>
-                            <td>{ &entry.tipper_name }</td>
+                        <tr key={entry.tipper_id}>
+                            <td>{ entry.rank }</td>
+                            <td>{ movement(entry) }</td>
+                            <td>
+                                <span class="tipper-link" onclick={show_form.reform({ let tipper_id = entry.tipper_id; move |_| tipper_id })}>
+                                    { &entry.tipper_name }
+                                </span>
+                            </td>
                             <td>{ &entry.tip_score }</td>
                             <td>{ &entry.bonus_score }</td>
                             <td>{ &entry.total_score}</td>
+                            <td>{ &entry.margin_error }</td>
                         </tr>
                     }) }
                 </tbody>
             </table>
+            if let Some(round_id) = *selected_round {
+                <GameBreakdownView round_id={round_id} />
+            }
         </div>
     }
 }
\ No newline at end of file
@@ -0,0 +1,190 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use gloo_net::http::Request;
+use gloo_utils::window;
+use log::debug;
+use serde::Deserialize;
+use yew::prelude::*;
+use crate::components::buttons::IconButton;
+use crate::components::icons::{csv_icon, print_icon};
+use crate::components::reports::download_csv;
+use crate::{View, ViewContext};
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+struct ScoreMatrix {
+    rounds: Vec<MatrixRound>,
+    rows: Vec<MatrixRow>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct MatrixRound {
+    round_id: i32,
+    round_number: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct MatrixRow {
+    tipper_id: i32,
+    tipper_name: String,
+    rank: i64,
+    cells: Vec<Option<MatrixCell>>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct MatrixCell {
+    tip_score: i64,
+    bonus_score: i64,
+    running_total: i64,
+}
+
+/// The season's history, one row per tipper and one column per round.
+#[function_component(Matrix)]
+pub(crate) fn matrix() -> Html {
+    let matrix = use_state(ScoreMatrix::default);
+
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+
+    {
+        let matrix = matrix.clone();
+        use_effect_with(season_id, move |season_id| {
+            let season_id = *season_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                let resp = Request::get("/reports/matrix")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await;
+                match resp {
+                    Ok(response) => {
+                        if let Ok(json) = response.json::<ScoreMatrix>().await {
+                            matrix.set(json);
+                        } else {
+                            matrix.set(ScoreMatrix::default());
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Error fetching score matrix: {}", e);
+                        matrix.set(ScoreMatrix::default());
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    // Export the matrix to CSV with score, bonus and total columns for each round
+    let export_to_excel = {
+        let matrix = matrix.clone();
+        Callback::from(move |_| {
+            let mut csv = String::from("Rank,Tipper");
+            for round in matrix.rounds.iter() {
+                let n = round.round_number;
+                csv.push_str(&format!(",R{n} Score,R{n} Bonus,R{n} Total"));
+            }
+            csv.push('\n');
+            for row in matrix.rows.iter() {
+                csv.push_str(&format!("{},\"{}\"", row.rank, row.tipper_name.replace('"', "\"\"")));
+                for cell in row.cells.iter() {
+                    match cell {
+                        Some(cell) => csv.push_str(&format!(",{},{},{}", cell.tip_score, cell.bonus_score, cell.running_total)),
+                        None => csv.push_str(",,,"),
+                    }
+                }
+                csv.push('\n');
+            }
+            download_csv("score-matrix.csv", &csv);
+        })
+    };
+
+    let print = Callback::from(|_| {
+        let _ = window().print();
+    });
+
+    let show_form = {
+        let view_context = view_context.clone();
+        Callback::from(move |tipper_id: i32| view_context.set_view(View::FormGuide { tipper_id }))
+    };
+
+    html! {
+        <div class="matrix-report">
+            <h1>{ "Score Matrix" }</h1>
+            <div class="no-print" style="display: flex; flex-direction:row; padding: 10px; border-bottom: 1px solid #ccc; align-items: center;">
+                <IconButton label={Some("Export".to_string())} onclick={export_to_excel} disabled={false}>
+                    { csv_icon() }
+                </IconButton>
+                <IconButton label={Some("Print".to_string())} onclick={print} disabled={false}>
+                    { print_icon() }
+                </IconButton>
+            </div>
+            <table class="matrix">
+                <thead>
+                    <tr>
+                        <th rowspan="2">{ "Rank" }</th>
+                        <th rowspan="2">{ "Tipper" }</th>
+                        { for matrix.rounds.iter().map(|round| html! {
+                            <th key={round.round_id} colspan="3" class="round-start">{ format!("Round {}", round.round_number) }</th>
+                        }) }
+                    </tr>
+                    <tr>
+                        { for matrix.rounds.iter().map(|_| html! {
+                            <>
+                                <th class="round-start" title="Score">{ "S" }</th>
+                                <th title="Bonus">{ "B" }</th>
+                                <th title="Running total">{ "T" }</th>
+                            </>
+                        }) }
+                    </tr>
+                </thead>
+                <tbody>
+                    { for matrix.rows.iter().map(|row| html! {
+                        <tr key={row.tipper_id}>
+                            <td>{ row.rank }</td>
+                            <td>
+                                <span class="tipper-link" onclick={show_form.reform({ let tipper_id = row.tipper_id; move |_| tipper_id })}>
+                                    { &row.tipper_name }
+                                </span>
+                            </td>
+                            { for row.cells.iter().map(|cell| match cell {
+                                Some(cell) => html! {
+                                    <>
+                                        <td class="round-start">{ cell.tip_score }</td>
+                                        <td>{ cell.bonus_score }</td>
+                                        <td class="running-total">{ cell.running_total }</td>
+                                    </>
+                                },
+                                None => html! {
+                                    <>
+                                        <td class="round-start"></td>
+                                        <td></td>
+                                        <td></td>
+                                    </>
+                                },
+                            }) }
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
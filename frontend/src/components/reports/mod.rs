@@ -22,4 +22,40 @@
  *
  */
 
-pub(crate) mod leaderboard;
\ No newline at end of file
+pub(crate) mod form_guide;
+pub(crate) mod game_breakdown;
+pub(crate) mod ladder;
+pub(crate) mod leaderboard;
+pub(crate) mod matrix;
+
+use gloo_utils::document;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use web_sys::{js_sys, Blob, Url};
+
+/// Offer `csv` to the browser as a file download (Excel-compatible).
+pub(crate) fn download_csv(file_name: &str, csv: &str) {
+    let array = js_sys::Array::new();
+    array.push(&JsValue::from_str(csv));
+    let blob = Blob::new_with_str_sequence(&array).unwrap();
+    let url = Url::create_object_url_with_blob(&blob).unwrap();
+    click_link(&url, file_name);
+    Url::revoke_object_url(&url).unwrap();
+}
+
+/// Download a file the server sends as an attachment, such as an export.
+pub(crate) fn download_url(url: &str) {
+    click_link(url, "");
+}
+
+fn click_link(url: &str, file_name: &str) {
+    let document = document();
+    let a = document.create_element("a").unwrap();
+    a.set_attribute("href", url).unwrap();
+    a.set_attribute("download", file_name).unwrap();
+    a.set_attribute("style", "display: none;").unwrap();
+    document.body().unwrap().append_child(&a).unwrap();
+    let a_html = a.dyn_ref::<web_sys::HtmlElement>().unwrap();
+    a_html.click();
+    document.body().unwrap().remove_child(&a).unwrap();
+}
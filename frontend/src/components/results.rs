@@ -0,0 +1,283 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::save_icon;
+use crate::components::tips::RoundWithGames;
+use crate::ViewContext;
+use gloo_net::http::Request;
+use kelpie_models::game::Game;
+use kelpie_models::round::Round;
+use kelpie_models::team::Team;
+use kelpie_models::venue::Venue;
+use serde::Serialize;
+use std::collections::HashMap;
+use yew::prelude::*;
+
+#[derive(Serialize)]
+struct GameResult {
+    home_team_score: Option<i32>,
+    away_team_score: Option<i32>,
+}
+
+/// The scores as typed, keyed by game id.
+type Entries = HashMap<i32, (String, String)>;
+
+fn score_text(score: Option<i32>) -> String {
+    score.map(|s| s.to_string()).unwrap_or_default()
+}
+
+/// Pick a round and enter the scores of its games.
+#[function_component(ResultsView)]
+pub(crate) fn results_view() -> Html {
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+
+    let rounds = use_state(|| Option::<Vec<Round>>::None);
+    let selected_round = use_state(|| None::<i32>);
+
+    // Start on the current round when it is in this season
+    {
+        let rounds = rounds.clone();
+        let selected_round = selected_round.clone();
+        use_effect_with(season_id, move |season_id| {
+            selected_round.set(None);
+            let season_id = *season_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                let list = match Request::get("api/rounds")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await {
+                    Ok(response) => response.json::<Vec<Round>>().await.unwrap_or_default(),
+                    Err(_) => vec![],
+                };
+                let current = match Request::get("/api/rounds/current").send().await {
+                    Ok(resp) => resp.json::<Round>().await.ok().filter(|r| Some(r.season_id) == season_id),
+                    Err(_) => None,
+                };
+                selected_round.set(current.or_else(|| list.last().cloned()).and_then(|r| r.round_id));
+                rounds.set(Some(list));
+            });
+            || ()
+        });
+    }
+
+    let on_round_select = {
+        let selected_round = selected_round.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                selected_round.set(select.value().parse::<i32>().ok());
+            }
+        })
+    };
+
+    html! {
+        <div class="content">
+            <div style="display: flex; flex-direction:row; padding: 10px; border-bottom: 1px solid #ccc;">
+                <h3 style="padding-right: 3rem;">{ "Enter results" }</h3>
+                {
+                    match &*rounds {
+                        None => html! { <span>{ "Loading..." }</span> },
+                        Some(list) if list.is_empty() => html! { <span>{ "No rounds found" }</span> },
+                        Some(list) => html! {
+                            <select onchange={on_round_select} style="width: 15rem;">
+                                { for list.iter().map(|round| html! {
+                                    <option value={round.round_id.unwrap_or(0).to_string()} selected={round.round_id == *selected_round}>
+                                        { format!("Round {}", round.round_number) }
+                                    </option>
+                                })}
+                            </select>
+                        }
+                    }
+                }
+            </div>
+            if let Some(round_id) = *selected_round {
+                <div style="padding: 1em;">
+                    <RoundResults round_id={round_id} />
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct RoundResultsProps {
+    pub(crate) round_id: i32,
+}
+
+#[function_component(RoundResults)]
+fn round_results(props: &RoundResultsProps) -> Html {
+    let games = use_state(Vec::<Game>::new);
+    let teams = use_state(Vec::<Team>::new);
+    let venues = use_state(Vec::<Venue>::new);
+    let entries = use_state(Entries::new);
+    let saved = use_state(|| None::<i32>);
+    let error_msg = use_state(|| None::<String>);
+
+    {
+        let games = games.clone();
+        let teams = teams.clone();
+        let venues = venues.clone();
+        let entries = entries.clone();
+        let error_msg = error_msg.clone();
+        use_effect_with(props.round_id, move |&round_id| {
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let round = match Request::get(&format!("api/rounds/{}", round_id)).send().await {
+                    Ok(resp) => resp.json::<RoundWithGames>().await.ok(),
+                    Err(e) => {
+                        error_msg.set(Some(format!("Error loading round: {}", e)));
+                        None
+                    }
+                };
+                let list = match Request::get("/api/teams").send().await {
+                    Ok(resp) => resp.json::<Vec<Team>>().await.unwrap_or_default(),
+                    Err(_) => vec![],
+                };
+                teams.set(list);
+                let list = match Request::get("/api/venues").send().await {
+                    Ok(resp) => resp.json::<Vec<Venue>>().await.unwrap_or_default(),
+                    Err(_) => vec![],
+                };
+                venues.set(list);
+                let mut round_games = round.map(|r| r.games).unwrap_or_default();
+                round_games.sort_by_key(|g| (g.kick_off, g.game_id));
+                entries.set(round_games.iter()
+                    .filter_map(|g| g.game_id.map(|id| (id, (score_text(g.home_team_score), score_text(g.away_team_score)))))
+                    .collect());
+                games.set(round_games);
+            });
+            || ()
+        });
+    }
+
+    let on_score = {
+        let entries = entries.clone();
+        move |game_id: i32, home: bool| {
+            let entries = entries.clone();
+            Callback::from(move |e: InputEvent| {
+                let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                let mut updated = (*entries).clone();
+                let entry = updated.entry(game_id).or_default();
+                if home { entry.0 = value } else { entry.1 = value }
+                entries.set(updated);
+            })
+        }
+    };
+
+    let on_save = {
+        let entries = entries.clone();
+        let games = games.clone();
+        let saved = saved.clone();
+        let error_msg = error_msg.clone();
+        move |game_id: i32| {
+            let entries = entries.clone();
+            let games = games.clone();
+            let saved = saved.clone();
+            let error_msg = error_msg.clone();
+            Callback::from(move |_| {
+                error_msg.set(None);
+                let (home, away) = entries.get(&game_id).cloned().unwrap_or_default();
+                let parse = |s: &str| s.trim().parse::<i32>().ok();
+                let result = GameResult { home_team_score: parse(&home), away_team_score: parse(&away) };
+                let games = games.clone();
+                let saved = saved.clone();
+                let error_msg = error_msg.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("/api/games/{}/result", game_id);
+                    match Request::patch(&url).json(&result).unwrap().send().await {
+                        Ok(resp) if resp.ok() => {
+                            if let Ok(updated) = resp.json::<Game>().await {
+                                let list = games.iter()
+                                    .map(|g| if g.game_id == updated.game_id { updated.clone() } else { g.clone() })
+                                    .collect();
+                                games.set(list);
+                            }
+                            saved.set(Some(game_id));
+                            let saved = saved.clone();
+                            gloo_timers::callback::Timeout::new(2000, move || saved.set(None)).forget();
+                        }
+                        Ok(resp) => {
+                            let text = resp.text().await.unwrap_or_default();
+                            error_msg.set(Some(format!("Failed to save result: {}", text)));
+                        }
+                        Err(e) => error_msg.set(Some(format!("Failed to save result: {}", e))),
+                    }
+                });
+            })
+        }
+    };
+
+    let team_name = |id: i32| teams.iter().find(|t| t.id == Some(id)).map(|t| t.name.clone()).unwrap_or_default();
+    let venue_name = |id: Option<i32>| venues.iter().find(|v| id.is_some() && v.id == id).map(|v| v.name.clone()).unwrap_or_default();
+
+    html! {
+        <div>
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            if games.is_empty() {
+                <p>{ "No games found for this round." }</p>
+            } else {
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{ "Kick-off" }</th>
+                            <th>{ "Venue" }</th>
+                            <th>{ "Home" }</th>
+                            <th>{ "Score" }</th>
+                            <th>{ "Away" }</th>
+                            <th>{ "Score" }</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for games.iter().filter_map(|game| game.game_id.map(|id| (id, game))).map(|(game_id, game)| {
+                            let (home, away) = entries.get(&game_id).cloned().unwrap_or_default();
+                            html! {
+                                <tr key={game_id}>
+                                    <td>{ game.kick_off.format("%a %d %b %H:%M").to_string() }</td>
+                                    <td>{ venue_name(game.venue_id) }</td>
+                                    <td>{ team_name(game.home_team_id) }</td>
+                                    <td style="width: 5rem;">
+                                        <input type="number" min="0" value={home} oninput={on_score(game_id, true)} />
+                                    </td>
+                                    <td>{ team_name(game.away_team_id) }</td>
+                                    <td style="width: 5rem;">
+                                        <input type="number" min="0" value={away} oninput={on_score(game_id, false)} />
+                                    </td>
+                                    <td class="actions">
+                                        <IconButton label="Save" onclick={on_save(game_id)}>{ save_icon() }</IconButton>
+                                        if *saved == Some(game_id) {
+                                            <span style="color: #388e3c; font-weight: bold;">{ "Saved!" }</span>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
@@ -22,12 +22,14 @@
  *
  */
 
-use crate::components::icons::{delete_icon, edit_icon, rounds_icon};
+use crate::components::icons::{delete_icon, edit_icon, print_icon, rounds_icon};
 use crate::{View, ViewContext};
 use gloo_net::http::Request;
 use kelpie_models::round::Round;
 use yew::prelude::*;
 use crate::components::buttons::IconButton;
+use crate::components::import::ImportPanel;
+use crate::components::reports::download_url;
 
 #[function_component(RoundList)]
 pub fn round_list() -> Html {
@@ -35,16 +37,23 @@ pub fn round_list() -> Html {
 
     let error_msg = use_state(|| None::<String>);
     let rounds = use_state(|| vec![]);
+    // Bumped to reload the rounds after an import
+    let reload = use_state(|| 0u32);
 
     // Load rounds
     {
         let rounds = rounds.clone();
         let error_msg = error_msg.clone();
-        use_effect_with((), move |_| {
+        use_effect_with((*view_context.season_id, *reload), move |(season_id, _)| {
             // Clear error on load
             error_msg.set(None);
+            let season_id = *season_id;
             wasm_bindgen_futures::spawn_local(async move {
-                match Request::get("/api/rounds").send().await {
+                match Request::get("/api/rounds")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await
+                {
                     Ok(resp) => {
                         if resp.ok() {
                             match resp.json::<Vec<Round>>().await {
@@ -118,6 +127,42 @@ pub fn round_list() -> Html {
         Callback::from(move |_| view.set(View::RoundEdit{round_id: None}))
     };
 
+    // Draw up the whole season when it has no rounds yet
+    let double = use_state(|| false);
+    let generate_fixture = {
+        let double = double.clone();
+        let reload = reload.clone();
+        let error_msg = error_msg.clone();
+        let season_id = *view_context.season_id;
+        Callback::from(move |_| {
+            let Some(season_id) = season_id else { return };
+            let options = serde_json::json!({ "double": *double });
+            let reload = reload.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("/api/seasons/{}/fixture", season_id);
+                match Request::post(&url).json(&options).unwrap().send().await {
+                    Ok(resp) if resp.ok() => reload.set(*reload + 1),
+                    Ok(resp) => {
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Unable to draw the season: {}", text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Unable to draw the season: {}", e))),
+                }
+            });
+        })
+    };
+    let toggle_double = {
+        let double = double.clone();
+        Callback::from(move |_| double.set(!*double))
+    };
+
+    let on_import = {
+        let reload = reload.clone();
+        Callback::from(move |_| reload.set(*reload + 1))
+    };
+
     html! {
         <div class="content">
             if let Some(msg) = &*error_msg {
@@ -149,6 +194,12 @@ pub fn round_list() -> Html {
                                 }
                             })
                         };
+                        // A tipping sheet to print for anyone tipping on paper
+                        let print_sheet = Callback::from(move |_| {
+                            if let Some(id) = round.round_id {
+                                download_url(&format!("/reports/pdf/tipping_sheet/{}", id));
+                            }
+                        });
                         let do_edit = {
                             let edit_round = edit_round.clone();
                             // Use Callback::from to create a callback that captures the round ID
@@ -169,6 +220,9 @@ pub fn round_list() -> Html {
                                             <IconButton onclick={do_edit}>
                                                 { edit_icon() }
                                             </IconButton>
+                                            <IconButton onclick={print_sheet}>
+                                                { print_icon() }
+                                            </IconButton>
                                             <IconButton onclick={delete}>
                                                 { delete_icon() }
                                             </IconButton>
@@ -184,7 +238,19 @@ pub fn round_list() -> Html {
                 <IconButton label="Add" onclick={add_round}>
                     { rounds_icon() }
                 </IconButton>
+                if rounds.is_empty() && view_context.season_id.is_some() {
+                    <IconButton label="Draw Season" onclick={generate_fixture}>
+                        { rounds_icon() }
+                    </IconButton>
+                    <label>
+                        <input type="checkbox" checked={*double} onclick={toggle_double} />
+                        { " Home and away" }
+                    </label>
+                }
             </div>
+            if let Some(season_id) = *view_context.season_id {
+                <ImportPanel season_id={season_id} on_import={on_import} />
+            }
         </div>
     }
 }
\ No newline at end of file
@@ -0,0 +1,231 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::{add_icon, delete_icon, save_icon};
+use gloo_net::http::Request;
+use kelpie_models::scoring::{MarginPoints, ScoringRules, Untipped};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ScoringRulesProps {
+    pub season_id: i32,
+}
+
+fn input_value(e: &Event) -> String {
+    e.target_unchecked_into::<web_sys::HtmlInputElement>().value()
+}
+
+/// Edit how tips are scored for a season.
+#[function_component(ScoringRulesEditor)]
+pub fn scoring_rules_editor(props: &ScoringRulesProps) -> Html {
+    let rules = use_state(ScoringRules::default);
+    let save_status = use_state(|| None::<String>);
+    let error_msg = use_state(|| None::<String>);
+
+    {
+        let rules = rules.clone();
+        let error_msg = error_msg.clone();
+        use_effect_with(props.season_id, move |season_id| {
+            let url = format!("/api/seasons/{}/scoring", season_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get(&url).send().await {
+                    Ok(resp) => rules.set(resp.json::<ScoringRules>().await.unwrap_or_default()),
+                    Err(e) => error_msg.set(Some(format!("Error loading scoring rules: {}", e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    // Apply a change to the rules being edited
+    let update = {
+        let rules = rules.clone();
+        move |f: fn(&mut ScoringRules, String)| {
+            let rules = rules.clone();
+            Callback::from(move |e: Event| {
+                let mut updated = (*rules).clone();
+                f(&mut updated, input_value(&e));
+                rules.set(updated);
+            })
+        }
+    };
+
+    let update_margin = {
+        let rules = rules.clone();
+        move |idx: usize, f: fn(&mut MarginPoints, String)| {
+            let rules = rules.clone();
+            Callback::from(move |e: Event| {
+                let mut updated = (*rules).clone();
+                if let Some(m) = updated.margin_points.get_mut(idx) {
+                    f(m, input_value(&e));
+                }
+                rules.set(updated);
+            })
+        }
+    };
+
+    let add_margin = {
+        let rules = rules.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut updated = (*rules).clone();
+            updated.margin_points.push(MarginPoints { min_margin: 0, points: 0 });
+            rules.set(updated);
+        })
+    };
+
+    let save = {
+        let rules = rules.clone();
+        let save_status = save_status.clone();
+        let error_msg = error_msg.clone();
+        let url = format!("/api/seasons/{}/scoring", props.season_id);
+        Callback::from(move |_: MouseEvent| {
+            let rules = rules.clone();
+            let save_status = save_status.clone();
+            let error_msg = error_msg.clone();
+            let url = url.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::put(&url).json(&*rules).unwrap().send().await {
+                    Ok(resp) if resp.ok() => {
+                        save_status.set(Some("Saved!".to_string()));
+                        let save_status = save_status.clone();
+                        gloo_timers::callback::Timeout::new(2000, move || save_status.set(None)).forget();
+                    }
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Save failed ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error saving scoring rules: {}", e))),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div style="padding: 0 1rem;">
+            <div style="display: flex; align-items: center; gap: 1rem;">
+                <h3>{ "Scoring" }</h3>
+                <IconButton label="Save" onclick={save}>{ save_icon() }</IconButton>
+                if let Some(msg) = &*save_status {
+                    <div style="color: #388e3c; font-weight: bold;">{ msg }</div>
+                }
+            </div>
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <table style="width: auto;">
+                <tbody>
+                    <tr>
+                        <td>{ "Points for a correct tip" }</td>
+                        <td>
+                            <input type="number" min="0" value={rules.correct_points.to_string()}
+                                onchange={update(|r, v| r.correct_points = v.parse().unwrap_or(0))} />
+                        </td>
+                    </tr>
+                    <tr>
+                        <td>{ "Points for a draw (blank for the same as a correct tip)" }</td>
+                        <td>
+                            <input type="number" min="0" value={rules.draw_points.map(|p| p.to_string()).unwrap_or_default()}
+                                onchange={update(|r, v| r.draw_points = v.parse().ok())} />
+                        </td>
+                    </tr>
+                    <tr>
+                        <td>{ "Untipped games" }</td>
+                        <td>
+                            <select onchange={{
+                                let rules = rules.clone();
+                                Callback::from(move |e: Event| {
+                                    let value = e.target_unchecked_into::<web_sys::HtmlSelectElement>().value();
+                                    let mut updated = (*rules).clone();
+                                    updated.untipped = match value.as_str() {
+                                        "miss" => Untipped::Miss,
+                                        "home_team" => Untipped::HomeTeam,
+                                        _ => Untipped::Ignore,
+                                    };
+                                    rules.set(updated);
+                                })
+                            }}>
+                                <option value="ignore" selected={rules.untipped == Untipped::Ignore}>{ "Ignored" }</option>
+                                <option value="miss" selected={rules.untipped == Untipped::Miss}>{ "Count as a miss" }</option>
+                                <option value="home_team" selected={rules.untipped == Untipped::HomeTeam}>{ "Tip the home team" }</option>
+                            </select>
+                        </td>
+                    </tr>
+                    <tr>
+                        <td>{ "Bonus points for a perfect round" }</td>
+                        <td>
+                            <input type="checkbox" checked={rules.perfect_round_bonus}
+                                onchange={{
+                                    let rules = rules.clone();
+                                    Callback::from(move |_: Event| {
+                                        let mut updated = (*rules).clone();
+                                        updated.perfect_round_bonus = !updated.perfect_round_bonus;
+                                        rules.set(updated);
+                                    })
+                                }} />
+                        </td>
+                    </tr>
+                    <tr>
+                        <td>{ "Extra points for tipping an upset" }</td>
+                        <td>
+                            <input type="number" min="0" value={rules.upset_points.to_string()}
+                                onchange={update(|r, v| r.upset_points = v.parse().unwrap_or(0))} />
+                        </td>
+                    </tr>
+                    { for rules.margin_points.iter().enumerate().map(|(idx, m)| {
+                        let remove = {
+                            let rules = rules.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                let mut updated = (*rules).clone();
+                                updated.margin_points.remove(idx);
+                                rules.set(updated);
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td>
+                                    { "Extra points for a win by at least " }
+                                    <input type="number" min="0" style="width: 5rem;" value={m.min_margin.to_string()}
+                                        onchange={update_margin(idx, |m, v| m.min_margin = v.parse().unwrap_or(0))} />
+                                </td>
+                                <td>
+                                    <div class="button-row">
+                                        <input type="number" min="0" value={m.points.to_string()}
+                                            onchange={update_margin(idx, |m, v| m.points = v.parse().unwrap_or(0))} />
+                                        <IconButton onclick={remove}>{ delete_icon() }</IconButton>
+                                    </div>
+                                </td>
+                            </tr>
+                        }
+                    })}
+                    <tr>
+                        <td>{ "Add margin points" }</td>
+                        <td><IconButton onclick={add_margin}>{ add_icon() }</IconButton></td>
+                    </tr>
+                </tbody>
+            </table>
+        </div>
+    }
+}
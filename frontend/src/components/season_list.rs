@@ -0,0 +1,453 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+
+use crate::components::buttons::IconButton;
+use crate::components::icons::{add_icon, cancel_icon, delete_icon, edit_icon, save_icon};
+use crate::components::scoring_rules::ScoringRulesEditor;
+use crate::ViewContext;
+use chrono::NaiveDate;
+use gloo_net::http::Request;
+use kelpie_models::season::Season;
+use kelpie_models::team::Team;
+use kelpie_models::tipper::Tipper;
+use std::collections::HashSet;
+use yew::prelude::*;
+
+fn parse_date(e: InputEvent) -> Option<NaiveDate> {
+    let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+    NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").ok()
+}
+
+#[function_component(SeasonList)]
+pub fn season_list() -> Html {
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let seasons = view_context.seasons.clone();
+    let season_id = view_context.season_id.clone();
+
+    let new_season = use_state(Season::default);
+    let editing = use_state(|| None::<Season>);
+
+    // Teams and tippers, and which of them take part in the selected season
+    let teams = use_state(Vec::<Team>::new);
+    let tippers = use_state(Vec::<Tipper>::new);
+    let season_teams = use_state(HashSet::<i32>::new);
+    let season_tippers = use_state(HashSet::<i32>::new);
+    let save_status = use_state(|| None::<String>);
+
+    let error_msg = use_state(|| None::<String>);
+
+    // Load all teams and tippers on mount
+    {
+        let teams = teams.clone();
+        let tippers = tippers.clone();
+        let error_msg = error_msg.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get("/api/teams").send().await {
+                    Ok(resp) => teams.set(resp.json::<Vec<Team>>().await.unwrap_or_default()),
+                    Err(e) => error_msg.set(Some(format!("Error loading teams: {}", e))),
+                }
+                match Request::get("/api/tippers").send().await {
+                    Ok(resp) => tippers.set(resp.json::<Vec<Tipper>>().await.unwrap_or_default()),
+                    Err(e) => error_msg.set(Some(format!("Error loading tippers: {}", e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    // Load the enrolments whenever the selected season changes
+    {
+        let season_teams = season_teams.clone();
+        let season_tippers = season_tippers.clone();
+        let error_msg = error_msg.clone();
+        use_effect_with(*season_id, move |season_id| {
+            let season_id = *season_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(id) = season_id {
+                    match Request::get(&format!("/api/seasons/{}/teams", id)).send().await {
+                        Ok(resp) => season_teams.set(resp.json::<Vec<i32>>().await.unwrap_or_default().into_iter().collect()),
+                        Err(e) => error_msg.set(Some(format!("Error loading season teams: {}", e))),
+                    }
+                    match Request::get(&format!("/api/seasons/{}/tippers", id)).send().await {
+                        Ok(resp) => season_tippers.set(resp.json::<Vec<i32>>().await.unwrap_or_default().into_iter().collect()),
+                        Err(e) => error_msg.set(Some(format!("Error loading season tippers: {}", e))),
+                    }
+                } else {
+                    season_teams.set(HashSet::new());
+                    season_tippers.set(HashSet::new());
+                }
+            });
+            || ()
+        });
+    }
+
+    let add_season = {
+        let new_season = new_season.clone();
+        let seasons = seasons.clone();
+        let season_id = season_id.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |_: MouseEvent| {
+            let new_season = new_season.clone();
+            let seasons = seasons.clone();
+            let season_id = season_id.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::post("/api/seasons").json(&*new_season).unwrap().send().await {
+                    Ok(resp) if resp.ok() => match resp.json::<Season>().await {
+                        Ok(added) => {
+                            let mut list = (*seasons).clone();
+                            season_id.set(added.id);
+                            list.push(added);
+                            list.sort_by_key(|s| s.start_date);
+                            seasons.set(list);
+                            new_season.set(Season::default());
+                        }
+                        Err(e) => error_msg.set(Some(format!("Failed to parse season: {}", e))),
+                    },
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Add failed ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error adding season: {}", e))),
+                }
+            });
+        })
+    };
+
+    let save_edit = {
+        let editing = editing.clone();
+        let seasons = seasons.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |_: MouseEvent| {
+            let editing = editing.clone();
+            let seasons = seasons.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            if let Some(season) = (*editing).clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Request::put("/api/seasons").json(&season).unwrap().send().await {
+                        Ok(resp) if resp.ok() => match resp.json::<Season>().await {
+                            Ok(updated) => {
+                                let list = seasons.iter()
+                                    .map(|s| if s.id == updated.id { updated.clone() } else { s.clone() })
+                                    .collect();
+                                seasons.set(list);
+                                editing.set(None);
+                            }
+                            Err(e) => error_msg.set(Some(format!("Failed to parse season: {}", e))),
+                        },
+                        Ok(resp) => {
+                            let status = resp.status();
+                            let text = resp.text().await.unwrap_or_default();
+                            error_msg.set(Some(format!("Update failed ({}): {}", status, text)));
+                        }
+                        Err(e) => error_msg.set(Some(format!("Error updating season: {}", e))),
+                    }
+                });
+            }
+        })
+    };
+
+    let delete_season = {
+        let seasons = seasons.clone();
+        let season_id = season_id.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |id: i32| {
+            let seasons = seasons.clone();
+            let season_id = season_id.clone();
+            let error_msg = error_msg.clone();
+            if web_sys::window()
+                .and_then(|w| w.confirm_with_message("Are you sure you want to delete this season?").ok())
+                .unwrap_or(false)
+            {
+                error_msg.set(None);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Request::delete(&format!("/api/seasons/{}", id)).send().await {
+                        Ok(resp) if resp.ok() => {
+                            let list: Vec<Season> = seasons.iter().filter(|s| s.id != Some(id)).cloned().collect();
+                            if *season_id == Some(id) {
+                                season_id.set(list.last().and_then(|s| s.id));
+                            }
+                            seasons.set(list);
+                        }
+                        Ok(resp) => {
+                            let status = resp.status();
+                            let text = resp.text().await.unwrap_or_default();
+                            error_msg.set(Some(format!("Delete failed ({}): {}", status, text)));
+                        }
+                        Err(e) => error_msg.set(Some(format!("Error deleting season: {}", e))),
+                    }
+                });
+            }
+        })
+    };
+
+    let save_enrolment = {
+        let season_id = season_id.clone();
+        let season_teams = season_teams.clone();
+        let season_tippers = season_tippers.clone();
+        let save_status = save_status.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(id) = *season_id else { return };
+            let team_ids: Vec<i32> = season_teams.iter().copied().collect();
+            let tipper_ids: Vec<i32> = season_tippers.iter().copied().collect();
+            let save_status = save_status.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let teams_resp = Request::put(&format!("/api/seasons/{}/teams", id)).json(&team_ids).unwrap().send().await;
+                let tippers_resp = Request::put(&format!("/api/seasons/{}/tippers", id)).json(&tipper_ids).unwrap().send().await;
+                match (teams_resp, tippers_resp) {
+                    (Ok(a), Ok(b)) if a.ok() && b.ok() => {
+                        save_status.set(Some("Saved!".to_string()));
+                        let save_status = save_status.clone();
+                        gloo_timers::callback::Timeout::new(2000, move || save_status.set(None)).forget();
+                    }
+                    (Ok(a), Ok(b)) => {
+                        let failed = if a.ok() { b } else { a };
+                        let status = failed.status();
+                        let text = failed.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Save failed ({}): {}", status, text)));
+                    }
+                    (Err(e), _) | (_, Err(e)) => error_msg.set(Some(format!("Error saving season: {}", e))),
+                }
+            });
+        })
+    };
+
+    let toggle = |set: &UseStateHandle<HashSet<i32>>, id: i32| {
+        let set = set.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*set).clone();
+            if !updated.remove(&id) {
+                updated.insert(id);
+            }
+            set.set(updated);
+        })
+    };
+
+    let selected_name = seasons.iter()
+        .find(|s| s.id == *season_id)
+        .map(|s| s.name.clone());
+
+    html! {
+        <div class="content">
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <h2>{ "Seasons" }</h2>
+            <div class="scrollable-table" style="border-right: 1px solid #ccc; flex: 0 0 auto;">
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ "Name" }</th>
+                        <th>{ "From" }</th>
+                        <th>{ "To" }</th>
+                        <th>{ "Actions" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    // Add season form as the first row
+                    <tr>
+                        <td>
+                            <input type="text" placeholder="Name" value={new_season.name.clone()}
+                                oninput={{
+                                    let new_season = new_season.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        let mut s = (*new_season).clone();
+                                        s.name = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                                        new_season.set(s);
+                                    })
+                                }}
+                            />
+                        </td>
+                        <td>
+                            <input type="date" value={new_season.start_date.format("%Y-%m-%d").to_string()}
+                                oninput={{
+                                    let new_season = new_season.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(date) = parse_date(e) {
+                                            let mut s = (*new_season).clone();
+                                            s.start_date = date;
+                                            new_season.set(s);
+                                        }
+                                    })
+                                }}
+                            />
+                        </td>
+                        <td>
+                            <input type="date" value={new_season.end_date.format("%Y-%m-%d").to_string()}
+                                oninput={{
+                                    let new_season = new_season.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(date) = parse_date(e) {
+                                            let mut s = (*new_season).clone();
+                                            s.end_date = date;
+                                            new_season.set(s);
+                                        }
+                                    })
+                                }}
+                            />
+                        </td>
+                        <td class="actions">
+                            <IconButton onclick={add_season}>
+                                { add_icon() }
+                            </IconButton>
+                        </td>
+                    </tr>
+                    { for seasons.iter().map(|season| {
+                        match &*editing {
+                            Some(edit) if edit.id == season.id => html! {
+                                <tr key={season.id.unwrap_or(-1)}>
+                                    <td>
+                                        <input value={edit.name.clone()}
+                                            oninput={{
+                                                let editing = editing.clone();
+                                                Callback::from(move |e: InputEvent| {
+                                                    if let Some(mut s) = (*editing).clone() {
+                                                        s.name = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                                                        editing.set(Some(s));
+                                                    }
+                                                })
+                                            }}
+                                        />
+                                    </td>
+                                    <td>
+                                        <input type="date" value={edit.start_date.format("%Y-%m-%d").to_string()}
+                                            oninput={{
+                                                let editing = editing.clone();
+                                                Callback::from(move |e: InputEvent| {
+                                                    if let (Some(mut s), Some(date)) = ((*editing).clone(), parse_date(e)) {
+                                                        s.start_date = date;
+                                                        editing.set(Some(s));
+                                                    }
+                                                })
+                                            }}
+                                        />
+                                    </td>
+                                    <td>
+                                        <input type="date" value={edit.end_date.format("%Y-%m-%d").to_string()}
+                                            oninput={{
+                                                let editing = editing.clone();
+                                                Callback::from(move |e: InputEvent| {
+                                                    if let (Some(mut s), Some(date)) = ((*editing).clone(), parse_date(e)) {
+                                                        s.end_date = date;
+                                                        editing.set(Some(s));
+                                                    }
+                                                })
+                                            }}
+                                        />
+                                    </td>
+                                    <td class="actions">
+                                        <div class="button-row">
+                                            <IconButton onclick={save_edit.clone()}>
+                                                { save_icon() }
+                                            </IconButton>
+                                            <IconButton onclick={{
+                                                let editing = editing.clone();
+                                                Callback::from(move |_| editing.set(None))
+                                            }}>
+                                                { cancel_icon() }
+                                            </IconButton>
+                                        </div>
+                                    </td>
+                                </tr>
+                            },
+                            _ => {
+                                let start_edit = {
+                                    let editing = editing.clone();
+                                    let season = season.clone();
+                                    Callback::from(move |_| editing.set(Some(season.clone())))
+                                };
+                                let delete = {
+                                    let delete_season = delete_season.clone();
+                                    let id = season.id.unwrap_or(-1);
+                                    Callback::from(move |_| delete_season.emit(id))
+                                };
+                                html! {
+                                    <tr key={season.id.unwrap_or(-1)}>
+                                        <td>{ &season.name }</td>
+                                        <td>{ season.start_date.format("%Y-%m-%d").to_string() }</td>
+                                        <td>{ season.end_date.format("%Y-%m-%d").to_string() }</td>
+                                        <td class="actions">
+                                            <div class="button-row">
+                                                <IconButton onclick={start_edit}>
+                                                    { edit_icon() }
+                                                </IconButton>
+                                                <IconButton onclick={delete}>
+                                                    { delete_icon() }
+                                                </IconButton>
+                                            </div>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        }
+                    })}
+                </tbody>
+            </table>
+            </div>
+            if let Some(name) = selected_name {
+                <div style="display: flex; align-items: center; gap: 1rem;">
+                    <h3>{ format!("Taking part in {}", name) }</h3>
+                    <IconButton label="Save" onclick={save_enrolment}>{ save_icon() }</IconButton>
+                    if let Some(msg) = &*save_status {
+                        <div style="color: #388e3c; font-weight: bold;">{ msg }</div>
+                    }
+                </div>
+                <div style="display: flex; gap: 2rem; padding: 0 1rem;">
+                    <div>
+                        <h4>{ "Teams" }</h4>
+                        { for teams.iter().filter_map(|team| team.id.map(|id| (id, team))).map(|(id, team)| html! {
+                            <label style="display: block;">
+                                <input type="checkbox" style="width: auto; margin: 0 0.5rem 0 0;"
+                                    checked={season_teams.contains(&id)}
+                                    onchange={toggle(&season_teams, id)} />
+                                { &team.name }
+                            </label>
+                        })}
+                    </div>
+                    <div>
+                        <h4>{ "Tippers" }</h4>
+                        { for tippers.iter().filter_map(|tipper| tipper.id.map(|id| (id, tipper))).map(|(id, tipper)| html! {
+                            <label style="display: block;">
+                                <input type="checkbox" style="width: auto; margin: 0 0.5rem 0 0;"
+                                    checked={season_tippers.contains(&id)}
+                                    onchange={toggle(&season_tippers, id)} />
+                                { &tipper.name }
+                            </label>
+                        })}
+                    </div>
+                </div>
+            }
+            if let Some(id) = *season_id {
+                <ScoringRulesEditor season_id={id} />
+            }
+        </div>
+    }
+}
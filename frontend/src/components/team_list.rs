@@ -26,6 +26,7 @@ use crate::components::buttons::IconButton;
 use crate::components::icons::{add_icon, cancel_icon, delete_icon, edit_icon, save_icon};
 use gloo_net::http::Request;
 use kelpie_models::team::Team;
+use kelpie_models::venue::Venue;
 use log::warn;
 use serde_json::json;
 use yew::prelude::*;
@@ -40,11 +41,14 @@ pub fn team_list(props: &TeamListProps) -> Html {
     let teams = use_state(|| vec![]);
     let name_input = use_state(|| String::new());
     let nickname_input = use_state(|| String::new());
+    let venue_input = use_state(|| None::<i32>);
+    let venues = use_state(|| Vec::<Venue>::new());
 
     // New state for editing
     let editing_id = use_state(|| None as Option<i32>);
     let edit_name = use_state(|| String::new());
     let edit_nickname = use_state(|| String::new());
+    let edit_venue = use_state(|| None::<i32>);
 
     let error_msg = use_state(|| None::<String>);
 
@@ -83,16 +87,31 @@ pub fn team_list(props: &TeamListProps) -> Html {
         });
     }
 
+    // Load the grounds teams can call home
+    {
+        let venues = venues.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(resp) = Request::get("/api/venues").send().await {
+                    venues.set(resp.json::<Vec<Venue>>().await.unwrap_or_default());
+                }
+            });
+            || ()
+        });
+    }
+
     // Add team
     let add_team = {
         let name_input = name_input.clone();
         let nickname_input = nickname_input.clone();
+        let venue_input = venue_input.clone();
         let teams = teams.clone();
         let error_msg = error_msg.clone();
 
         Callback::from(move |_e: MouseEvent| {
             let name = name_input.clone();
             let nickname = nickname_input.clone();
+            let venue = venue_input.clone();
             let teams = teams.clone();
             let error_msg = error_msg.clone();
 
@@ -103,6 +122,7 @@ pub fn team_list(props: &TeamListProps) -> Html {
                 let payload = json!({
                     "name": (*name).clone(),
                     "nickname": (*nickname).clone(),
+                    "home_venue_id": *venue,
                 });
 
                 match Request::post("/api/teams")
@@ -120,6 +140,7 @@ pub fn team_list(props: &TeamListProps) -> Html {
                                             teams.set(new_list);
                                             name.set(String::new());
                                             nickname.set(String::new());
+                                            venue.set(None);
                                             error_msg.set(None); // Clear error on success
                                         }
                                         Err(e) => {
@@ -150,10 +171,12 @@ pub fn team_list(props: &TeamListProps) -> Html {
         let editing_id = editing_id.clone();
         let edit_name = edit_name.clone();
         let edit_nickname = edit_nickname.clone();
+        let edit_venue = edit_venue.clone();
         Callback::from(move |team: Team| {
             editing_id.set(team.id);
             edit_name.set(team.name.clone());
             edit_nickname.set(team.nickname.clone());
+            edit_venue.set(team.home_venue_id);
         })
     };
 
@@ -168,12 +191,14 @@ pub fn team_list(props: &TeamListProps) -> Html {
         let editing_id = editing_id.clone();
         let edit_name = edit_name.clone();
         let edit_nickname = edit_nickname.clone();
+        let edit_venue = edit_venue.clone();
         let teams = teams.clone();
         let error_msg = error_msg.clone();
         Callback::from(move |_| {
             let id = editing_id.clone();
             let name = edit_name.clone();
             let nickname = edit_nickname.clone();
+            let venue = edit_venue.clone();
             let teams = teams.clone();
             let editing_id = editing_id.clone();
             let error_msg = error_msg.clone();
@@ -183,7 +208,7 @@ pub fn team_list(props: &TeamListProps) -> Html {
 
             wasm_bindgen_futures::spawn_local(async move {
                 if let Some(id) = *id {
-                    let payload = json!({ "id": id, "name": (*name).clone() , "nickname": (*nickname).clone() });
+                    let payload = json!({ "id": id, "name": (*name).clone() , "nickname": (*nickname).clone(), "home_venue_id": *venue });
                     let url = "/api/teams";
                     match Request::put(&url)
                         .header("Content-Type", "application/json")
@@ -271,6 +296,19 @@ pub fn team_list(props: &TeamListProps) -> Html {
     };
 
 
+    // A select of the venues, for a team's home ground
+    let venue_select = |selected: Option<i32>, state: UseStateHandle<Option<i32>>| html! {
+        <select onchange={Callback::from(move |e: Event| {
+            state.set(e.target_unchecked_into::<web_sys::HtmlSelectElement>().value().parse().ok());
+        })}>
+            <option value="" selected={selected.is_none()}>{ "None" }</option>
+            { for venues.iter().filter_map(|v| v.id.map(|id| (id, v))).map(|(id, venue)| html! {
+                <option value={id.to_string()} selected={selected == Some(id)}>{ &venue.name }</option>
+            })}
+        </select>
+    };
+    let venue_name = |id: Option<i32>| venues.iter().find(|v| id.is_some() && v.id == id).map(|v| v.name.clone()).unwrap_or_default();
+
     // Delete team
 html! {
         <div class="content">
@@ -284,6 +322,7 @@ html! {
                     <tr>
                         <th>{ "Name" }</th>
                         <th>{ "Nick Name" }</th>
+                        <th>{ "Home Ground" }</th>
                         <th>{ "Actions" }</th>
                     </tr>
                 </thead>
@@ -311,6 +350,7 @@ html! {
                                 }}
                             />
                         </td>
+                        <td>{ venue_select(*venue_input, venue_input.clone()) }</td>
                         <td class="actions">
                             <IconButton onclick={add_team}>
                                 { add_icon() }
@@ -344,6 +384,7 @@ html! {
                                             }}
                                         />
                                     </td>
+                                    <td>{ venue_select(*edit_venue, edit_venue.clone()) }</td>
                                     <td class="actions">
                                         <div class="button-row">
                                             <IconButton onclick={save_edit.clone()}>
@@ -376,6 +417,7 @@ html! {
                                 <tr key={team.id.unwrap_or(-1)}>
                                     <td>{ &team.name }</td>
                                     <td>{ &team.nickname }</td>
+                                    <td>{ venue_name(team.home_venue_id) }</td>
                                     <td class="actions">
                                         <div class="button-row">
                                             <IconButton onclick={start_edit}>
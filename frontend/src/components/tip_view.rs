@@ -23,16 +23,16 @@
  */
 
 use crate::components::tips::Tips;
+use crate::ViewContext;
 use gloo_net::http::Request;
 use kelpie_models::round::Round;
-use kelpie_models::tipper::Tipper;
 use yew::prelude::*;
 
 #[function_component(TipView)]
 pub fn tip_view() -> Html {
-    // State for tippers fetched from backend
-    let tippers = use_state(|| Option::<Vec<Tipper>>::None);
-    let selected_tipper = use_state(|| None::<usize>);
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let season_id = *view_context.season_id;
+    let tipper_id = view_context.tipper.as_ref().and_then(|t| t.id);
 
     // State for rounds and selected round
     let rounds = use_state(|| Option::<Vec<Round>>::None);
@@ -40,44 +40,21 @@ pub fn tip_view() -> Html {
 
     let error_msg = use_state(|| None::<String>);
 
-    // Fetch tippers from backend on mount
-    {
-        let tippers = tippers.clone();
-        let error_msg = error_msg.clone();
-        use_effect_with((), move |_| {
-            // Clear error on load
-            error_msg.set(None);
-            wasm_bindgen_futures::spawn_local(async move {
-                let resp = Request::get("api/tippers").send().await;
-                match resp {
-                    Ok(response) => {
-                        if let Ok(json) = response.json::<Vec<Tipper>>().await {
-                            tippers.set(Some(json));
-                            error_msg.set(None); // Clear error on success
-                        } else {
-                            tippers.set(Some(vec![]));
-                            error_msg.set(Some("Failed to parse tippers.".to_string()));
-                        }
-                    }
-                    Err(e) => {
-                        tippers.set(Some(vec![]));
-                        error_msg.set(Some(format!("Error loading tippers: {}", e)));
-                    }
-                }
-            });
-            || ()
-        });
-    }
-
-    // Fetch rounds from backend on mount
+    // Fetch the season's rounds, starting on the tipper's current round when it is in this season
     {
         let rounds = rounds.clone();
+        let selected_round = selected_round.clone();
         let error_msg = error_msg.clone();
-        use_effect_with((), move |_| {
+        use_effect_with(season_id, move |season_id| {
             // Clear error on load
             error_msg.set(None);
+            selected_round.set(None);
+            let season_id = *season_id;
             wasm_bindgen_futures::spawn_local(async move {
-                let resp = Request::get("api/rounds").send().await;
+                let resp = Request::get("api/rounds")
+                    .query(season_id.map(|id| ("season_id", id.to_string())))
+                    .send()
+                    .await;
                 match resp {
                     Ok(response) => {
                         if let Ok(json) = response.json::<Vec<Round>>().await {
@@ -93,6 +70,13 @@ pub fn tip_view() -> Html {
                         error_msg.set(Some(format!("Error loading rounds: {}", e)));
                     }
                 }
+                if let Ok(resp) = Request::get("/api/rounds/current").send().await {
+                    if let Ok(current) = resp.json::<Round>().await {
+                        if Some(current.season_id) == season_id {
+                            selected_round.set(Some(current.round_number));
+                        }
+                    }
+                }
             });
             || ()
         });
@@ -111,12 +95,6 @@ pub fn tip_view() -> Html {
         })
     };
 
-    // Define the tipper selection callback
-    let on_tipper_select = {
-        let selected_tipper = selected_tipper.clone();
-        Callback::from(move |idx: usize| selected_tipper.set(Some(idx)))
-    };
-
     html! {
         <div class="content">
             if let Some(msg) = &*error_msg {
@@ -144,75 +122,30 @@ pub fn tip_view() -> Html {
                     }
                 }
             </div>
-            <div style="display: flex; flex: 1; min-height: 0;">
-                // Sidebar for tipper selection
-                <div class="scrollable-list" style="border-right: 1px solid #ccc;">
-                    <ul>
+            <div style="flex: 1; display: flex; flex-direction: column;">
+                // Main body for tips recording
+                <div style="flex: 1; padding: 1em;">
                     {
-                        match &*tippers {
-                            None => html! { <li>{ "Loading..." }</li> },
-                            Some(list) if list.is_empty() => html! { <li>{ "No tippers found" }</li> },
-                            Some(list) => html! {
-                                <>
-                                    { for list.iter().enumerate().map(|(idx, tipper)| {
-                                    let is_selected = *selected_tipper == Some(idx);
-                                    let on_click = {
-                                        let on_tipper_select = on_tipper_select.clone();
-                                        Callback::from(move |_| on_tipper_select.emit(idx))
-                                    };
+                        if let (Some(tipper_id), Some(round_list)) = (tipper_id, &*rounds) {
+                            if let Some(selected_round_number) = *selected_round {
+                                // Find the round object by round_number
+                                if let Some(round) = round_list.iter().find(|r| r.round_number == selected_round_number) {
+                                    let round_id = round.round_id.unwrap_or(0);
                                     html! {
-                                        <li style={ if is_selected {
-                                            "font-weight: bold; background: #e0f7fa; border-radius: 4px;"
-                                        } else {
-                                            ""
-                                        } }>
-                                            <button onclick={on_click.clone()} class="key-button" style={ if is_selected {
-                                                "width: 90%; background: #e0f7fa; border: none; text-align: left; font-weight: bold;"
-                                            } else {
-                                                "width: 90%; background: none; border: none; text-align: left;"
-                                            }}>
-                                                { &tipper.name }
-                                            </button>
-                                        </li>
-                                    }
-                                })}
-                                </>
-                            }
-                        }
-                    }
-                    </ul>
-                </div>
-                <div style="flex: 1; display: flex; flex-direction: column;">
-                    // Main body for tips recording
-                    <div style="flex: 1; padding: 1em;">
-                        {
-                            if let (Some(tipper_list), Some(round_list)) = (&*tippers, &*rounds) {
-                                if !tipper_list.is_empty() && selected_tipper.unwrap_or(0) < tipper_list.len() {
-                                    if let Some(selected_round_number) = *selected_round {
-                                        let tipper = &tipper_list[selected_tipper.unwrap_or(0)];
-                                        // Find the round object by round_number
-                                        if let Some(round) = round_list.iter().find(|r| r.round_number == selected_round_number) {
-                                            let round_id = round.round_id.unwrap_or(0);
-                                            let tipper_id = tipper.id.unwrap_or(0);
-                                            html! {
-                                                <Tips tipper_id={tipper_id} round_id={round_id} />
-                                            }
-                                        } else {
-                                            html! { <p>{ "Selected round not found." }</p> }
-                                        }
-                                    } else {
-                                        html! { <p>{ "Please select a round." }</p> }
+                                        <Tips tipper_id={tipper_id} round_id={round_id} />
                                     }
                                 } else {
-                                    html! { <></> }
+                                    html! { <p>{ "Selected round not found." }</p> }
                                 }
                             } else {
-                                html! { <></> }
+                                html! { <p>{ "Please select a round." }</p> }
                             }
+                        } else {
+                            html! { <></> }
                         }
-                    </div>
+                    }
                 </div>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,205 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::{csv_icon, save_icon};
+use crate::components::import::RowError;
+use crate::components::reports::download_url;
+use gloo_net::http::Request;
+use serde::Deserialize;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct TipperImportReport {
+    applied: bool,
+    changes: Vec<TipperChange>,
+    errors: Vec<RowError>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct TipperChange {
+    row: usize,
+    action: String,
+    name: String,
+    email: String,
+}
+
+fn action_label(action: &str) -> &'static str {
+    match action {
+        "add_tipper" => "Add tipper",
+        "enrol" => "Add to season",
+        _ => "No change",
+    }
+}
+
+async fn send(season_id: Option<i32>, csv: &str, dry_run: bool) -> Result<TipperImportReport, String> {
+    let resp = Request::post("/api/tippers/import")
+        .query(season_id.map(|id| ("season_id", id.to_string())))
+        .query([("dry_run", dry_run.to_string())])
+        .header("Content-Type", "text/csv")
+        .body(csv.to_string())
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.ok() {
+        resp.json::<TipperImportReport>().await.map_err(|e| e.to_string())
+    } else {
+        Err(resp.text().await.unwrap_or_default())
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct TipperImportProps {
+    /// Imported tippers are enrolled in this season
+    pub(crate) season_id: Option<i32>,
+    /// Called once an import has been applied
+    pub(crate) on_import: Callback<()>,
+}
+
+/// Upload a CSV file of tippers, preview who it adds, then apply it. Also exports the tippers in the same format.
+#[function_component(TipperImportPanel)]
+pub(crate) fn tipper_import_panel(props: &TipperImportProps) -> Html {
+    let file = use_mut_ref(|| None::<String>);
+    let report = use_state(|| None::<TipperImportReport>);
+    let error_msg = use_state(|| None::<String>);
+
+    let on_file = {
+        let file = file.clone();
+        let report = report.clone();
+        let error_msg = error_msg.clone();
+        let season_id = props.season_id;
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            let Some(selected) = input.files().and_then(|files| files.get(0)) else { return };
+            let file = file.clone();
+            let report = report.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            report.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let text = match JsFuture::from(selected.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(_) => {
+                        error_msg.set(Some("Unable to read the file".to_string()));
+                        return;
+                    }
+                };
+                match send(season_id, &text, true).await {
+                    Ok(preview) => report.set(Some(preview)),
+                    Err(e) => error_msg.set(Some(format!("Import failed: {}", e))),
+                }
+                *file.borrow_mut() = Some(text);
+            });
+        })
+    };
+
+    let on_apply = {
+        let file = file.clone();
+        let report = report.clone();
+        let error_msg = error_msg.clone();
+        let on_import = props.on_import.clone();
+        let season_id = props.season_id;
+        Callback::from(move |_| {
+            let file = file.clone();
+            let report = report.clone();
+            let error_msg = error_msg.clone();
+            let on_import = on_import.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(text) = file.borrow().clone() else { return };
+                match send(season_id, &text, false).await {
+                    Ok(applied) => {
+                        report.set(Some(applied));
+                        on_import.emit(());
+                    }
+                    Err(e) => error_msg.set(Some(format!("Import failed: {}", e))),
+                }
+            });
+        })
+    };
+
+    let on_export = {
+        let season_id = props.season_id;
+        Callback::from(move |_| match season_id {
+            Some(id) => download_url(&format!("/api/tippers/export?season_id={}", id)),
+            None => download_url("/api/tippers/export"),
+        })
+    };
+
+    let can_apply = report.as_ref().is_some_and(|r| !r.applied && r.errors.is_empty()
+        && r.changes.iter().any(|c| c.action != "unchanged"));
+
+    html! {
+        <div class="card">
+            <h3>{ "Import tippers" }</h3>
+            <p>{
+                if props.season_id.is_some() {
+                    "CSV with the columns name, email. Everyone in the file is added to the current season."
+                } else {
+                    "CSV with the columns name, email."
+                }
+            }</p>
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <div style="display: flex; align-items: center; gap: 1rem;">
+                { csv_icon() }
+                <input type="file" accept=".csv" onchange={on_file} />
+                <IconButton label="Apply" onclick={on_apply} disabled={!can_apply}>{ save_icon() }</IconButton>
+                <IconButton label="Export" onclick={on_export}>{ csv_icon() }</IconButton>
+            </div>
+            if let Some(report) = &*report {
+                if report.applied {
+                    <p style="color: #388e3c; font-weight: bold;">{ "Import applied." }</p>
+                }
+                if !report.errors.is_empty() {
+                    <div class="alert">
+                        { for report.errors.iter().map(|e| html! { <div>{ format!("Row {}: {}", e.row, e.message) }</div> }) }
+                    </div>
+                }
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{ "Row" }</th>
+                            <th>{ "Change" }</th>
+                            <th>{ "Name" }</th>
+                            <th>{ "Email" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.changes.iter().map(|c| html! {
+                            <tr key={c.row}>
+                                <td>{ c.row }</td>
+                                <td>{ action_label(&c.action) }</td>
+                                <td>{ &c.name }</td>
+                                <td>{ &c.email }</td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
@@ -24,6 +24,8 @@
 
 use crate::components::buttons::IconButton;
 use crate::components::icons::{add_icon, cancel_icon, delete_icon, edit_icon, save_icon};
+use crate::components::tipper_import::TipperImportPanel;
+use crate::ViewContext;
 use gloo_net::http::Request;
 use kelpie_models::tipper::Tipper;
 use log::warn;
@@ -36,19 +38,25 @@ pub fn tipper_list() -> Html {
     let tippers = use_state(|| vec![]);
     let name_input = use_state(|| String::new());
     let email_input = use_state(|| String::new());
+    let password_input = use_state(|| String::new());
+    let admin_input = use_state(|| false);
 
        // New state for editing
     let editing_id = use_state(|| None as Option<i32>);
     let edit_name = use_state(|| String::new());
     let edit_email = use_state(|| String::new());
+    let edit_password = use_state(|| String::new());
+    let edit_admin = use_state(|| false);
 
     let error_msg = use_state(|| None::<String>);
+    let view_context = use_context::<ViewContext>().expect("ViewContext not found");
+    let reload = use_state(|| 0u32);
 
-    // Load tippers on mount
+    // Load tippers on mount, and again after an import
     {
         let tippers = tippers.clone();
         let error_msg = error_msg.clone();
-        use_effect_with((), move |_| {
+        use_effect_with(*reload, move |_| {
             // Clear error on load
             error_msg.set(None);
             wasm_bindgen_futures::spawn_local(async move {
@@ -83,12 +91,16 @@ pub fn tipper_list() -> Html {
     let add_tipper = {
         let name_input = name_input.clone();
         let email_input = email_input.clone();
+        let password_input = password_input.clone();
+        let admin_input = admin_input.clone();
         let tippers = tippers.clone();
         let error_msg = error_msg.clone();
 
         Callback::from(move |_e: MouseEvent| {
             let name = name_input.clone();
             let email = email_input.clone();
+            let password = password_input.clone();
+            let admin = admin_input.clone();
             let tippers = tippers.clone();
             let error_msg = error_msg.clone();
 
@@ -99,6 +111,8 @@ pub fn tipper_list() -> Html {
                 let payload = json!({
                     "name": (*name).clone(),
                     "email": (*email).clone(),
+                    "password": (*password).clone(),
+                    "is_admin": *admin,
                 });
 
                 match Request::post("/api/tippers")
@@ -116,6 +130,8 @@ pub fn tipper_list() -> Html {
                                             tippers.set(new_list);
                                             name.set(String::new());
                                             email.set(String::new());
+                                            password.set(String::new());
+                                            admin.set(false);
                                             error_msg.set(None); // Clear error on success
                                         }
                                         Err(e) => {
@@ -146,8 +162,12 @@ pub fn tipper_list() -> Html {
         let editing_id = editing_id.clone();
         let edit_name = edit_name.clone();
         let edit_email = edit_email.clone();
+        let edit_password = edit_password.clone();
+        let edit_admin = edit_admin.clone();
         Callback::from(move |tipper: Tipper| {
             editing_id.set(tipper.id);
+            edit_password.set(String::new());
+            edit_admin.set(tipper.is_admin);
             edit_name.set(tipper.name.clone());
             edit_email.set(tipper.email.clone());
         })
@@ -166,12 +186,16 @@ pub fn tipper_list() -> Html {
         let editing_id = editing_id.clone();
         let edit_name = edit_name.clone();
         let edit_email = edit_email.clone();
+        let edit_password = edit_password.clone();
+        let edit_admin = edit_admin.clone();
         let tippers = tippers.clone();
         let error_msg = error_msg.clone();
         Callback::from(move |_| {
             let id = editing_id.clone();
             let name = edit_name.clone();
             let email = edit_email.clone();
+            let password = edit_password.clone();
+            let admin = edit_admin.clone();
             let tippers = tippers.clone();
             let editing_id = editing_id.clone();
             let error_msg = error_msg.clone();
@@ -181,7 +205,8 @@ pub fn tipper_list() -> Html {
 
             wasm_bindgen_futures::spawn_local(async move {
                 if let Some(id) = *id {
-                    let payload = json!({"id": id, "name": (*name).clone(), "email": (*email).clone()});
+                    // A blank password leaves the tipper's existing password alone
+                    let payload = json!({"id": id, "name": (*name).clone(), "email": (*email).clone(), "password": (*password).clone(), "is_admin": *admin});
                     let url = "/api/tippers";
                     match Request::put(&url)
                         .header("Content-Type", "application/json")
@@ -260,6 +285,11 @@ pub fn tipper_list() -> Html {
         })
     };
 
+    let on_import = {
+        let reload = reload.clone();
+        Callback::from(move |_| reload.set(*reload + 1))
+    };
+
     html! {
         <div class="content">
             if let Some(msg) = &*error_msg {
@@ -272,6 +302,8 @@ pub fn tipper_list() -> Html {
                     <tr>
                         <th>{ "Name" }</th>
                         <th>{ "Email" }</th>
+                        <th>{ "Password" }</th>
+                        <th>{ "Admin" }</th>
                         <th>{ "Actions" }</th>
                     </tr>
                 </thead>
@@ -298,6 +330,26 @@ pub fn tipper_list() -> Html {
                                     })}
                                 />
                             </td>
+                            <td>
+                                <input
+                                    type="password"
+                                    placeholder="Password"
+                                    value={(*password_input).clone()}
+                                    oninput={Callback::from(move |e: InputEvent| {
+                                        password_input.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+                                    })}
+                                />
+                            </td>
+                            <td>
+                                <input
+                                    type="checkbox"
+                                    checked={*admin_input}
+                                    onchange={{
+                                        let admin_input = admin_input.clone();
+                                        Callback::from(move |_: Event| admin_input.set(!*admin_input))
+                                    }}
+                                />
+                            </td>
                             <td class="actions">
                             <IconButton onclick={add_tipper}>
                                 { add_icon() }
@@ -333,6 +385,29 @@ pub fn tipper_list() -> Html {
                                             }}
                                         />
                                     </td>
+                                    <td>
+                                        <input
+                                            type="password"
+                                            placeholder="Unchanged"
+                                            value={(*edit_password).clone()}
+                                            oninput={{
+                                                let edit_password = edit_password.clone();
+                                                Callback::from(move |e: InputEvent| {
+                                                    edit_password.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+                                                })
+                                            }}
+                                        />
+                                    </td>
+                                    <td>
+                                        <input
+                                            type="checkbox"
+                                            checked={*edit_admin}
+                                            onchange={{
+                                                let edit_admin = edit_admin.clone();
+                                                Callback::from(move |_: Event| edit_admin.set(!*edit_admin))
+                                            }}
+                                        />
+                                    </td>
                                     <td class="actions">
                                         <div class="button-row">
                                             <IconButton onclick={save_edit.clone()}>
@@ -364,6 +439,8 @@ pub fn tipper_list() -> Html {
                                 <tr key={tipper.id.unwrap_or(-1)}>
                                     <td>{ &tipper.name }</td>
                                     <td>{ &tipper.email }</td>
+                                    <td></td>
+                                    <td>{ if tipper.is_admin { "Yes" } else { "" } }</td>
                                     <td class="actions">
                                         <div class="button-row">
                                             <IconButton onclick={start_edit}>
@@ -381,6 +458,7 @@ pub fn tipper_list() -> Html {
                 </tbody>
             </table>
             </div>
+            <TipperImportPanel season_id={*view_context.season_id} on_import={on_import} />
         </div>
     }
 }
@@ -29,8 +29,9 @@ use kelpie_models::round::Round;
 use kelpie_models::team::Team;
 use kelpie_models::tip::Tip;
 use kelpie_models::tipper::Tipper;
+use kelpie_models::venue::Venue;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -43,6 +44,9 @@ pub struct TipsProps {
 pub struct RoundWithGames {
     pub round: Round,
     pub games: Vec<Game>,
+    /// Teams without a game this round
+    #[serde(default)]
+    pub byes: Vec<i32>,
 }
 
 // Helper function to load tips for a tipper and round
@@ -51,6 +55,7 @@ fn load_tips(
     round_id: i32,
     round: &Option<RoundWithGames>,
     game_tips: UseStateHandle<HashMap<i32, Option<i32>>>,
+    margin: UseStateHandle<Option<i32>>,
 ) {
     let round = round.clone();
     wasm_bindgen_futures::spawn_local(async move {
@@ -60,6 +65,10 @@ fn load_tips(
             Err(_) => vec![],
         };
 
+        // The margin is recorded against the first game of the round
+        let first_game = round.as_ref().and_then(|r| r.games.first()).and_then(|g| g.game_id);
+        margin.set(tips.iter().find(|t| Some(t.game_id) == first_game).and_then(|t| t.margin));
+
         let mut tips_map = HashMap::new();
         if tips.is_empty() {
             if let Some(r) = round {
@@ -80,14 +89,18 @@ fn load_tips(
 pub fn record_tips(props: &TipsProps) -> Html {
     let round = use_state(|| None::<RoundWithGames>);
     let teams = use_state(|| Vec::<Team>::new());
+    let venues = use_state(|| Vec::<Venue>::new());
     let game_tips = use_state(|| HashMap::<i32, Option<i32>>::new());
+    let margin = use_state(|| None::<i32>);
+    let locked_games = use_state(|| HashSet::<i32>::new());
     let tipper = use_state(|| None::<Tipper>);
     let save_status = use_state(|| None::<String>);
     let error_msg = use_state(|| None::<String>);
 
-    // Fetch teams once
+    // Fetch teams and venues once
     {
         let teams = teams.clone();
+        let venues = venues.clone();
         let error_msg = error_msg.clone();
         use_effect_with((), move |_| {
             // Clear error on load
@@ -101,6 +114,9 @@ pub fn record_tips(props: &TipsProps) -> Html {
                     }
                 };
                 teams.set(data);
+                if let Ok(resp) = Request::get("/api/venues").send().await {
+                    venues.set(resp.json::<Vec<Venue>>().await.ok().unwrap_or_default());
+                }
             });
             || ()
         });
@@ -112,22 +128,34 @@ pub fn record_tips(props: &TipsProps) -> Html {
         let round_id = props.round_id;
         let tipper_id = props.tipper_id;
         let game_tips = game_tips.clone();
+        let margin = margin.clone();
+        let locked_games = locked_games.clone();
         let error_msg = error_msg.clone();
         use_effect_with(round_id, move |&round_id| {
             // Clear error on round change
             error_msg.set(None);
             let round = round.clone();
             let game_tips = game_tips.clone();
+            let margin = margin.clone();
+            let locked_games = locked_games.clone();
             let error_msg = error_msg.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let new_round = match Request::get(&format!("api/rounds/{}", round_id)).send().await {
                     Ok(resp) => resp.json::<RoundWithGames>().await.ok(),
                     Err(e) => {
                         error_msg.set(Some(format!("Error loading round: {}", e)));
-                        Some(RoundWithGames { round: Round::default(), games: vec![] })
+                        Some(RoundWithGames { round: Round::default(), games: vec![], byes: vec![] })
                     }
                 };
-                load_tips(tipper_id, round_id, &new_round, game_tips.clone());
+                let locked = match Request::get(&format!("/api/tips/locked/round/{}", round_id)).send().await {
+                    Ok(resp) => resp.json::<Vec<i32>>().await.unwrap_or_default(),
+                    Err(e) => {
+                        error_msg.set(Some(format!("Error loading locked games: {}", e)));
+                        vec![]
+                    }
+                };
+                locked_games.set(locked.into_iter().collect());
+                load_tips(tipper_id, round_id, &new_round, game_tips.clone(), margin.clone());
                 round.set(new_round.clone());
             });
             || ()
@@ -141,6 +169,7 @@ pub fn record_tips(props: &TipsProps) -> Html {
         let round_id = props.round_id;
         let round = round.clone();
         let game_tips = game_tips.clone();
+        let margin = margin.clone();
         let error_msg = error_msg.clone();
         use_effect_with(tipper_id, move |&tipper_id| {
             // Clear error on tipper change
@@ -156,7 +185,7 @@ pub fn record_tips(props: &TipsProps) -> Html {
                     }
                 };
                 tipper.set(data);
-                load_tips(tipper_id, round_id, &round, game_tips.clone());
+                load_tips(tipper_id, round_id, &round, game_tips.clone(), margin.clone());
             });
             || ()
         });
@@ -176,13 +205,24 @@ pub fn record_tips(props: &TipsProps) -> Html {
         let round_id = props.round_id;
         let round = round.clone();
         let game_tips = game_tips.clone();
+        let margin = margin.clone();
         Callback::from(move |_| {
-            load_tips(tipper_id, round_id, &round, game_tips.clone());
+            load_tips(tipper_id, round_id, &round, game_tips.clone(), margin.clone());
+        })
+    };
+
+    let update_margin = {
+        let margin = margin.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+            margin.set(value.trim().parse::<i32>().ok().filter(|m| *m >= 0));
         })
     };
 
     let save_tips = {
         let game_tips = game_tips.clone();
+        let margin = margin.clone();
+        let locked_games = locked_games.clone();
         let round = round.clone();
         let tipper_id = props.tipper_id;
         let round_id = props.round_id;
@@ -191,8 +231,10 @@ pub fn record_tips(props: &TipsProps) -> Html {
         Callback::from(move |_| {
             // Clear error before save
             error_msg.set(None);
+            // Games that have started can't be changed, so only the open ones are checked and sent
+            let is_open = |g: &&Game| !locked_games.contains(&g.game_id.unwrap_or(-1));
             let all_tipped = if let Some(r) = &*round {
-                r.games.iter().all(|g| {
+                r.games.iter().filter(is_open).all(|g| {
                     let game_id = g.game_id.unwrap_or(-1);
                     game_tips.get(&game_id).and_then(|t| *t).is_some()
                 })
@@ -204,11 +246,13 @@ pub fn record_tips(props: &TipsProps) -> Html {
                 return;
             }
             let tips: Vec<Tip> = if let Some(r) = &*round {
-                r.games.iter().map(|g| {
+                let first_game = r.games.first().and_then(|g| g.game_id);
+                r.games.iter().filter(is_open).map(|g| {
                     Tip {
                         tipper_id,
                         game_id: g.game_id.unwrap_or(-1),
                         team_id: game_tips.get(&g.game_id.unwrap_or(-1)).and_then(|t| *t),
+                        margin: if g.game_id == first_game { *margin } else { None },
                     }
                 }).collect()
             } else {
@@ -245,6 +289,11 @@ pub fn record_tips(props: &TipsProps) -> Html {
         })
     };
 
+    let all_locked = match &*round {
+        Some(r) => r.games.iter().all(|g| locked_games.contains(&g.game_id.unwrap_or(-1))),
+        None => true,
+    };
+
     html! {
         <div>
             if let Some(msg) = &*error_msg {
@@ -266,7 +315,7 @@ pub fn record_tips(props: &TipsProps) -> Html {
                     }
                 </h4>
                <div style="margin-left: 7rem; display: flex; gap: 0.5rem;">
-                    <IconButton label="Save" onclick={save_tips.clone()}>{ save_icon() }</IconButton>
+                    <IconButton label="Save" onclick={save_tips.clone()} disabled={all_locked}>{ save_icon() }</IconButton>
                     <IconButton label="Reset" onclick={reset_tips.clone()}>{ reset_icon() }</IconButton>
                 </div>
                 if let Some(msg) = &*save_status {
@@ -281,24 +330,43 @@ pub fn record_tips(props: &TipsProps) -> Html {
                     Some(r) if r.games.is_empty() => html! { <p>{ "No games found for this round." }</p> },
                     Some(r) => html! {
                         <ul style="list-style: none; padding: 0;">
-                            { for r.games.iter().map(|game| {
+                            { for r.games.iter().enumerate().map(|(idx, game)| {
                                 let home = teams.iter().find(|t| t.id == Some(game.home_team_id)).cloned();
                                 let away = teams.iter().find(|t| t.id == Some(game.away_team_id)).cloned();
                                 let selected = game_tips.get(&game.game_id.unwrap_or(-1)).and_then(|t| *t);
                                 let radio_name = format!("tip-game-{}", game.game_id.unwrap_or(-1));
+                                let locked = locked_games.contains(&game.game_id.unwrap_or(-1));
+                                let venue = venues.iter().find(|v| game.venue_id.is_some() && v.id == game.venue_id);
                                 html! {
-                                    <li style="margin-bottom: 1rem;">
+                                    <li style="margin-bottom: 1rem; display: flex; align-items: center; gap: 1rem;">
+                                        <span style="width: 8rem;">{ game.kick_off.format("%a %e %b %H:%M").to_string() }</span>
                                         <TipSelector
                                             name={radio_name}
                                             home_team={home.clone().unwrap()}
                                             away_team={away.clone().unwrap()}
                                             selected_team_id={selected}
+                                            disabled={locked}
                                             on_change={Callback::from({
                                                 let update_tip = update_tip.clone();
                                                 let game_id = game.game_id.unwrap();
                                                 move |team_id| update_tip.emit((game_id, team_id))
                                             })}
                                         />
+                                        if let Some(venue) = venue {
+                                            <span class="venue">{ &venue.name }</span>
+                                        }
+                                        if idx == 0 {
+                                            <label style="display: flex; align-items: center; gap: 0.5rem;">
+                                                { "Margin" }
+                                                <input type="number" min="0" style="width: 5rem; margin: 0;"
+                                                    value={margin.map(|m| m.to_string()).unwrap_or_default()}
+                                                    disabled={locked}
+                                                    oninput={update_margin.clone()} />
+                                            </label>
+                                        }
+                                        if locked {
+                                            <span class="locked">{ "Locked" }</span>
+                                        }
                                     </li>
                                 }
                             })}
@@ -306,6 +374,18 @@ pub fn record_tips(props: &TipsProps) -> Html {
                     }
                 }
             }
+            if let Some(r) = &*round {
+                if !r.byes.is_empty() {
+                    <p class="byes">
+                        { "Bye: " }
+                        { r.byes.iter()
+                            .filter_map(|id| teams.iter().find(|t| t.id == Some(*id)))
+                            .map(|t| t.name.clone())
+                            .collect::<Vec<_>>()
+                            .join(", ") }
+                    </p>
+                }
+            }
         </div>
     }
 }
@@ -0,0 +1,259 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use crate::components::buttons::IconButton;
+use crate::components::icons::{add_icon, cancel_icon, delete_icon, edit_icon, save_icon};
+use gloo_net::http::Request;
+use kelpie_models::venue::Venue;
+use serde_json::json;
+use yew::prelude::*;
+
+#[function_component(VenueList)]
+pub fn venue_list() -> Html {
+    let venues = use_state(|| vec![]);
+    let name_input = use_state(|| String::new());
+    let editing_id = use_state(|| None as Option<i32>);
+    let edit_name = use_state(|| String::new());
+    let error_msg = use_state(|| None::<String>);
+
+    // Load venues
+    {
+        let venues = venues.clone();
+        let error_msg = error_msg.clone();
+        use_effect_with((), move |_| {
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::get("/api/venues").send().await {
+                    Ok(resp) if resp.ok() => match resp.json::<Vec<Venue>>().await {
+                        Ok(data) => venues.set(data),
+                        Err(e) => error_msg.set(Some(format!("Failed to parse venues: {}", e))),
+                    },
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Failed to load venues ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error loading venues: {}", e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    let add_venue = {
+        let name_input = name_input.clone();
+        let venues = venues.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |_e: MouseEvent| {
+            let name = name_input.clone();
+            let venues = venues.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let payload = json!({ "name": (*name).clone() });
+                let resp = Request::post("/api/venues")
+                    .header("Content-Type", "application/json")
+                    .body(payload.to_string())
+                    .unwrap()
+                    .send()
+                    .await;
+                match resp {
+                    Ok(resp) if resp.ok() => match resp.json::<Venue>().await {
+                        Ok(new_venue) => {
+                            let mut new_list = (*venues).clone();
+                            new_list.push(new_venue);
+                            new_list.sort_by(|a: &Venue, b: &Venue| a.name.cmp(&b.name));
+                            venues.set(new_list);
+                            name.set(String::new());
+                        }
+                        Err(e) => error_msg.set(Some(format!("Failed to parse venue: {}", e))),
+                    },
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Add failed ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error adding venue: {}", e))),
+                }
+            });
+        })
+    };
+
+    let save_edit = {
+        let editing_id = editing_id.clone();
+        let edit_name = edit_name.clone();
+        let venues = venues.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |_| {
+            let Some(id) = *editing_id else { return };
+            let name = (*edit_name).clone();
+            let venues = venues.clone();
+            let editing_id = editing_id.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                let payload = json!({ "id": id, "name": name });
+                let resp = Request::put("/api/venues")
+                    .header("Content-Type", "application/json")
+                    .body(payload.to_string())
+                    .unwrap()
+                    .send()
+                    .await;
+                match resp {
+                    Ok(resp) if resp.ok() => match resp.json::<Venue>().await {
+                        Ok(updated) => {
+                            let new_list: Vec<Venue> = (*venues)
+                                .iter()
+                                .map(|v| if v.id == Some(id) { Venue { can_delete: v.can_delete, ..updated.clone() } } else { v.clone() })
+                                .collect();
+                            venues.set(new_list);
+                            editing_id.set(None);
+                        }
+                        Err(e) => error_msg.set(Some(format!("Failed to parse venue: {}", e))),
+                    },
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Update failed ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error updating venue: {}", e))),
+                }
+            });
+        })
+    };
+
+    let delete_venue = {
+        let venues = venues.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |id: i32| {
+            let venues = venues.clone();
+            let error_msg = error_msg.clone();
+            error_msg.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match Request::delete(&format!("/api/venues/{}", id)).send().await {
+                    Ok(resp) if resp.ok() => {
+                        let new_list: Vec<Venue> = (*venues).iter().filter(|v| v.id != Some(id)).cloned().collect();
+                        venues.set(new_list);
+                    }
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let text = resp.text().await.unwrap_or_default();
+                        error_msg.set(Some(format!("Delete failed ({}): {}", status, text)));
+                    }
+                    Err(e) => error_msg.set(Some(format!("Error: {}", e))),
+                }
+            });
+        })
+    };
+
+    let on_input = |state: UseStateHandle<String>| {
+        Callback::from(move |e: InputEvent| {
+            state.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+        })
+    };
+
+    html! {
+        <div class="content">
+            if let Some(msg) = &*error_msg {
+                <div class="alert">{ msg }</div>
+            }
+            <h2>{ "Venues" }</h2>
+            <div class="scrollable-table" style="border-right: 1px solid #ccc;">
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ "Name" }</th>
+                        <th>{ "Actions" }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <tr>
+                        <td>
+                            <input value={(*name_input).clone()} oninput={on_input(name_input.clone())} />
+                        </td>
+                        <td class="actions">
+                            <IconButton onclick={add_venue}>
+                                { add_icon() }
+                            </IconButton>
+                        </td>
+                    </tr>
+                    { for (*venues).iter().map(|venue| {
+                        let id = venue.id.unwrap_or(-1);
+                        if venue.id.is_some() && venue.id == *editing_id {
+                            html! {
+                                <tr key={id}>
+                                    <td>
+                                        <input value={(*edit_name).clone()} oninput={on_input(edit_name.clone())} />
+                                    </td>
+                                    <td class="actions">
+                                        <div class="button-row">
+                                            <IconButton onclick={save_edit.clone()}>
+                                                { save_icon() }
+                                            </IconButton>
+                                            <IconButton onclick={{
+                                                let editing_id = editing_id.clone();
+                                                Callback::from(move |_| editing_id.set(None))
+                                            }}>
+                                                { cancel_icon() }
+                                            </IconButton>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        } else {
+                            let start_edit = {
+                                let editing_id = editing_id.clone();
+                                let edit_name = edit_name.clone();
+                                let venue = venue.clone();
+                                Callback::from(move |_| {
+                                    editing_id.set(venue.id);
+                                    edit_name.set(venue.name.clone());
+                                })
+                            };
+                            let delete = {
+                                let delete_venue = delete_venue.clone();
+                                Callback::from(move |_| delete_venue.emit(id))
+                            };
+                            html! {
+                                <tr key={id}>
+                                    <td>{ &venue.name }</td>
+                                    <td class="actions">
+                                        <div class="button-row">
+                                            <IconButton onclick={start_edit}>
+                                                { edit_icon() }
+                                            </IconButton>
+                                            <IconButton onclick={delete} disabled={!venue.can_delete.unwrap_or(false)}>
+                                                { delete_icon() }
+                                            </IconButton>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }
+                    })}
+                </tbody>
+            </table>
+            </div>
+        </div>
+    }
+}
@@ -26,28 +26,50 @@ mod components;
 
 use crate::components::edit_round::EditRound;
 use crate::components::buttons::IconButton;
-use crate::components::icons::{rounds_icon, teams_icon, tippers_icon, tips_icon, leaderboard_icon};
+use crate::components::icons::{logout_icon, rounds_icon, seasons_icon, teams_icon, tippers_icon, tips_icon, leaderboard_icon, matrix_icon, ladder_icon, results_icon, venues_icon};
+use crate::components::login::Login;
+use crate::components::reports::form_guide::FormGuideView;
+use crate::components::reports::ladder::Ladder;
 use crate::components::reports::leaderboard::Leaderboard;
+use crate::components::reports::matrix::Matrix;
+use crate::components::results::ResultsView;
 use crate::components::round_list::RoundList;
+use crate::components::season_list::SeasonList;
 use crate::components::team_list::TeamList;
 use crate::components::tip_view::TipView;
+use crate::components::venue_list::VenueList;
 use components::tipper_list::TipperList;
+use gloo_net::http::Request;
+use kelpie_models::round::Round;
+use kelpie_models::season::Season;
+use kelpie_models::tipper::Tipper;
 use yew::prelude::*;
 
 #[derive(PartialEq, Clone)]
 enum View {
     Teams,
+    Venues,
     Tippers,
     Rounds,
     RoundEdit{round_id: Option<i32>},
+    Results,
     Tips,
     Leaderboard,
+    Matrix,
+    Ladder,
+    FormGuide{tipper_id: i32},
+    Seasons,
 }
 
 #[derive(PartialEq, Clone)]
 pub(crate) struct ViewContext {
     view: UseStateHandle<View>,
     error_msg: UseStateHandle<Option<String>>,
+    /// The season selected in the nav, everything other than the season list is shown for this season
+    pub(crate) season_id: UseStateHandle<Option<i32>>,
+    pub(crate) seasons: UseStateHandle<Vec<Season>>,
+    /// The logged in tipper, the app only shows the login screen until this is set
+    pub(crate) tipper: UseStateHandle<Option<Tipper>>,
 }
 impl ViewContext {
     pub(crate) fn set_view(&self, view: View) {
@@ -60,10 +82,92 @@ impl ViewContext {
 fn app() -> Html {
     let view = use_state(|| View::Tips);
     let error_msg = use_state(|| None::<String>);
+    let season_id = use_state(|| None::<i32>);
+    let seasons = use_state(Vec::<Season>::new);
+    let tipper = use_state(|| None::<Tipper>);
+    let session_checked = use_state(|| false);
 
     let view_context = ViewContext {
         view: view.clone(),
         error_msg: error_msg.clone(),
+        season_id: season_id.clone(),
+        seasons: seasons.clone(),
+        tipper: tipper.clone(),
+    };
+
+    // Pick up an existing session
+    {
+        let tipper = tipper.clone();
+        let session_checked = session_checked.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(resp) = Request::get("/api/me").send().await {
+                    if resp.ok() {
+                        tipper.set(resp.json::<Tipper>().await.ok());
+                    }
+                }
+                session_checked.set(true);
+            });
+            || ()
+        });
+    }
+
+    // Once logged in load the seasons, starting in the season of the tipper's current round
+    {
+        let seasons = seasons.clone();
+        let season_id = season_id.clone();
+        let error_msg = error_msg.clone();
+        let tipper_id = tipper.as_ref().and_then(|t| t.id);
+        use_effect_with(tipper_id, move |tipper_id| {
+            if tipper_id.is_some() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Request::get("/api/seasons").send().await {
+                        Ok(resp) => match resp.json::<Vec<Season>>().await {
+                            Ok(data) => {
+                                let current = match Request::get("/api/rounds/current").send().await {
+                                    Ok(resp) if resp.ok() => resp.json::<Round>().await.ok().map(|r| r.season_id),
+                                    _ => None,
+                                };
+                                season_id.set(current.or_else(|| data.last().and_then(|s| s.id)));
+                                seasons.set(data);
+                            }
+                            Err(e) => error_msg.set(Some(format!("Failed to parse seasons: {}", e))),
+                        },
+                        Err(e) => error_msg.set(Some(format!("Error loading seasons: {}", e))),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_login = {
+        let tipper = tipper.clone();
+        let view_context = view_context.clone();
+        Callback::from(move |t: Tipper| {
+            tipper.set(Some(t));
+            view_context.set_view(View::Tips);
+        })
+    };
+
+    let on_logout = {
+        let tipper = tipper.clone();
+        Callback::from(move |_: MouseEvent| {
+            let tipper = tipper.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = Request::post("/api/logout").send().await;
+                tipper.set(None);
+            });
+        })
+    };
+
+    let on_season_select = {
+        let season_id = season_id.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                season_id.set(select.value().parse::<i32>().ok());
+            }
+        })
     };
 
     // Single set_view callback
@@ -77,33 +181,83 @@ fn app() -> Html {
         Callback::from(move |msg: Option<String>| error_msg.set(msg))
     };
 
+    let header = html! {
+        <h1 style="display: flex; align-items: center;">
+            <img src="images/kelpiedog_120x120_transparent.png" alt="Kelpie Logo" style="margin-right: 12px;"/>
+            <span>{ "Kelpie Footy Tipping" }<span style="font-size:1rem;"><br/>{"by Shartrec"}</span></span>
+
+        </h1>
+    };
+
+    let is_admin = tipper.as_ref().is_some_and(|t| t.is_admin);
+
+    if !*session_checked {
+        return html! {};
+    }
+    if tipper.is_none() {
+        return html! {
+            <main class="content">
+                { header }
+                <Login on_login={on_login} />
+            </main>
+        };
+    }
+
     html! {
         <ContextProvider<ViewContext> context={view_context}>
 
             <div class="page-container" style="display: flex;">
                 <nav style="width: 9rem; background: #f0f0f0; padding: 16px 0;">
+                    <select id="season-select" onchange={on_season_select} style="width: 8rem;">
+                        if seasons.is_empty() {
+                            <option value="" selected=true disabled=true>{ "No seasons" }</option>
+                        }
+                        { for seasons.iter().map(|season| html! {
+                            <option value={season.id.unwrap_or(-1).to_string()} selected={season.id == *season_id}>
+                                { &season.name }
+                            </option>
+                        })}
+                    </select>
                     <IconButton label="Tips" onclick={set_view.reform(|_| View::Tips)}>
                         { tips_icon() }
                     </IconButton>
-                    <IconButton label="Teams" onclick={set_view.reform(|_| View::Teams)}>
-                        { teams_icon() }
+                    if is_admin {
+                        <IconButton label="Teams" onclick={set_view.reform(|_| View::Teams)}>
+                            { teams_icon() }
+                        </IconButton>
+                        <IconButton label="Venues" onclick={set_view.reform(|_| View::Venues)}>
+                            { venues_icon() }
+                        </IconButton>
+                        <IconButton label="Tippers" onclick={set_view.reform(|_| View::Tippers)}>
+                            { tippers_icon() }
+                        </IconButton>
+                        <IconButton label="Rounds" onclick={set_view.reform(|_| View::Rounds)}>
+                            { rounds_icon() }
+                        </IconButton>
+                        <IconButton label="Results" onclick={set_view.reform(|_| View::Results)}>
+                            { results_icon() }
+                        </IconButton>
+                    }
+                    <IconButton label="Leaderboard" onclick={set_view.reform(|_| View::Leaderboard)}>
+                        { leaderboard_icon() }
                     </IconButton>
-                    <IconButton label="Tippers" onclick={set_view.reform(|_| View::Tippers)}>
-                        { tippers_icon() }
+                    <IconButton label="Matrix" onclick={set_view.reform(|_| View::Matrix)}>
+                        { matrix_icon() }
                     </IconButton>
-                    <IconButton label="Rounds" onclick={set_view.reform(|_| View::Rounds)}>
-                        { rounds_icon() }
+                    <IconButton label="Ladder" onclick={set_view.reform(|_| View::Ladder)}>
+                        { ladder_icon() }
                     </IconButton>
-                    <IconButton label="Leaderboard" onclick={set_view.reform(|_| View::Leaderboard)}>
-                        { leaderboard_icon() }
+                    if is_admin {
+                        <IconButton label="Seasons" onclick={set_view.reform(|_| View::Seasons)}>
+                            { seasons_icon() }
+                        </IconButton>
+                    }
+                    <IconButton label="Log out" onclick={on_logout}>
+                        { logout_icon() }
                     </IconButton>
                 </nav>
                 <main class="content" style="flex: 1; display: flex; flex-direction: column; overflow: hidden; min-height: 0;">
-                    <h1 style="display: flex; align-items: center;">
-                        <img src="images/kelpiedog_120x120_transparent.png" alt="Kelpie Logo" style="margin-right: 12px;"/>
-                        <span>{ "Kelpie Footy Tipping" }<span style="font-size:1rem;"><br/>{"by Shartrec"}</span></span>
-
-                    </h1>
+                    { header }
                     {
                         if let Some(msg) = &*error_msg {
                             html! { <div style="color: red;">{ msg }</div> }
@@ -114,12 +268,20 @@ fn app() -> Html {
 
                     {
                         match *view {
+                            // Only admins can manage the competition
+                            View::Teams | View::Venues | View::Tippers | View::Rounds | View::RoundEdit{..} | View::Results | View::Seasons if !is_admin => html! { <TipView /> },
                             View::Tips => html! { <TipView /> },
                             View::Teams => html! { <TeamList set_error_msg={set_error_msg.clone()}/> },
+                            View::Venues => html! { <VenueList /> },
                             View::Tippers => html! { <TipperList /> },
                             View::Rounds => html! { <RoundList /> },
                             View::RoundEdit{round_id} => html! { <EditRound set_error_msg={set_error_msg.clone()} round_id={round_id}/> },
+                            View::Results => html! { <ResultsView /> },
                             View::Leaderboard => html! { <Leaderboard /> },
+                            View::Matrix => html! { <Matrix /> },
+                            View::Ladder => html! { <Ladder /> },
+                            View::FormGuide{tipper_id} => html! { <FormGuideView tipper_id={tipper_id} /> },
+                            View::Seasons => html! { <SeasonList /> },
                         }
                     }
                 </main>
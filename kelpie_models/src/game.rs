@@ -21,7 +21,7 @@
  *      Trevor Campbell
  *
  */
-use chrono::NaiveDate;
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -30,7 +30,10 @@ pub struct Game {
     pub round_id: Option<i32>,
     pub home_team_id: i32,
     pub away_team_id: i32,
-    pub game_date: NaiveDate,
+    /// Kick-off in the competition's local time zone
+    pub kick_off: DateTime<FixedOffset>,
+    #[serde(default)]
+    pub venue_id: Option<i32>,
     pub home_team_score: Option<i32>,
     pub away_team_score: Option<i32>,
 }
@@ -1,5 +1,8 @@
 pub mod game;
 pub mod round;
+pub mod scoring;
+pub mod season;
 pub mod team;
 pub mod tip;
-pub mod tipper;
\ No newline at end of file
+pub mod tipper;
+pub mod venue;
\ No newline at end of file
@@ -27,6 +27,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Round {
     pub round_id: Option<i32>,
+    pub season_id: i32,
     pub round_number: i32,
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
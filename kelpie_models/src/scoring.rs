@@ -0,0 +1,81 @@
+/*
+ * Copyright (c) 2025. Trevor Campbell and others.
+ *
+ * This file is part of KelpieRustWeb.
+ *
+ * KelpieRustWeb is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License,or
+ * (at your option) any later version.
+ *
+ * KelpieRustWeb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with KelpieRustWeb; if not, write to the Free Software
+ * Foundation, Inc., 59 Temple Place, Suite 330, Boston, MA  02111-1307  USA
+ *
+ * Contributors:
+ *      Trevor Campbell
+ *
+ */
+use serde::{Deserialize, Serialize};
+
+/// How tips are scored. Each season has its own rules, anything not set takes the default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringRules {
+    /// Points for tipping the winner
+    pub correct_points: i64,
+    /// Points for either team when a game is drawn, the same as a correct tip when not set.
+    /// For half points on a draw use 2 correct points and 1 draw point.
+    pub draw_points: Option<i64>,
+    /// How games a tipper didn't tip are treated
+    pub untipped: Untipped,
+    /// Whether tipping every game of a round correctly earns the round's bonus points
+    pub perfect_round_bonus: bool,
+    /// Extra points for tipping a winner fewer than half the tippers picked
+    pub upset_points: i64,
+    /// Extra points when the tipped team wins by at least a margin, only the largest margin reached counts
+    pub margin_points: Vec<MarginPoints>,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        ScoringRules {
+            correct_points: 1,
+            draw_points: None,
+            untipped: Untipped::Ignore,
+            perfect_round_bonus: true,
+            upset_points: 0,
+            margin_points: vec![],
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Points for a tip on a drawn game.
+    pub fn draw(&self) -> i64 {
+        self.draw_points.unwrap_or(self.correct_points)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Untipped {
+    /// No points, and the game doesn't count towards a perfect round
+    #[default]
+    Ignore,
+    /// No points, and the tipper can't have a perfect round
+    Miss,
+    /// Treated as a tip for the home team
+    HomeTeam,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarginPoints {
+    pub min_margin: i32,
+    pub points: i64,
+}
@@ -28,6 +28,9 @@ pub struct Team {
     pub id: Option<i32>,
     pub name: String,
     pub nickname: String,
+    /// The ground the team plays its home games at
+    #[serde(default)]
+    pub home_venue_id: Option<i32>,
     pub can_delete: Option<bool>,
 }
 
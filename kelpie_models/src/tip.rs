@@ -28,4 +28,7 @@ pub struct Tip {
     pub tipper_id: i32,
     pub game_id: i32,
     pub team_id: Option<i32>,
+    /// Predicted winning margin of the tipped team, only given for the first game of a round
+    #[serde(default)]
+    pub margin: Option<i32>,
 }
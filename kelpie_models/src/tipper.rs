@@ -23,9 +23,15 @@
  */
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tipper {
     pub id: Option<i32>,
     pub name: String,
     pub email: String,
+    /// Only ever sent to the server to set a new password, it is never returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Admins can manage seasons, teams, tippers and rounds
+    #[serde(default)]
+    pub is_admin: bool,
 }